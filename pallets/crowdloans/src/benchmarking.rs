@@ -6,12 +6,17 @@ use super::{types::*, *};
 use crate::Pallet as Crowdloans;
 
 use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
-use frame_support::{assert_ok, pallet_prelude::*, traits::fungibles::Mutate};
+use frame_support::{
+    assert_ok,
+    pallet_prelude::*,
+    traits::{fungibles::Mutate, Currency},
+};
 use frame_system::{self, RawOrigin as SystemOrigin};
 use pallet_traits::ump::{XcmCall, XcmWeightFeeMisc};
-use primitives::{Balance, CurrencyId, ParaId};
-use sp_runtime::traits::{One, StaticLookup};
+use primitives::{AccountId, Balance, CurrencyId, Hash, ParaId, PersistedValidationData};
+use sp_runtime::traits::{BlakeTwo256, Bounded, One, StaticLookup};
 use sp_std::prelude::*;
+use sp_trie::{LayoutV1, MemoryDB, TrieDBMutBuilder, TrieMut};
 use xcm::latest::prelude::*;
 
 const XCM_WEIGHT_FEE: XcmWeightFeeMisc<Weight, Balance> = XcmWeightFeeMisc {
@@ -83,6 +88,21 @@ fn initial_set_up<
     .unwrap();
 }
 
+fn relay_storage_proof(entries: &[(Vec<u8>, Vec<u8>)]) -> (Hash, Vec<Vec<u8>>) {
+    let mut db = MemoryDB::<BlakeTwo256>::default();
+    let mut root = Default::default();
+    {
+        let mut trie = TrieDBMutBuilder::<LayoutV1<BlakeTwo256>>::new(&mut db, &mut root).build();
+        for (key, value) in entries {
+            trie.insert(key, value).unwrap();
+        }
+    }
+    (
+        root,
+        db.drain().into_iter().map(|(_, (node, _))| node).collect(),
+    )
+}
+
 benchmarks! {
     where_clause {
         where
@@ -511,6 +531,106 @@ benchmarks! {
     verify {
         assert_last_event::<T>(Event::UserRefunded(crowdloan, (LEASE_START, LEASE_END), caller, ChildStorageKind::Pending, CONTRIBUTE_AMOUNT).into())
     }
+
+    update_ctoken_metadata {
+        let ctoken = 19;
+        let caller: T::AccountId = whitelisted_caller();
+        let crowdloan = ParaId::from(1345u32);
+
+        initial_set_up::<T>(caller.clone(), ctoken);
+        // ctoken owner holds the metadata deposit
+        <T as pallet_assets::Config>::Currency::make_free_balance_be(&caller, Bounded::max_value());
+        assert_ok!(Crowdloans::<T>::create_vault(SystemOrigin::Root.into(), crowdloan, ctoken, LEASE_START, LEASE_END, ContributionStrategy::XCM, LARGE_CAP, END_BLOCK.into()));
+    }: _(
+        SystemOrigin::Root,
+        crowdloan,
+        LEASE_START,
+        LEASE_END,
+        b"cDOT-1345".to_vec(),
+        b"CDOT1345".to_vec(),
+        12
+    )
+    verify {
+        assert_last_event::<T>(Event::CTokenMetadataUpdated(crowdloan, (LEASE_START, LEASE_END), ctoken).into())
+    }
+
+    reclaim_pending {
+        let ctoken = 20;
+        let caller: T::AccountId = whitelisted_caller();
+        let crowdloan = ParaId::from(1346u32);
+
+        initial_set_up::<T>(caller.clone(), ctoken);
+        assert_ok!(Crowdloans::<T>::create_vault(SystemOrigin::Root.into(), crowdloan, ctoken, LEASE_START, LEASE_END, ContributionStrategy::XCM, LARGE_CAP, END_BLOCK.into()));
+        // contribution stays pending since the vault is not opened yet
+        assert_ok!(Crowdloans::<T>::contribute(SystemOrigin::Signed(caller.clone()).into(), crowdloan, CONTRIBUTE_AMOUNT, Vec::new()));
+        assert_ok!(Crowdloans::<T>::open(SystemOrigin::Root.into(), crowdloan));
+        assert_ok!(Crowdloans::<T>::close(SystemOrigin::Root.into(), crowdloan));
+        assert_ok!(Crowdloans::<T>::auction_succeeded(SystemOrigin::Root.into(), crowdloan));
+        assert_ok!(Crowdloans::<T>::slot_expired(SystemOrigin::Root.into(), crowdloan));
+        assert_ok!(Crowdloans::<T>::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        ));
+    }: _(
+        SystemOrigin::Signed(caller.clone()),
+        T::Lookup::unlookup(caller.clone()),
+        crowdloan,
+        LEASE_START,
+        LEASE_END
+    )
+    verify {
+        assert_last_event::<T>(Event::VaultPendingReclaimed(crowdloan, (LEASE_START, LEASE_END), caller, CONTRIBUTE_AMOUNT).into())
+    }
+
+    set_vault_contributed {
+        let ctoken = 21;
+        let caller: T::AccountId = whitelisted_caller();
+        let crowdloan = ParaId::from(1347u32);
+        let fund_index = 3;
+
+        initial_set_up::<T>(caller.clone(), ctoken);
+        assert_ok!(Crowdloans::<T>::create_vault(SystemOrigin::Root.into(), crowdloan, ctoken, LEASE_START, LEASE_END, ContributionStrategy::XCM, LARGE_CAP, END_BLOCK.into()));
+
+        // relaychain fund of the crowdloan and its contributions child trie
+        let fund = RelayFundInfo {
+            depositor: AccountId::from([0u8; 32]),
+            verifier: None,
+            deposit: 0,
+            raised: CONTRIBUTE_AMOUNT,
+            end: END_BLOCK,
+            cap: LARGE_CAP,
+            last_contribution: RelayLastContribution::Never,
+            first_period: LEASE_START,
+            last_period: LEASE_END,
+            fund_index,
+        };
+        let (child_root, mut proof) = relay_storage_proof(&[(
+            Crowdloans::<T>::para_account_id().encode(),
+            (CONTRIBUTE_AMOUNT, Vec::<u8>::new()).encode(),
+        )]);
+        let (root, fund_proof) = relay_storage_proof(&[
+            (Crowdloans::<T>::get_relay_fund_key(crowdloan), fund.encode()),
+            (Crowdloans::<T>::get_relay_fund_child_key(fund_index), child_root.encode()),
+        ]);
+        proof.extend(fund_proof);
+        ValidationData::<T>::put(PersistedValidationData {
+            parent_head: Default::default(),
+            relay_parent_number: Default::default(),
+            relay_parent_storage_root: root,
+            max_pov_size: Default::default(),
+        });
+    }: _(
+        SystemOrigin::Signed(caller),
+        crowdloan,
+        LEASE_START,
+        LEASE_END,
+        CONTRIBUTE_AMOUNT,
+        proof
+    )
+    verify {
+        assert_last_event::<T>(Event::VaultContributedSet(crowdloan, (LEASE_START, LEASE_END), CONTRIBUTE_AMOUNT).into())
+    }
 }
 
 impl_benchmark_test_suite!(Crowdloans, crate::mock::new_test_ext(), crate::mock::Test,);
@@ -43,12 +43,15 @@ pub mod pallet {
         log,
         pallet_prelude::*,
         require_transactional,
-        storage::{child, ChildTriePrefixIterator},
+        storage::{child, storage_prefix, ChildTriePrefixIterator},
         traits::{
-            fungibles::{Inspect, Mutate, Transfer},
+            fungibles::{
+                metadata::Mutate as MetadataMutate, roles::Inspect as RolesInspect, Inspect,
+                Mutate, Transfer,
+            },
             Get, SortedMembers,
         },
-        transactional, Blake2_128Concat, PalletId,
+        transactional, Blake2_128Concat, PalletId, StorageHasher, Twox64Concat,
     };
     use frame_system::{
         ensure_signed,
@@ -56,19 +59,22 @@ pub mod pallet {
     };
     use pallet_xcm::ensure_response;
     use primitives::{
-        ArithmeticKind, Balance, CurrencyId, LeasePeriod, ParaId, Rate, TrieIndex, VaultId,
+        ArithmeticKind, Balance, CurrencyId, LeasePeriod, ParaId, PersistedValidationData, Rate,
+        TrieIndex, VaultId,
     };
     use sp_runtime::{
         traits::{
-            AccountIdConversion, BlockNumberProvider, Hash, One, Saturating, StaticLookup, Zero,
+            AccountIdConversion, BlakeTwo256, BlockNumberProvider, Hash, One, Saturating,
+            StaticLookup, Zero,
         },
         ArithmeticError, DispatchError, FixedPointNumber, SaturatedConversion,
     };
-    use sp_std::{boxed::Box, cmp::Ordering, vec::Vec};
+    use sp_std::{boxed::Box, cmp::Ordering, collections::btree_set::BTreeSet, vec::Vec};
+    use sp_trie::StorageProof;
     use xcm::latest::prelude::*;
 
     use pallet_traits::{
-        DecimalProvider, Loans, Streaming, VaultTokenCurrenciesFilter,
+        DecimalProvider, Loans, Streaming, ValidationDataProvider, VaultTokenCurrenciesFilter,
         VaultTokenExchangeRateProvider,
     };
 
@@ -108,7 +114,9 @@ pub mod pallet {
         /// Assets for deposit/withdraw assets to/from crowdloan account
         type Assets: Transfer<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
             + Inspect<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
-            + Mutate<Self::AccountId, AssetId = CurrencyId, Balance = Balance>;
+            + Mutate<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
+            + MetadataMutate<Self::AccountId>
+            + RolesInspect<Self::AccountId>;
 
         type RuntimeOrigin: IsType<<Self as frame_system::Config>::RuntimeOrigin>
             + Into<Result<pallet_xcm::Origin, <Self as Config>::RuntimeOrigin>>;
@@ -127,7 +135,8 @@ pub mod pallet {
         #[pallet::constant]
         type PalletId: Get<PalletId>;
 
-        /// Minimum contribute amount
+        /// Minimum contribute amount, dust contributions cost more in xcm fees
+        /// and contributor storage than they are worth
         #[pallet::constant]
         type MinContribution: Get<BalanceOf<Self>>;
 
@@ -191,6 +200,9 @@ pub mod pallet {
         /// The relay's BlockNumber provider
         type RelayChainBlockNumberProvider: BlockNumberProvider<BlockNumber = BlockNumberFor<Self>>;
 
+        /// The relay's validation data provider
+        type RelayChainValidationDataProvider: ValidationDataProvider;
+
         /// To expose XCM helper functions
         type XCM: XcmHelper<Self, BalanceOf<Self>, Self::AccountId>;
 
@@ -302,6 +314,15 @@ pub mod pallet {
         ProxyUpdated(T::AccountId),
         /// Update leases bonus
         LeasesBonusUpdated(VaultId, BonusConfig<BalanceOf<T>>),
+        /// CToken metadata of a vault was updated
+        /// [para_id, vault_id, ctoken]
+        CTokenMetadataUpdated(ParaId, VaultId, AssetIdOf<T>),
+        /// A user reclaimed pending contribution from an expired vault
+        /// [para_id, vault_id, account, amount]
+        VaultPendingReclaimed(ParaId, VaultId, T::AccountId, BalanceOf<T>),
+        /// Vault's contributed amount was set from relaychain storage proof
+        /// [para_id, vault_id, contributed]
+        VaultContributedSet(ParaId, VaultId, BalanceOf<T>),
     }
 
     #[pallet::error]
@@ -344,6 +365,12 @@ pub mod pallet {
         EmptyProxyAddress,
         /// BonusConfig is wrong
         WrongBonusConfig,
+        /// Contribution is below `MinContribution`
+        ContributionTooSmall,
+        /// Invalid relaychain storage proof
+        InvalidProof,
+        /// Vault still has contributions waiting for relaychain confirmation
+        FlyingContributionsExist,
     }
 
     #[pallet::storage]
@@ -408,6 +435,24 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// ValidationData of previous block
+    ///
+    /// This is needed since validation data from cumulus_pallet_parachain_system
+    /// will be updated in set_validation_data Inherent which happens before external
+    /// extrinsics
+    #[pallet::storage]
+    #[pallet::getter(fn validation_data)]
+    pub type ValidationData<T: Config> = StorageValue<_, PersistedValidationData, OptionQuery>;
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_finalize(_n: T::BlockNumber) {
+            if let Some(data) = T::RelayChainValidationDataProvider::validation_data() {
+                ValidationData::<T>::put(data);
+            }
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Create a new vault via a governance decision
@@ -611,7 +656,7 @@ pub mod pallet {
 
             ensure!(
                 amount >= T::MinContribution::get(),
-                Error::<T>::InsufficientContribution
+                Error::<T>::ContributionTooSmall
             );
 
             ensure!(!Self::is_vrf(), Error::<T>::VrfDelayInProgress);
@@ -1221,6 +1266,117 @@ pub mod pallet {
             T::Loans::do_mint(&who, T::RelayCurrency::get(), amount)?;
             Ok(())
         }
+
+        /// Fix the metadata of a vault's ctoken before it's distributed,
+        /// the metadata deposit is held by the ctoken's owner
+        #[pallet::call_index(24)]
+        #[pallet::weight(<T as Config>::WeightInfo::update_ctoken_metadata())]
+        #[transactional]
+        pub fn update_ctoken_metadata(
+            origin: OriginFor<T>,
+            crowdloan: ParaId,
+            lease_start: LeasePeriod,
+            lease_end: LeasePeriod,
+            name: Vec<u8>,
+            symbol: Vec<u8>,
+            decimals: u8,
+        ) -> DispatchResult {
+            ensure_origin!(UpdateOrigin, origin)?;
+
+            let vault = Self::vaults((&crowdloan, &lease_start, &lease_end))
+                .ok_or(Error::<T>::VaultDoesNotExist)?;
+            ensure!(
+                vault.phase == VaultPhase::Pending
+                    || vault.phase == VaultPhase::Contributing
+                    || vault.phase == VaultPhase::Closed,
+                Error::<T>::IncorrectVaultPhase
+            );
+
+            let owner = T::Assets::owner(vault.ctoken).ok_or(Error::<T>::CTokenDoesNotExist)?;
+            T::Assets::set(vault.ctoken, &owner, name, symbol, decimals)?;
+
+            Self::deposit_event(Event::<T>::CTokenMetadataUpdated(
+                crowdloan,
+                (lease_start, lease_end),
+                vault.ctoken,
+            ));
+            Ok(())
+        }
+
+        /// If a `crowdloan` expired, reclaim the contribution which stayed
+        /// pending and was never sent to relaychain
+        #[pallet::call_index(25)]
+        #[pallet::weight(<T as Config>::WeightInfo::reclaim_pending())]
+        #[transactional]
+        pub fn reclaim_pending(
+            origin: OriginFor<T>,
+            dest: <T::Lookup as StaticLookup>::Source,
+            crowdloan: ParaId,
+            lease_start: LeasePeriod,
+            lease_end: LeasePeriod,
+        ) -> DispatchResult {
+            let _ = ensure_signed(origin)?;
+            let who = T::Lookup::lookup(dest)?;
+            Self::do_reclaim_pending(who, crowdloan, lease_start, lease_end)
+        }
+
+        /// Set the contributed amount of a vault by providing relaychain
+        /// storage proof of the crowdloan fund and its contribution
+        #[pallet::call_index(26)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_vault_contributed())]
+        #[transactional]
+        pub fn set_vault_contributed(
+            origin: OriginFor<T>,
+            crowdloan: ParaId,
+            lease_start: LeasePeriod,
+            lease_end: LeasePeriod,
+            #[pallet::compact] amount: BalanceOf<T>,
+            proof: Vec<Vec<u8>>,
+        ) -> DispatchResult {
+            let _ = ensure_signed(origin)?;
+
+            let mut vault = Self::vaults((&crowdloan, &lease_start, &lease_end))
+                .ok_or(Error::<T>::VaultDoesNotExist)?;
+            ensure!(vault.flying.is_zero(), Error::<T>::FlyingContributionsExist);
+
+            let contributor = match vault.contribution_strategy {
+                ContributionStrategy::XCM => Self::para_account_id(),
+                ContributionStrategy::XCMPROXY => {
+                    Self::proxy_address().ok_or(Error::<T>::EmptyProxyAddress)?
+                }
+            };
+            ensure!(
+                Self::verify_contribution_proof(
+                    crowdloan,
+                    (lease_start, lease_end),
+                    &contributor,
+                    amount,
+                    proof
+                ),
+                Error::<T>::InvalidProof
+            );
+
+            log::trace!(
+                target: "crowdloans::set_vault_contributed",
+                "para_id: {:?}, lease_start: {:?}, lease_end: {:?}, pre_contributed: {:?}, contributed: {:?}",
+                &crowdloan,
+                &lease_start,
+                &lease_end,
+                &vault.contributed,
+                &amount,
+            );
+
+            vault.contributed = amount;
+            Vaults::<T>::insert((&crowdloan, &lease_start, &lease_end), vault);
+
+            Self::deposit_event(Event::<T>::VaultContributedSet(
+                crowdloan,
+                (lease_start, lease_end),
+                amount,
+            ));
+
+            Ok(())
+        }
     }
 
     impl<T: Config> Pallet<T> {
@@ -1234,6 +1390,35 @@ pub mod pallet {
             T::SelfParaId::get().into_account_truncating()
         }
 
+        /// Summary of the current vault of a crowdloan, `None` if it has no vault
+        pub fn vault_summary(
+            crowdloan: ParaId,
+        ) -> Option<VaultSummary<BalanceOf<T>, BlockNumberFor<T>>> {
+            let vault = Self::current_vault(crowdloan)?;
+            let contributors = [
+                ChildStorageKind::Pending,
+                ChildStorageKind::Flying,
+                ChildStorageKind::Contributed,
+            ]
+            .into_iter()
+            .flat_map(|kind| Self::contribution_iterator(vault.trie_index, kind))
+            .map(|(who, _)| who)
+            .collect::<BTreeSet<_>>();
+
+            Some(VaultSummary {
+                phase: vault.phase,
+                total_contributed: vault
+                    .contributed
+                    .saturating_add(vault.flying)
+                    .saturating_add(vault.pending),
+                contributors: contributors.len() as u32,
+                cap: vault.cap,
+                end_block: vault.end_block,
+                lease_start: vault.lease_start,
+                lease_end: vault.lease_end,
+            })
+        }
+
         pub(crate) fn current_vault(crowdloan: ParaId) -> Option<Vault<T>> {
             Self::current_lease(crowdloan).and_then(|(lease_start, lease_end)| {
                 Self::vaults((&crowdloan, &lease_start, &lease_end))
@@ -1856,6 +2041,130 @@ pub mod pallet {
             Ok(())
         }
 
+        #[require_transactional]
+        fn do_reclaim_pending(
+            who: T::AccountId,
+            crowdloan: ParaId,
+            lease_start: LeasePeriod,
+            lease_end: LeasePeriod,
+        ) -> DispatchResult {
+            let mut vault = Self::vaults((&crowdloan, &lease_start, &lease_end))
+                .ok_or(Error::<T>::VaultDoesNotExist)?;
+
+            ensure!(
+                vault.phase == VaultPhase::Expired,
+                Error::<T>::IncorrectVaultPhase
+            );
+            let (amount, _) =
+                Self::contribution_get(vault.trie_index, &who, ChildStorageKind::Pending);
+            ensure!(!amount.is_zero(), Error::<T>::NoContributions);
+
+            log::trace!(
+                target: "crowdloans::reclaim_pending",
+                "who: {:?}, amount: {:?}, para_id: {:?}, lease_start: {:?}, lease_end: {:?}",
+                &who,
+                &amount,
+                &crowdloan,
+                &lease_start,
+                &lease_end
+            );
+
+            // pending contribution never left the pallet account and
+            // no ctoken was minted for it, refunding clears it so it
+            // can't be reclaimed twice
+            Self::do_refund_for(&who, &mut vault, ChildStorageKind::Pending, amount)?;
+
+            Vaults::<T>::insert((&crowdloan, &lease_start, &lease_end), vault);
+
+            Self::deposit_event(Event::<T>::VaultPendingReclaimed(
+                crowdloan,
+                (lease_start, lease_end),
+                who,
+                amount,
+            ));
+
+            Ok(())
+        }
+
+        /// Check `proof` against the relaychain storage root of previous block,
+        /// `contributor` must have contributed `amount` to the fund of `crowdloan`
+        /// covering `vault_id`
+        pub(crate) fn verify_contribution_proof(
+            crowdloan: ParaId,
+            (lease_start, lease_end): VaultId,
+            contributor: &T::AccountId,
+            amount: BalanceOf<T>,
+            proof: Vec<Vec<u8>>,
+        ) -> bool {
+            let validation_data = Self::validation_data();
+            if validation_data.is_none() {
+                return false;
+            }
+            let PersistedValidationData {
+                relay_parent_number,
+                relay_parent_storage_root,
+                ..
+            } = validation_data.expect("Could not be none, qed;");
+            log::trace!(
+                target: "crowdloans::verify_contribution_proof",
+                "relay_parent_number: {:?}, relay_parent_storage_root: {:?}",
+                &relay_parent_number, &relay_parent_storage_root,
+            );
+            let db = StorageProof::new(proof).into_memory_db();
+            let read = |root, key: &[u8]| {
+                sp_trie::read_trie_value::<sp_trie::LayoutV1<BlakeTwo256>, _>(
+                    &db, root, key, None, None,
+                )
+                .ok()
+                .flatten()
+            };
+
+            let fund = read(
+                &relay_parent_storage_root,
+                &Self::get_relay_fund_key(crowdloan),
+            )
+            .and_then(|v| RelayFundInfo::decode(&mut &v[..]).ok());
+            let fund_index = match fund {
+                Some(fund) if fund.first_period == lease_start && fund.last_period == lease_end => {
+                    fund.fund_index
+                }
+                _ => return false,
+            };
+
+            let child_root = read(
+                &relay_parent_storage_root,
+                &Self::get_relay_fund_child_key(fund_index),
+            )
+            .and_then(|v| <BlakeTwo256 as Hash>::Output::decode(&mut &v[..]).ok());
+            let contribution = child_root
+                .and_then(|root| read(&root, &contributor.encode()))
+                .and_then(|v| <(BalanceOf<T>, Vec<u8>)>::decode(&mut &v[..]).ok());
+
+            matches!(contribution, Some((contributed, _)) if contributed == amount)
+        }
+
+        /// Key of relaychain `Crowdloan::Funds` for `crowdloan`
+        pub(crate) fn get_relay_fund_key(crowdloan: ParaId) -> Vec<u8> {
+            let storage_prefix = storage_prefix("Crowdloan".as_bytes(), "Funds".as_bytes());
+            let key_hashed = crowdloan.using_encoded(Twox64Concat::hash);
+            let mut final_key = Vec::with_capacity(storage_prefix.len() + key_hashed.len());
+
+            final_key.extend_from_slice(&storage_prefix);
+            final_key.extend_from_slice(&key_hashed);
+
+            final_key
+        }
+
+        /// Key of the child trie root holding contributions of relaychain fund `fund_index`
+        pub(crate) fn get_relay_fund_child_key(fund_index: TrieIndex) -> Vec<u8> {
+            let mut buf = Vec::new();
+            buf.extend_from_slice(b"crowdloan");
+            buf.extend_from_slice(&fund_index.encode()[..]);
+            child::ChildInfo::new_default(BlakeTwo256::hash(&buf[..]).as_ref())
+                .prefixed_storage_key()
+                .into_inner()
+        }
+
         // just iterate now and require improve later when CTokensRegistry increased
         fn find_vault_by_asset_id(asset_id: &AssetIdOf<T>) -> Option<(AssetIdOf<T>, AssetIdOf<T>)> {
             for (vault, ctoken_id) in CTokensRegistry::<T>::iter() {
@@ -18,7 +18,7 @@ pub use pallet_traits::{VaultTokenCurrenciesFilter, VaultTokenExchangeRateProvid
 use pallet_xcm::XcmPassthrough;
 use polkadot_parachain::primitives::{IsSystem, Sibling};
 use polkadot_runtime_parachains::configuration::HostConfiguration;
-use primitives::{tokens::*, Balance, ParaId};
+use primitives::{tokens::*, Balance, ParaId, PersistedValidationData};
 use sp_core::H256;
 use sp_runtime::{
     generic,
@@ -46,7 +46,7 @@ pub use kusama_runtime;
 use pallet_traits::{
     ump::{XcmCall, XcmWeightFeeMisc},
     xcm::MultiCurrencyAdapter,
-    DecimalProvider, Loans,
+    DecimalProvider, Loans, ValidationDataProvider,
 };
 
 pub struct RelayChainBlockNumberProvider<T>(sp_std::marker::PhantomData<T>);
@@ -79,6 +79,23 @@ impl BlockNumberProvider for MockBlockNumberProvider {
     }
 }
 
+parameter_types! {
+    pub static RelayStorageRoot: H256 = H256::zero();
+}
+
+pub struct MockValidationDataProvider;
+
+impl ValidationDataProvider for MockValidationDataProvider {
+    fn validation_data() -> Option<PersistedValidationData> {
+        Some(PersistedValidationData {
+            parent_head: Default::default(),
+            relay_parent_number: MockBlockNumberProvider::current_block_number(),
+            relay_parent_storage_root: RelayStorageRoot::get(),
+            max_pov_size: Default::default(),
+        })
+    }
+}
+
 parameter_types! {
     pub const ReservedXcmpWeight: Weight = Weight::from_ref_time(WEIGHT_REF_TIME_PER_SECOND.saturating_div(4));
     pub const ReservedDmpWeight: Weight = Weight::from_ref_time(WEIGHT_REF_TIME_PER_SECOND.saturating_div(4));
@@ -496,7 +513,7 @@ impl SortedMembers<AccountId> for EveOrigin {
 
 parameter_types! {
     pub const CrowdloansPalletId: PalletId = PalletId(*b"crwloans");
-    pub const MinContribution: Balance = 0;
+    pub static MinContribution: Balance = 0;
     pub const MigrateKeysLimit: u32 = 5;
     pub const RemoveKeysLimit: u32 = 1000;
     pub SelfParaId: ParaId = para_a_id();
@@ -553,6 +570,7 @@ impl crate::Config for Test {
     type WeightInfo = ();
     type XCM = XcmHelper;
     type RelayChainBlockNumberProvider = MockBlockNumberProvider;
+    type RelayChainValidationDataProvider = MockValidationDataProvider;
     type Members = CharlieOrigin;
     type LeasePeriod = LeasePeriod;
     type LeaseOffset = LeaseOffset;
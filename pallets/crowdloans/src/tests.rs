@@ -5,16 +5,18 @@ use codec::Encode;
 use frame_support::{
     assert_err, assert_noop, assert_ok,
     storage::child,
-    traits::{Hooks, OneSessionHandler},
+    traits::{fungibles::InspectMetadata, Hooks, OneSessionHandler},
 };
 use frame_system::RawOrigin;
 use polkadot_parachain::primitives::{HeadData, ValidationCode};
 use primitives::{tokens::DOT, BlockNumber, ParaId, Rate};
+use sp_core::H256;
 use sp_runtime::{
-    traits::{One, Zero},
+    traits::{BlakeTwo256, One, Zero},
     DispatchError,
     MultiAddress::Id,
 };
+use sp_trie::TrieMut;
 use xcm_simulator::TestExt;
 
 pub const LEASE_START: u32 = 0;
@@ -406,6 +408,80 @@ fn contribute_should_work() {
     });
 }
 
+#[test]
+fn vault_summary_should_work() {
+    new_test_ext().execute_with(|| {
+        let crowdloan = ParaId::from(1337u32);
+        let ctoken = 10;
+        let cap = 1_000_000_000_000;
+        let end_block = BlockNumber::from(1_000_000_000u32);
+        let contribution_strategy = ContributionStrategy::XCM;
+
+        assert_eq!(Crowdloans::vault_summary(crowdloan), None);
+
+        // create the ctoken asset
+        assert_ok!(Assets::force_create(
+            RawOrigin::Root.into(),
+            ctoken.into(),
+            Id(Crowdloans::account_id()),
+            true,
+            One::one(),
+        ));
+
+        // create a vault to contribute to
+        assert_ok!(Crowdloans::create_vault(
+            frame_system::RawOrigin::Root.into(), // origin
+            crowdloan,                            // crowdloan
+            ctoken,                               // ctoken
+            LEASE_START,                          // lease_start
+            LEASE_END,                            // lease_end
+            contribution_strategy,                // contribution_strategy
+            cap,                                  // cap
+            end_block                             // end_block
+        ));
+
+        // do open
+        assert_ok!(Crowdloans::open(
+            frame_system::RawOrigin::Root.into(), // origin
+            crowdloan,                            // crowdloan
+        ));
+
+        assert_ok!(Crowdloans::contribute(
+            RuntimeOrigin::signed(ALICE), // origin
+            crowdloan,                    // crowdloan
+            1_000,                        // amount
+            Vec::new()
+        ));
+        Crowdloans::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        )
+        .unwrap();
+
+        // ALICE is both contributed and flying now but counted once
+        assert_ok!(Crowdloans::contribute(
+            RuntimeOrigin::signed(ALICE), // origin
+            crowdloan,                    // crowdloan
+            500,                          // amount
+            Vec::new()
+        ));
+
+        assert_eq!(
+            Crowdloans::vault_summary(crowdloan),
+            Some(VaultSummary {
+                phase: VaultPhase::Contributing,
+                total_contributed: 1_500,
+                contributors: 1,
+                cap,
+                end_block,
+                lease_start: LEASE_START,
+                lease_end: LEASE_END,
+            })
+        );
+    });
+}
+
 #[test]
 fn contribute_should_fail_insufficient_funds() {
     new_test_ext().execute_with(|| {
@@ -450,6 +526,56 @@ fn contribute_should_fail_insufficient_funds() {
     });
 }
 
+#[test]
+fn contribute_should_fail_below_min_contribution() {
+    new_test_ext().execute_with(|| {
+        let crowdloan = ParaId::from(1337u32);
+        let ctoken = 10;
+        let cap = 1_000_000_000_000;
+        let end_block = BlockNumber::from(1_000_000_000u32);
+        let contribution_strategy = ContributionStrategy::XCM;
+        MinContribution::set(1_000);
+
+        // create the ctoken asset
+        assert_ok!(Assets::force_create(
+            RawOrigin::Root.into(),
+            ctoken.into(),
+            Id(Crowdloans::account_id()),
+            true,
+            One::one(),
+        ));
+
+        // create a vault to contribute to
+        assert_ok!(Crowdloans::create_vault(
+            frame_system::RawOrigin::Root.into(), // origin
+            crowdloan,                            // crowdloan
+            ctoken,                               // ctoken
+            LEASE_START,                          // lease_start
+            LEASE_END,                            // lease_end
+            contribution_strategy,                // contribution_strategy
+            cap,                                  // cap
+            end_block                             // end_block
+        ));
+
+        // do contribute
+        assert_noop!(
+            Crowdloans::contribute(
+                RuntimeOrigin::signed(ALICE), // origin
+                crowdloan,                    // crowdloan
+                999,                          // amount
+                Vec::new()
+            ),
+            Error::<Test>::ContributionTooSmall
+        );
+        assert_ok!(Crowdloans::contribute(
+            RuntimeOrigin::signed(ALICE), // origin
+            crowdloan,                    // crowdloan
+            1_000,                        // amount
+            Vec::new()
+        ));
+    });
+}
+
 #[test]
 fn close_should_work() {
     new_test_ext().execute_with(|| {
@@ -1911,3 +2037,297 @@ fn normalized_amount_should_work() {
         assert_eq!(normalized_amount, amount * 100);
     })
 }
+
+#[test]
+fn update_ctoken_metadata_should_work() {
+    new_test_ext().execute_with(|| {
+        let crowdloan = ParaId::from(1337u32);
+        let ctoken = 10;
+        let cap = 1_000_000_000_000;
+        let end_block = BlockNumber::from(1_000_000_000u32);
+        let contribution_strategy = ContributionStrategy::XCM;
+
+        // create the ctoken asset
+        assert_ok!(Assets::force_create(
+            RawOrigin::Root.into(),
+            ctoken.into(),
+            Id(Crowdloans::account_id()),
+            true,
+            One::one(),
+        ));
+
+        // create a vault to contribute to
+        assert_ok!(Crowdloans::create_vault(
+            frame_system::RawOrigin::Root.into(), // origin
+            crowdloan,                            // crowdloan
+            ctoken,                               // ctoken
+            LEASE_START,                          // lease_start
+            LEASE_END,                            // lease_end
+            contribution_strategy,                // contribution_strategy
+            cap,                                  // cap
+            end_block                             // end_block
+        ));
+
+        assert_noop!(
+            Crowdloans::update_ctoken_metadata(
+                RuntimeOrigin::signed(BOB),
+                crowdloan,
+                LEASE_START,
+                LEASE_END,
+                b"cDOT-1337".to_vec(),
+                b"CDOT1337".to_vec(),
+                12,
+            ),
+            DispatchError::BadOrigin
+        );
+
+        assert_ok!(Crowdloans::open(
+            frame_system::RawOrigin::Root.into(),
+            crowdloan,
+        ));
+        assert_ok!(Crowdloans::close(
+            frame_system::RawOrigin::Root.into(),
+            crowdloan,
+        ));
+
+        assert_ok!(Crowdloans::update_ctoken_metadata(
+            frame_system::RawOrigin::Root.into(),
+            crowdloan,
+            LEASE_START,
+            LEASE_END,
+            b"cDOT-1337".to_vec(),
+            b"CDOT1337".to_vec(),
+            12,
+        ));
+        assert_eq!(
+            <Assets as InspectMetadata<AccountId>>::name(&ctoken),
+            b"cDOT-1337".to_vec()
+        );
+        assert_eq!(
+            <Assets as InspectMetadata<AccountId>>::symbol(&ctoken),
+            b"CDOT1337".to_vec()
+        );
+        assert_eq!(
+            <Assets as InspectMetadata<AccountId>>::decimals(&ctoken),
+            12
+        );
+
+        // metadata is frozen once the auction outcome is known
+        assert_ok!(Crowdloans::auction_succeeded(
+            frame_system::RawOrigin::Root.into(),
+            crowdloan,
+        ));
+        assert_noop!(
+            Crowdloans::update_ctoken_metadata(
+                frame_system::RawOrigin::Root.into(),
+                crowdloan,
+                LEASE_START,
+                LEASE_END,
+                b"cDOT".to_vec(),
+                b"CDOT".to_vec(),
+                12,
+            ),
+            Error::<Test>::IncorrectVaultPhase
+        );
+    })
+}
+
+#[test]
+fn reclaim_pending_should_work() {
+    new_test_ext().execute_with(|| {
+        let crowdloan = ParaId::from(1337u32);
+        let ctoken = 10;
+        let amount = 1_000;
+        let cap = 1_000_000_000_000;
+        let end_block = BlockNumber::from(1_000_000_000u32);
+        let contribution_strategy = ContributionStrategy::XCM;
+
+        // create a vault to contribute to
+        assert_ok!(Crowdloans::create_vault(
+            frame_system::RawOrigin::Root.into(), // origin
+            crowdloan,                            // crowdloan
+            ctoken,                               // ctoken
+            LEASE_START,                          // lease_start
+            LEASE_END,                            // lease_end
+            contribution_strategy,                // contribution_strategy
+            cap,                                  // cap
+            end_block                             // end_block
+        ));
+
+        // contribution stays pending since the vault is not opened yet
+        assert_ok!(Crowdloans::contribute(
+            RuntimeOrigin::signed(ALICE), // origin
+            crowdloan,                    // crowdloan
+            amount,                       // amount
+            Vec::new()
+        ));
+
+        assert_ok!(Crowdloans::open(
+            frame_system::RawOrigin::Root.into(),
+            crowdloan,
+        ));
+        assert_ok!(Crowdloans::close(
+            frame_system::RawOrigin::Root.into(),
+            crowdloan,
+        ));
+        assert_ok!(Crowdloans::auction_succeeded(
+            frame_system::RawOrigin::Root.into(),
+            crowdloan,
+        ));
+
+        // pending contribution can't be reclaimed before the slot expired
+        assert_noop!(
+            Crowdloans::reclaim_pending(
+                RuntimeOrigin::signed(BOB),
+                Id(ALICE),
+                crowdloan,
+                LEASE_START,
+                LEASE_END,
+            ),
+            Error::<Test>::IncorrectVaultPhase
+        );
+
+        assert_ok!(Crowdloans::slot_expired(
+            frame_system::RawOrigin::Root.into(),
+            crowdloan,
+        ));
+        Crowdloans::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        )
+        .unwrap();
+
+        assert_eq!(Assets::balance(DOT, ALICE), dot(100f64) - amount);
+        assert_ok!(Crowdloans::reclaim_pending(
+            RuntimeOrigin::signed(BOB),
+            Id(ALICE),
+            crowdloan,
+            LEASE_START,
+            LEASE_END,
+        ));
+        assert_eq!(Assets::balance(DOT, ALICE), dot(100f64));
+        assert_eq!(Assets::balance(ctoken, ALICE), 0);
+
+        let vault = Crowdloans::vaults((&crowdloan, &LEASE_START, &LEASE_END)).unwrap();
+        assert_eq!(vault.pending, 0);
+
+        assert_noop!(
+            Crowdloans::reclaim_pending(
+                RuntimeOrigin::signed(BOB),
+                Id(ALICE),
+                crowdloan,
+                LEASE_START,
+                LEASE_END,
+            ),
+            Error::<Test>::NoContributions
+        );
+    })
+}
+
+fn relay_storage_proof(entries: &[(Vec<u8>, Vec<u8>)]) -> (H256, Vec<Vec<u8>>) {
+    let mut db = sp_trie::MemoryDB::<BlakeTwo256>::default();
+    let mut root = Default::default();
+    {
+        let mut trie =
+            sp_trie::TrieDBMutBuilder::<sp_trie::LayoutV1<BlakeTwo256>>::new(&mut db, &mut root)
+                .build();
+        for (key, value) in entries {
+            trie.insert(key, value).unwrap();
+        }
+    }
+    (
+        root,
+        db.drain().into_values().map(|(node, _)| node).collect(),
+    )
+}
+
+#[test]
+fn set_vault_contributed_should_work() {
+    new_test_ext().execute_with(|| {
+        let crowdloan = ParaId::from(1337u32);
+        let ctoken = 10;
+        let amount = 1_000;
+        let fund_index = 3;
+        let cap = 1_000_000_000_000;
+        let end_block = BlockNumber::from(1_000_000_000u32);
+        let contribution_strategy = ContributionStrategy::XCM;
+
+        // create a vault to contribute to
+        assert_ok!(Crowdloans::create_vault(
+            frame_system::RawOrigin::Root.into(), // origin
+            crowdloan,                            // crowdloan
+            ctoken,                               // ctoken
+            LEASE_START,                          // lease_start
+            LEASE_END,                            // lease_end
+            contribution_strategy,                // contribution_strategy
+            cap,                                  // cap
+            end_block                             // end_block
+        ));
+
+        // relaychain fund of the crowdloan and its contributions child trie
+        let fund = RelayFundInfo {
+            depositor: BOB,
+            verifier: None,
+            deposit: 0,
+            raised: amount,
+            end: end_block,
+            cap,
+            last_contribution: RelayLastContribution::Never,
+            first_period: LEASE_START,
+            last_period: LEASE_END,
+            fund_index,
+        };
+        let (child_root, mut proof) = relay_storage_proof(&[(
+            Crowdloans::para_account_id().encode(),
+            (amount, Vec::<u8>::new()).encode(),
+        )]);
+        let (root, fund_proof) = relay_storage_proof(&[
+            (Crowdloans::get_relay_fund_key(crowdloan), fund.encode()),
+            (
+                Crowdloans::get_relay_fund_child_key(fund_index),
+                child_root.encode(),
+            ),
+        ]);
+        proof.extend(fund_proof);
+
+        // no validation data stored yet
+        assert_noop!(
+            Crowdloans::set_vault_contributed(
+                RuntimeOrigin::signed(BOB),
+                crowdloan,
+                LEASE_START,
+                LEASE_END,
+                amount,
+                proof.clone(),
+            ),
+            Error::<Test>::InvalidProof
+        );
+
+        RelayStorageRoot::set(root);
+        Crowdloans::on_finalize(1);
+
+        assert_noop!(
+            Crowdloans::set_vault_contributed(
+                RuntimeOrigin::signed(BOB),
+                crowdloan,
+                LEASE_START,
+                LEASE_END,
+                amount + 1,
+                proof.clone(),
+            ),
+            Error::<Test>::InvalidProof
+        );
+
+        assert_ok!(Crowdloans::set_vault_contributed(
+            RuntimeOrigin::signed(BOB),
+            crowdloan,
+            LEASE_START,
+            LEASE_END,
+            amount,
+            proof,
+        ));
+        let vault = Crowdloans::vaults((&crowdloan, &LEASE_START, &LEASE_END)).unwrap();
+        assert_eq!(vault.contributed, amount);
+    })
+}
@@ -19,9 +19,11 @@ use super::{AccountIdOf, AssetIdOf, BalanceOf, Config};
 use codec::{Decode, Encode};
 
 use frame_system::pallet_prelude::BlockNumberFor;
-use primitives::{LeasePeriod, ParaId, Timestamp, TrieIndex, VaultId};
+use primitives::{
+    AccountId, Balance, BlockNumber, LeasePeriod, ParaId, Timestamp, TrieIndex, VaultId,
+};
 use scale_info::TypeInfo;
-use sp_runtime::{traits::Zero, RuntimeDebug};
+use sp_runtime::{traits::Zero, MultiSigner, RuntimeDebug};
 use sp_std::vec::Vec;
 
 #[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
@@ -101,6 +103,25 @@ impl<T: Config> Vault<T> {
     }
 }
 
+/// Overview of a vault, as exposed to front-ends
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct VaultSummary<Balance, BlockNumber> {
+    /// Which phase the vault is at
+    pub phase: VaultPhase,
+    /// Contributed, flying and pending coins altogether
+    pub total_contributed: Balance,
+    /// Number of accounts with a contribution in the vault
+    pub contributors: u32,
+    /// parallel enforced limit
+    pub cap: Balance,
+    /// block that vault ends
+    pub end_block: BlockNumber,
+    /// lease start period index
+    pub lease_start: LeasePeriod,
+    /// lease end period index
+    pub lease_end: LeasePeriod,
+}
+
 #[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum ContributionStrategy {
     XCM = 0,
@@ -125,6 +146,30 @@ pub enum XcmRequest<T: Config> {
     },
 }
 
+/// Mirror of relaychain crowdloan's `LastContribution`
+#[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum RelayLastContribution {
+    Never,
+    PreEnding(u32),
+    Ending(BlockNumber),
+}
+
+/// Mirror of relaychain crowdloan's `FundInfo`, used to decode
+/// the fund of a crowdloan from a relaychain storage proof
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct RelayFundInfo {
+    pub depositor: AccountId,
+    pub verifier: Option<MultiSigner>,
+    pub deposit: Balance,
+    pub raised: Balance,
+    pub end: BlockNumber,
+    pub cap: Balance,
+    pub last_contribution: RelayLastContribution,
+    pub first_period: LeasePeriod,
+    pub last_period: LeasePeriod,
+    pub fund_index: TrieIndex,
+}
+
 #[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum ChildStorageKind {
     Pending,
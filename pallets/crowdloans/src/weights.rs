@@ -65,6 +65,9 @@ pub trait WeightInfo {
 	fn refund_for() -> Weight;
 	fn update_proxy() -> Weight;
 	fn update_leases_bonus() -> Weight;
+	fn update_ctoken_metadata() -> Weight;
+	fn reclaim_pending() -> Weight;
+	fn set_vault_contributed() -> Weight;
 }
 
 /// Weights for pallet_crowdloans using the Substrate node and recommended hardware.
@@ -298,6 +301,32 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1 as u64))
 			.saturating_add(T::DbWeight::get().writes(2 as u64))
 	}
+	// Storage: Crowdloans Vaults (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets Metadata (r:1 w:1)
+	fn update_ctoken_metadata() -> Weight {
+		Weight::from_ref_time(36_512_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Crowdloans Vaults (r:1 w:1)
+	// Storage: unknown [0x] (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn reclaim_pending() -> Weight {
+		Weight::from_ref_time(96_512_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(6 as u64))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
+	}
+	// Storage: Crowdloans Vaults (r:1 w:1)
+	// Storage: Crowdloans ValidationData (r:1 w:0)
+	// Storage: Crowdloans ProxyAddress (r:1 w:0)
+	fn set_vault_contributed() -> Weight {
+		Weight::from_ref_time(258_104_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -529,4 +558,30 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1 as u64))
 			.saturating_add(RocksDbWeight::get().writes(2 as u64))
 	}
+	// Storage: Crowdloans Vaults (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: Assets Metadata (r:1 w:1)
+	fn update_ctoken_metadata() -> Weight {
+		Weight::from_ref_time(36_512_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: Crowdloans Vaults (r:1 w:1)
+	// Storage: unknown [0x] (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn reclaim_pending() -> Weight {
+		Weight::from_ref_time(96_512_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
+	}
+	// Storage: Crowdloans Vaults (r:1 w:1)
+	// Storage: Crowdloans ValidationData (r:1 w:0)
+	// Storage: Crowdloans ProxyAddress (r:1 w:0)
+	fn set_vault_contributed() -> Weight {
+		Weight::from_ref_time(258_104_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
 }
@@ -0,0 +1,58 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use pallet_traits::ump::{
+    FullPending, InvariantError, LiquidBackingInfo, MatchingPoolState, MatchingStatus,
+    StakingLedgerInfo, XcmRequestInfo,
+};
+use primitives::{DerivativeIndex, EraIndex, Rate};
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+use xcm::latest::QueryId;
+
+sp_api::decl_runtime_apis! {
+    pub trait LiquidStakingApi<Balance, AccountId> where
+        Balance: Codec,
+        AccountId: Codec, {
+        fn compute_exchange_rate(
+            total_active_bonded: Balance,
+            matching_stake: Balance,
+            matching_unstake: Balance,
+            issuance: Balance,
+        ) -> Option<Rate>;
+        fn pending_xcm_requests() -> Vec<(QueryId, XcmRequestInfo<Balance>)>;
+        fn fast_unstake_capacity() -> Balance;
+        fn reward_snapshots(account: AccountId) -> Vec<(EraIndex, Rate)>;
+        fn liquid_backing() -> Option<LiquidBackingInfo<Balance>>;
+        fn check_invariants() -> Result<(), InvariantError>;
+        fn unbonding_schedule(derivative_index: DerivativeIndex) -> Vec<(EraIndex, Balance)>;
+        fn total_unbonding_schedule() -> Vec<(EraIndex, Balance)>;
+        fn matching_status() -> MatchingStatus;
+        fn relay_storage_keys(derivative_index: DerivativeIndex) -> (Vec<u8>, Vec<u8>);
+        fn claimable_now(account: AccountId) -> Balance;
+        /// [liquid_amount, reserves, xcm_fees]
+        fn preview_stake(amount: Balance) -> Result<(Balance, Balance, Balance), DispatchError>;
+        fn all_staking_ledgers() -> Vec<(DerivativeIndex, StakingLedgerInfo<AccountId, Balance>)>;
+        /// [era_index, stake_amount, unstake_amount]
+        fn era_net_flows() -> Vec<(EraIndex, Balance, Balance)>;
+        fn estimated_apy(lookback_eras: EraIndex) -> Option<Rate>;
+        fn ledger_cap_headroom() -> Vec<(DerivativeIndex, Balance)>;
+        fn matching_pool_state() -> MatchingPoolState<Balance>;
+        fn derivative_accounts() -> Vec<(DerivativeIndex, AccountId)>;
+        fn full_pending(account: AccountId) -> FullPending<Balance>;
+    }
+}
@@ -7,20 +7,22 @@ use frame_support::{
     dispatch::DispatchResult,
     storage::with_transaction,
     traits::{fungibles::Mutate, Hooks},
+    BoundedVec,
 };
 use frame_system::{self, RawOrigin as SystemOrigin};
 use sp_runtime::{
-    traits::{One, Saturating, StaticLookup},
-    TransactionOutcome,
+    traits::{BlakeTwo256, One, Saturating, StaticLookup, Zero},
+    FixedPointNumber, TransactionOutcome,
 };
 use sp_std::{prelude::*, vec};
+use sp_trie::{LayoutV1, MemoryDB, TrieDBMutBuilder, TrieMut};
 use xcm::latest::prelude::*;
 
 use pallet_traits::ump::RewardDestination;
-use primitives::{Balance, CurrencyId, Rate, Ratio};
+use primitives::{Balance, CurrencyId, PersistedValidationData, Rate, Ratio};
 
 use crate::{
-    types::{StakingLedger, UnstakeProvider},
+    types::{ParametersUpdate, RewardSource, StakingLedger, UnstakeProvider, XcmRequest},
     Pallet as LiquidStaking,
 };
 
@@ -111,6 +113,32 @@ fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
     frame_system::Pallet::<T>::assert_last_event(generic_event.into());
 }
 
+fn staking_ledger_proof<T: Config>(
+    derivative_index: DerivativeIndex,
+    staking_ledger: &StakingLedger<T::AccountId, BalanceOf<T>>,
+) -> Vec<Vec<u8>> {
+    let mut db = MemoryDB::<BlakeTwo256>::default();
+    let mut root = Default::default();
+    {
+        let mut trie = TrieDBMutBuilder::<LayoutV1<BlakeTwo256>>::new(&mut db, &mut root).build();
+        trie.insert(
+            &LiquidStaking::<T>::get_staking_ledger_key(derivative_index),
+            &staking_ledger.encode(),
+        )
+        .unwrap();
+    }
+    ValidationData::<T>::put(PersistedValidationData {
+        parent_head: Default::default(),
+        relay_parent_number: Default::default(),
+        relay_parent_storage_root: root,
+        max_pov_size: Default::default(),
+    });
+    db.drain()
+        .into_iter()
+        .filter_map(|(_, (node, rc))| (rc > 0).then_some(node))
+        .collect()
+}
+
 benchmarks! {
     where_clause {
         where
@@ -132,7 +160,7 @@ benchmarks! {
         let alice: T::AccountId = account("Sample", 100, SEED);
         initial_set_up::<T>(alice.clone());
         LiquidStaking::<T>::stake(SystemOrigin::Signed(alice.clone()).into(), STAKE_AMOUNT).unwrap();
-    }: _(SystemOrigin::Signed(alice.clone()), UNSTAKE_AMOUNT, Default::default())
+    }: _(SystemOrigin::Signed(alice.clone()), UNSTAKE_AMOUNT, Default::default(), None, None, None)
     verify {
         assert_last_event::<T>(Event::<T>::Unstaked(alice, UNSTAKE_AMOUNT, UNSTAKE_AMOUNT).into());
     }
@@ -198,7 +226,7 @@ benchmarks! {
         let alice: T::AccountId = account("Sample", 100, SEED);
         initial_set_up::<T>(alice.clone());
         LiquidStaking::<T>::stake(SystemOrigin::Signed(alice.clone()).into(), STAKE_AMOUNT).unwrap();
-        LiquidStaking::<T>::unstake(SystemOrigin::Signed(alice).into(), UNBOND_AMOUNT, Default::default()).unwrap();
+        LiquidStaking::<T>::unstake(SystemOrigin::Signed(alice).into(), UNBOND_AMOUNT, Default::default(), None, None, None).unwrap();
         LiquidStaking::<T>::bond(SystemOrigin::Root.into(), 0, BOND_AMOUNT, RewardDestination::Staked).unwrap();
         LiquidStaking::<T>::notification_received(
             pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
@@ -214,7 +242,7 @@ benchmarks! {
         let alice: T::AccountId = account("Sample", 100, SEED);
         initial_set_up::<T>(alice.clone());
         LiquidStaking::<T>::stake(SystemOrigin::Signed(alice.clone()).into(), STAKE_AMOUNT).unwrap();
-        LiquidStaking::<T>::unstake(SystemOrigin::Signed(alice).into(), UNBOND_AMOUNT, Default::default()).unwrap();
+        LiquidStaking::<T>::unstake(SystemOrigin::Signed(alice).into(), UNBOND_AMOUNT, Default::default(), None, None, None).unwrap();
         LiquidStaking::<T>::bond(SystemOrigin::Root.into(), 0, BOND_AMOUNT, RewardDestination::Staked).unwrap();
         LiquidStaking::<T>::notification_received(
             pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
@@ -236,7 +264,7 @@ benchmarks! {
         let alice: T::AccountId = account("Sample", 100, SEED);
         initial_set_up::<T>(alice.clone());
         LiquidStaking::<T>::stake(SystemOrigin::Signed(alice.clone()).into(), STAKE_AMOUNT).unwrap();
-        LiquidStaking::<T>::unstake(SystemOrigin::Signed(alice).into(), UNBOND_AMOUNT, Default::default()).unwrap();
+        LiquidStaking::<T>::unstake(SystemOrigin::Signed(alice).into(), UNBOND_AMOUNT, Default::default(), None, None, None).unwrap();
         LiquidStaking::<T>::bond(SystemOrigin::Root.into(), 0, BOND_AMOUNT, RewardDestination::Staked).unwrap();
         LiquidStaking::<T>::notification_received(
             pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
@@ -297,7 +325,7 @@ benchmarks! {
         let account_id = T::Lookup::unlookup(alice.clone());
         initial_set_up::<T>(alice.clone());
         LiquidStaking::<T>::stake(SystemOrigin::Signed(alice.clone()).into(), STAKE_AMOUNT).unwrap();
-        LiquidStaking::<T>::unstake(SystemOrigin::Signed(alice.clone()).into(), UNSTAKE_AMOUNT, Default::default()).unwrap();
+        LiquidStaking::<T>::unstake(SystemOrigin::Signed(alice.clone()).into(), UNSTAKE_AMOUNT, Default::default(), None, None, None).unwrap();
         assert_ok!(with_transaction(|| -> TransactionOutcome<DispatchResult>{
             LiquidStaking::<T>::do_advance_era(T::BondingDuration::get() + 1).unwrap();
             LiquidStaking::<T>::do_matching().unwrap();
@@ -397,11 +425,21 @@ benchmarks! {
         assert_last_event::<T>(Event::<T>::ReservesReduced(alice, reduce_amount).into());
     }
 
+    add_reserves {
+        let alice: T::AccountId = account("Sample", 100, SEED);
+        let add_amount: u128 = 1000;
+        initial_set_up::<T>(alice.clone());
+    }: _(SystemOrigin::Signed(alice.clone()), add_amount)
+    verify {
+        assert_eq!(TotalReserves::<T>::get(), add_amount);
+        assert_last_event::<T>(Event::<T>::ReservesAdded(alice, add_amount).into());
+    }
+
     cancel_unstake {
         let alice: T::AccountId = account("Sample", 100, SEED);
         initial_set_up::<T>(alice.clone());
         LiquidStaking::<T>::stake(SystemOrigin::Signed(alice.clone()).into(), STAKE_AMOUNT).unwrap();
-        LiquidStaking::<T>::unstake(SystemOrigin::Signed(alice.clone()).into(), UNSTAKE_AMOUNT, UnstakeProvider::MatchingPool).unwrap();
+        LiquidStaking::<T>::unstake(SystemOrigin::Signed(alice.clone()).into(), UNSTAKE_AMOUNT, UnstakeProvider::MatchingPool, None, None, None).unwrap();
     }: _(SystemOrigin::Signed(alice.clone()), UNSTAKE_AMOUNT)
     verify {
         assert_last_event::<T>(Event::<T>::UnstakeCancelled(alice, UNSTAKE_AMOUNT, UNSTAKE_AMOUNT).into());
@@ -424,8 +462,8 @@ benchmarks! {
             )
             .unwrap();
 
-            LiquidStaking::<T>::unstake(SystemOrigin::Signed(unstaker.clone()).into(), fast_unstake_amount, UnstakeProvider::MatchingPool).unwrap();
-            assert_eq!(FastUnstakeRequests::<T>::get(&unstaker), fast_unstake_amount);
+            LiquidStaking::<T>::unstake(SystemOrigin::Signed(unstaker.clone()).into(), fast_unstake_amount, UnstakeProvider::MatchingPool, None, None, None).unwrap();
+            assert_eq!(FastUnstakeRequests::<T>::get(&unstaker).amount, fast_unstake_amount);
             unstaker_list.push(unstaker);
         }
     }: _(SystemOrigin::Root, unstaker_list)
@@ -440,6 +478,161 @@ benchmarks! {
             STAKE_AMOUNT - xcm_fee - reserve - total_matched_amount
         );
     }
+
+    set_payee_override {
+    }: _(SystemOrigin::Root, 0, Some(RewardDestination::Staked))
+    verify {
+        assert_last_event::<T>(Event::<T>::PayeeOverrideUpdated(0, Some(RewardDestination::Staked)).into());
+    }
+
+    set_per_index_min_bond {
+    }: _(SystemOrigin::Root, 0, Some(BOND_AMOUNT))
+    verify {
+        assert_last_event::<T>(Event::<T>::PerIndexMinBondUpdated(0, Some(BOND_AMOUNT)).into());
+    }
+
+    set_slashing_spans {
+    }: _(SystemOrigin::Root, 0, Some(1))
+    verify {
+        assert_last_event::<T>(Event::<T>::SlashingSpansUpdated(0, Some(1)).into());
+    }
+
+    stake_reserves {
+        let alice: T::AccountId = account("Sample", 100, SEED);
+        initial_set_up::<T>(alice.clone());
+        LiquidStaking::<T>::stake(SystemOrigin::Signed(alice).into(), STAKE_AMOUNT).unwrap();
+        let reserves = TotalReserves::<T>::get();
+    }: _(SystemOrigin::Root, reserves)
+    verify {
+        assert_last_event::<T>(Event::<T>::ReservesStaked(reserves).into());
+    }
+
+    update_parameters {
+        let params = ParametersUpdate {
+            reserve_factor: Some(RESERVE_FACTOR),
+            commission_rate: Some(COMMISSION_RATE),
+            staking_ledger_cap: Some(STAKING_LEDGER_CAP),
+            incentive: Some(BalanceOf::<T>::one()),
+        };
+    }: _(SystemOrigin::Root, params.clone())
+    verify {
+        assert_last_event::<T>(Event::<T>::ParametersUpdated(params).into());
+    }
+
+    recompute_matching_locks {
+        let alice: T::AccountId = account("Sample", 100, SEED);
+        initial_set_up::<T>(alice.clone());
+        LiquidStaking::<T>::stake(SystemOrigin::Signed(alice).into(), STAKE_AMOUNT).unwrap();
+        LiquidStaking::<T>::bond(SystemOrigin::Root.into(), 0, BOND_AMOUNT, RewardDestination::Staked).unwrap();
+    }: _(SystemOrigin::Root)
+    verify {
+        assert_last_event::<T>(Event::<T>::MatchingLocksRepaired(BOND_AMOUNT, Zero::zero()).into());
+    }
+
+    set_protocol_fee_receiver {
+        let alice: T::AccountId = account("Sample", 100, SEED);
+    }: _(SystemOrigin::Root, alice.clone())
+    verify {
+        assert_last_event::<T>(Event::<T>::ProtocolFeeReceiverUpdated(alice).into());
+    }
+
+    force_consolidate_ledger {
+        let alice: T::AccountId = account("Sample", 100, SEED);
+        initial_set_up::<T>(alice);
+        let mut staking_ledger = <StakingLedger<T::AccountId, BalanceOf<T>>>::new(
+            LiquidStaking::<T>::derivative_sovereign_account_id(0u16),
+            BOND_AMOUNT,
+        );
+        staking_ledger.unbond(UNBOND_AMOUNT, 0);
+        StakingLedgers::<T>::insert(0u16, staking_ledger);
+    }: _(SystemOrigin::Root, 0u16)
+    verify {
+        assert_last_event::<T>(Event::<T>::LedgerConsolidated(0u16, UNBOND_AMOUNT).into());
+    }
+
+    update_source_commission_rate {
+    }: _(SystemOrigin::Root, RewardSource::SlashRecovery, Some(COMMISSION_RATE))
+    verify {
+        assert_eq!(SourceCommissionRates::<T>::get(RewardSource::SlashRecovery), Some(COMMISSION_RATE));
+    }
+
+    prune_stale_xcm_requests {
+        let n in 1..100;
+        let alice: T::AccountId = account("Sample", 100, SEED);
+        initial_set_up::<T>(alice.clone());
+        LiquidStaking::<T>::stake(SystemOrigin::Signed(alice).into(), STAKE_AMOUNT).unwrap();
+        LiquidStaking::<T>::bond(SystemOrigin::Root.into(), 0, BOND_AMOUNT, RewardDestination::Staked).unwrap();
+        for query_id in 1..n {
+            XcmRequests::<T>::insert(query_id as u64, XcmRequest::Nominate { index: 0, targets: vec![] });
+        }
+        let query_ids: Vec<u64> = (0..n as u64).collect();
+    }: _(SystemOrigin::Root, query_ids, Zero::zero())
+    verify {
+        assert_last_event::<T>(Event::<T>::StaleXcmRequestPruned(n as u64 - 1).into());
+    }
+
+    force_set_exchange_rate {
+        ExchangeRate::<T>::put(Rate::one());
+        let rate = Rate::saturating_from_rational(105u32, 100u32);
+    }: _(SystemOrigin::Root, rate)
+    verify {
+        assert_last_event::<T>(Event::<T>::ExchangeRateUpdated(rate, Zero::zero()).into());
+    }
+
+    set_reward_snapshots {
+        let alice: T::AccountId = account("Sample", 100, SEED);
+    }: _(SystemOrigin::Signed(alice.clone()), true)
+    verify {
+        assert_last_event::<T>(Event::<T>::RewardSnapshotsToggled(alice, true).into());
+    }
+
+    set_default_targets {
+        let n in 1 .. T::MaxNominations::get();
+        let targets: Vec<T::AccountId> = (0 .. n).map(|i| account("target", i, SEED)).collect();
+        let bounded_targets: BoundedVec<T::AccountId, T::MaxNominations> = targets.clone().try_into().unwrap();
+    }: _(SystemOrigin::Root, bounded_targets)
+    verify {
+        assert_last_event::<T>(Event::<T>::DefaultNominationTargetsUpdated(targets).into());
+    }
+
+    set_auto_match {
+        let alice: T::AccountId = account("Sample", 100, SEED);
+        initial_set_up::<T>(alice.clone());
+        LiquidStaking::<T>::stake(SystemOrigin::Signed(alice.clone()).into(), STAKE_AMOUNT).unwrap();
+        LiquidStaking::<T>::unstake(SystemOrigin::Signed(alice.clone()).into(), UNSTAKE_AMOUNT, UnstakeProvider::MatchingPool, None, None, None).unwrap();
+    }: _(SystemOrigin::Signed(alice.clone()), false)
+    verify {
+        assert_last_event::<T>(Event::<T>::AutoMatchUpdated(alice, false).into());
+    }
+
+    report_slash {
+        let alice: T::AccountId = account("Sample", 100, SEED);
+        initial_set_up::<T>(alice.clone());
+        LiquidStaking::<T>::stake(SystemOrigin::Signed(alice.clone()).into(), STAKE_AMOUNT).unwrap();
+        LiquidStaking::<T>::bond(SystemOrigin::Root.into(), 0, BOND_AMOUNT, RewardDestination::Staked).unwrap();
+        LiquidStaking::<T>::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0u64,
+            Response::ExecutionResult(None)
+        ).unwrap();
+        let mut staking_ledger = StakingLedgers::<T>::get(0).unwrap();
+        staking_ledger.active -= UNBOND_AMOUNT;
+        staking_ledger.total -= UNBOND_AMOUNT;
+        let proof = staking_ledger_proof::<T>(0, &staking_ledger);
+    }: _(SystemOrigin::Signed(alice), 0u16, staking_ledger, proof)
+    verify {
+        assert_last_event::<T>(Event::<T>::SlashReported(0, UNBOND_AMOUNT, LiquidStaking::<T>::exchange_rate()).into());
+    }
+
+    stake_and_collateralize {
+        let alice: T::AccountId = account("Sample", 100, SEED);
+        initial_set_up::<T>(alice.clone());
+    }: _(SystemOrigin::Signed(alice.clone()), STAKE_AMOUNT)
+    verify {
+        let xcm_fee = T::XcmFees::get();
+        let reserve = ReserveFactor::<T>::get().mul_floor(STAKE_AMOUNT);
+        assert_last_event::<T>(Event::<T>::StakedAndCollateralized(alice, STAKE_AMOUNT, STAKE_AMOUNT - xcm_fee - reserve).into());
+    }
 }
 
 impl_benchmark_test_suite!(LiquidStaking, crate::mock::para_ext(1), crate::mock::Test);
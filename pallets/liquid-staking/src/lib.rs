@@ -28,9 +28,9 @@ use sp_runtime::{
 
 pub use pallet::*;
 use pallet_traits::{
-    DecimalProvider, DistributionStrategy, ExchangeRateProvider, LiquidStakingConvert,
-    LiquidStakingCurrenciesProvider, Loans, LoansMarketDataProvider, LoansPositionDataProvider,
-    ValidationDataProvider,
+    DecimalProvider, DistributionStrategy, ExchangeRateChangeHandler, ExchangeRateProvider,
+    LiquidStakingConvert, LiquidStakingCurrenciesProvider, Loans, LoansMarketDataProvider,
+    LoansPositionDataProvider, ValidationDataProvider,
 };
 use primitives::{PersistedValidationData, Rate};
 
@@ -63,7 +63,7 @@ pub mod pallet {
         storage::{storage_prefix, with_transaction},
         traits::{
             fungibles::{Inspect, Mutate, Transfer},
-            IsType, SortedMembers,
+            Contains, IsType, SortedMembers,
         },
         transactional, PalletId, StorageHasher,
     };
@@ -79,17 +79,24 @@ pub mod pallet {
         },
         ArithmeticError, FixedPointNumber, TransactionOutcome,
     };
-    use sp_std::{borrow::Borrow, boxed::Box, cmp::min, result::Result, vec::Vec};
+    use sp_std::{
+        borrow::Borrow, boxed::Box, cmp::min, collections::btree_map::BTreeMap, result::Result,
+        vec::Vec,
+    };
     use sp_trie::StorageProof;
     use xcm::latest::prelude::*;
 
     use pallet_traits::ump::*;
     use pallet_xcm_helper::XcmHelper;
-    use primitives::{Balance, CurrencyId, DerivativeIndex, EraIndex, ParaId, Rate, Ratio};
+    use primitives::{
+        Balance, CurrencyId, DerivativeIndex, EraIndex, ParaId, Rate, Ratio, SECONDS_PER_YEAR,
+    };
 
     use super::{types::*, *};
 
     pub const MAX_UNLOCKING_CHUNKS: usize = 32;
+    pub const MAX_REWARD_SNAPSHOTS: usize = 256;
+    pub const MAX_HISTORY_PRUNE_PER_ERA: EraIndex = 32;
 
     pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
     pub type AssetIdOf<T> =
@@ -108,6 +115,7 @@ pub mod pallet {
         V1,
         V2,
         V3,
+        V4,
     }
 
     #[pallet::config]
@@ -133,6 +141,14 @@ pub mod pallet {
         /// Approved accouts which can call `withdraw_unbonded` and `settlement`
         type Members: SortedMembers<Self::AccountId>;
 
+        /// Only allow `Members` to submit era and staking ledger proofs
+        #[pallet::constant]
+        type RestrictProofSubmitters: Get<bool>;
+
+        /// Accounts allowed as `RewardDestination::Account` besides the derivative
+        /// sovereign accounts
+        type RewardDestinationAllowlist: Contains<Self::AccountId>;
+
         /// The pallet id of liquid staking, keeps all the staking assets
         #[pallet::constant]
         type PalletId: Get<PalletId>;
@@ -161,6 +177,16 @@ pub mod pallet {
         #[pallet::constant]
         type MatchingPoolFastUnstakeFee: Get<Rate>;
 
+        /// Minimum liquid amount matched from matching pool for a fast unstake request,
+        /// smaller matches are left pending
+        #[pallet::constant]
+        type MinFastUnstakeMatch: Get<BalanceOf<Self>>;
+
+        /// Number of eras a fast unstake request stays pending before it expires,
+        /// zero means it never expires
+        #[pallet::constant]
+        type FastUnstakeRequestLifetime: Get<EraIndex>;
+
         /// Staking currency
         #[pallet::constant]
         type StakingCurrency: Get<AssetIdOf<Self>>;
@@ -181,6 +207,25 @@ pub mod pallet {
         #[pallet::constant]
         type MinUnstake: Get<BalanceOf<Self>>;
 
+        /// Liquid balance left below this after an unstake is unstaked along
+        /// with it, so that no un-unstakeable dust is left behind
+        #[pallet::constant]
+        type DustThreshold: Get<BalanceOf<Self>>;
+
+        /// Fraction of active bonded discounted when updating the exchange rate,
+        /// so that slashes not yet reflected in the ledgers don't inflate it
+        #[pallet::constant]
+        type SlashingSafetyBuffer: Get<Ratio>;
+
+        /// Commission below this is accumulated in `PendingCommission` instead
+        /// of being minted right away
+        #[pallet::constant]
+        type MinCommissionMint: Get<BalanceOf<Self>>;
+
+        /// Exchange rate restored once liquid currency issuance drops to zero
+        #[pallet::constant]
+        type ZeroIssuanceExchangeRate: Get<Rate>;
+
         /// Weight information
         type WeightInfo: WeightInfo;
 
@@ -188,15 +233,60 @@ pub mod pallet {
         #[pallet::constant]
         type BondingDuration: Get<EraIndex>;
 
+        /// Extra eras added to `BondingDuration` for unstaked funds to unlock,
+        /// covering unbonds sent after relaychain stored the election solution.
+        #[pallet::constant]
+        type UnbondingEraBuffer: Get<EraIndex>;
+
         /// The minimum active bond to become and maintain the role of a nominator.
         #[pallet::constant]
         type MinNominatorBond: Get<BalanceOf<Self>>;
 
+        /// The minimum amount worth sending a bond/unbond XCM for, smaller
+        /// distributions are left in the matching pool for the next round.
+        #[pallet::constant]
+        type MinXcmBondAmount: Get<BalanceOf<Self>>;
+
+        /// The maximum deviation from the current exchange rate that
+        /// `force_set_exchange_rate` accepts.
+        #[pallet::constant]
+        type MaxExchangeRateDeviation: Get<Ratio>;
+
+        /// The maximum fraction of `nominate` targets allowed to differ from
+        /// the last confirmed nominations of the same derivative index.
+        #[pallet::constant]
+        type MaxNominationChurnPerEra: Get<Ratio>;
+
+        /// The maximum number of eras `on_initialize` advances in one block,
+        /// the remaining eras are carried to the next blocks.
+        #[pallet::constant]
+        type MaxEraAdvancePerBlock: Get<EraIndex>;
+
+        /// The maximum number of xcm requests sent in one block,
+        /// the remaining bonds are deferred to the next blocks.
+        #[pallet::constant]
+        type MaxXcmPerBlock: Get<u32>;
+
+        /// The maximum number of unstakers passed to one `fast_match_unstake` call
+        #[pallet::constant]
+        type MaxUnstakersPerCall: Get<u32>;
+
+        /// The maximum number of validators nominated by one derivative index,
+        /// matching relaychain's `MaxNominations`
+        #[pallet::constant]
+        type MaxNominations: Get<u32>;
+
+        /// The maximum amount of incentive paid to relayers in one era
+        #[pallet::constant]
+        type IncentiveBudgetPerEra: Get<BalanceOf<Self>>;
+
         /// Number of blocknumbers that each period contains.
         /// SessionsPerEra * EpochDuration / MILLISECS_PER_BLOCK
         #[pallet::constant]
         type EraLength: Get<BlockNumberFor<Self>>;
 
+        /// Number of slashing spans used to withdraw unbonded when
+        /// `SlashingSpans` isn't set for a derivative index
         #[pallet::constant]
         type NumSlashingSpans: Get<u32>;
 
@@ -220,7 +310,30 @@ pub mod pallet {
         #[pallet::constant]
         type ElectionSolutionStoredOffset: Get<BlockNumberFor<Self>>;
 
-        /// Who/where to send the protocol fees
+        /// Number of relaychain blocks between two extra matching rounds in one era,
+        /// zero disables matching more than once per era
+        #[pallet::constant]
+        type MatchingInterval: Get<BlockNumberFor<Self>>;
+
+        /// Free amount in matching pool from which an extra matching round is run
+        #[pallet::constant]
+        type IntraEraMatchingThreshold: Get<BalanceOf<Self>>;
+
+        /// Number of eras the per era history (net flow, exchange rate) is kept for
+        #[pallet::constant]
+        type HistoryDepth: Get<EraIndex>;
+
+        /// Whether bonds larger than `BondSplitThreshold` of an index's active bonded
+        /// are split, leaving the remainder in matching pool for the next era
+        #[pallet::constant]
+        type SplitLargeBonds: Get<bool>;
+
+        /// Largest bond sent to an index in one matching, relative to its active bonded
+        #[pallet::constant]
+        type BondSplitThreshold: Get<Rate>;
+
+        /// Who/where to send the protocol fees by default,
+        /// can be changed by `set_protocol_fee_receiver`
         #[pallet::constant]
         type ProtocolFeeReceiver: Get<Self::AccountId>;
 
@@ -230,6 +343,10 @@ pub mod pallet {
         /// The asset id for native currency.
         #[pallet::constant]
         type NativeCurrency: Get<AssetIdOf<Self>>;
+
+        /// Notified whenever the exchange rate changes, e.g. to let loans
+        /// re-evaluate positions using the liquid currency as collateral
+        type ExchangeRateChangeHandler: ExchangeRateChangeHandler<AssetIdOf<Self>>;
     }
 
     #[pallet::event]
@@ -263,7 +380,8 @@ pub mod pallet {
         /// Reserve_factor was updated
         ReserveFactorUpdated(Ratio),
         /// Exchange rate was updated
-        ExchangeRateUpdated(Rate),
+        /// [exchange_rate, slashing_discount]
+        ExchangeRateUpdated(Rate, BalanceOf<T>),
         /// Notification received
         /// [multi_location, query_id, res]
         NotificationReceived(Box<MultiLocation>, QueryId, Option<(u32, XcmError)>),
@@ -280,6 +398,9 @@ pub mod pallet {
         /// Event emitted when the reserves are reduced
         /// [receiver, reduced_amount]
         ReservesReduced(T::AccountId, BalanceOf<T>),
+        /// Event emitted when the reserves are accrued from a stake
+        /// [accrued_amount]
+        ReservesAccrued(BalanceOf<T>),
         /// Unstake cancelled
         /// [account_id, amount, liquid_amount]
         UnstakeCancelled(T::AccountId, BalanceOf<T>, BalanceOf<T>),
@@ -292,6 +413,103 @@ pub mod pallet {
         IncentiveUpdated(BalanceOf<T>),
         /// Not the ideal staking ledger
         NonIdealStakingLedger(DerivativeIndex),
+        /// Bond/unbond distribution below `MinXcmBondAmount` was skipped
+        /// [derivative_index, amount]
+        DustBondSkipped(DerivativeIndex, BalanceOf<T>),
+        /// Payee override was updated
+        /// [derivative_index, payee]
+        PayeeOverrideUpdated(DerivativeIndex, Option<RewardDestination<T::AccountId>>),
+        /// Xcm request was force completed by governance
+        /// [query_id, assume_success]
+        XcmRequestForceCompleted(QueryId, bool),
+        /// Per index minimum bond was updated
+        /// [derivative_index, min_bond]
+        PerIndexMinBondUpdated(DerivativeIndex, Option<BalanceOf<T>>),
+        /// Staked and supplied the liquid currency to loans as collateral
+        /// [account_id, staking_amount, liquid_amount]
+        StakedAndCollateralized(T::AccountId, BalanceOf<T>, BalanceOf<T>),
+        /// Commission rate of a reward source was updated
+        /// [reward_source, commission_rate]
+        SourceCommissionRateUpdated(RewardSource, Option<Rate>),
+        /// Stale xcm request was pruned
+        /// [query_id]
+        StaleXcmRequestPruned(QueryId),
+        /// Nomination was not sent because too many targets changed
+        /// [derivative_index, changed_targets]
+        NominationChurnRejected(DerivativeIndex, u32),
+        /// Reward snapshots were enabled or disabled for an account
+        /// [account_id, enabled]
+        RewardSnapshotsToggled(T::AccountId, bool),
+        /// Default nomination targets were updated
+        /// [targets]
+        DefaultNominationTargetsUpdated(Vec<T::AccountId>),
+        /// Era advance was clamped to `MaxEraAdvancePerBlock`
+        /// [requested_offset, applied_offset]
+        EraAdvanceClamped(EraIndex, EraIndex),
+        /// Auto matching of a fast unstake request was enabled or disabled
+        /// [account_id, auto_match]
+        AutoMatchUpdated(T::AccountId, bool),
+        /// Incentive budget of the era is spent, no incentive is paid
+        /// [era_index]
+        IncentiveBudgetExhausted(EraIndex),
+        /// Slashing spans of derivative index were updated
+        /// [derivative_index, num_slashing_spans]
+        SlashingSpansUpdated(DerivativeIndex, Option<u32>),
+        /// Reserves were moved into matching pool as protocol owned stake
+        /// [staked_amount]
+        ReservesStaked(BalanceOf<T>),
+        /// Matching pool was matched again before the era ends
+        /// [era_index, round]
+        IntraEraMatching(EraIndex, u32),
+        /// Bond was deferred to next block as `MaxXcmPerBlock` was reached
+        /// [derivative_index, amount]
+        XcmDeferred(DerivativeIndex, BalanceOf<T>),
+        /// A step of on_initialize failed, all of its changes were rolled back
+        /// [step, error]
+        OnInitializeFailed(OnInitializeStep, DispatchError),
+        /// Economic parameters were updated at once
+        /// [params]
+        ParametersUpdated(ParametersUpdate<BalanceOf<T>>),
+        /// Fast unstake request expired and was removed without matching
+        /// [unstaker, liquid_amount]
+        FastUnstakeExpired(T::AccountId, BalanceOf<T>),
+        /// Matching pool locks were recomputed from the pending xcm requests
+        /// [stake_amount_lock, unstake_amount_lock]
+        MatchingLocksRepaired(BalanceOf<T>, BalanceOf<T>),
+        /// Commission was minted to the protocol fee receiver from staking ledger rewards
+        /// [derivative_index, rewards, inflate_liquid_amount]
+        CommissionMinted(DerivativeIndex, BalanceOf<T>, BalanceOf<T>),
+        /// Large bond was split, the deferred part is left in matching pool
+        /// [derivative_index, bonded_amount, deferred_amount]
+        BondSplit(DerivativeIndex, BalanceOf<T>, BalanceOf<T>),
+        /// Protocol fee receiver was updated
+        /// [receiver]
+        ProtocolFeeReceiverUpdated(T::AccountId),
+        /// Matured unlock chunks were consolidated manually
+        /// [derivative_index, amount]
+        LedgerConsolidated(DerivativeIndex, BalanceOf<T>),
+        /// Exchange rate set at genesis, emitted at the first block
+        /// [exchange_rate]
+        ExchangeRateInitialized(Rate),
+        /// Event emitted when the reserves are topped up
+        /// [payer, added_amount]
+        ReservesAdded(T::AccountId, BalanceOf<T>),
+        /// Sent staking.nominate call to relaychain for every bonded index
+        /// [targets, index_count]
+        NominatingAll(Vec<T::AccountId>, u32),
+        /// Sent staking.set_payee call to relaychain
+        /// [derivative_index, payee]
+        PayeeUpdated(DerivativeIndex, RewardDestination<T::AccountId>),
+        /// Slash of a relaychain ledger was proven and applied
+        /// [derivative_index, slashed_amount, exchange_rate]
+        SlashReported(DerivativeIndex, BalanceOf<T>, Rate),
+        /// Liquid currency issuance dropped to zero, exchange rate was reset and
+        /// the residual backing moved into protocol owned stake
+        /// [exchange_rate, residual]
+        ExchangeRateReset(Rate, BalanceOf<T>),
+        /// A deferred bond failed and was dropped, the amount stays in matching pool
+        /// [derivative_index, amount, error]
+        DeferredBondFailed(DerivativeIndex, BalanceOf<T>, DispatchError),
     }
 
     #[pallet::error]
@@ -333,10 +551,42 @@ pub mod pallet {
         InsufficientBond,
         /// The merkle proof is invalid
         InvalidProof,
+        /// No relaychain validation data to verify the merkle proof against
+        NoValidationData,
+        /// The merkle proof doesn't contain the storage key
+        ProofKeyNotFound,
+        /// The value in the merkle proof doesn't match the provided one
+        ProofValueMismatch,
         /// No unlocking items
         NoUnlockings,
         /// Invalid commission rate
         InvalidCommissionRate,
+        /// Xcm request doesn't exist
+        XcmRequestNotFound,
+        /// Decimal of the staking or liquid currency is not registered
+        CurrencyDecimalNotRegistered,
+        /// Pending unstake exceeds the bonded and matching pool stake
+        InsufficientBonded,
+        /// No default nomination targets were set
+        NoDefaultNominationTargets,
+        /// Unstake fee is higher than the accepted `max_fee`
+        FeeTooHigh,
+        /// No fast unstake request found for the account
+        NoFastUnstakeRequest,
+        /// A beneficiary can only be set when unstaking through relaychain
+        BeneficiaryNotSupported,
+        /// Rewards can't be paid to this account
+        InvalidRewardDestination,
+        /// Too many unstakers passed in one call, exceeding `MaxUnstakersPerCall`
+        TooManyUnstakers,
+        /// No matured unlock chunks to consolidate
+        NothingToConsolidate,
+        /// Era was already advanced in this block
+        EraAlreadyAdvanced,
+        /// Proven ledger is not a slash of the stored ledger
+        InvalidSlashAmount,
+        /// Nomination targets are empty or contain duplicates
+        InvalidNominationTargets,
     }
 
     /// The exchange rate between relaychain native asset and the voucher.
@@ -349,6 +599,12 @@ pub mod pallet {
     #[pallet::getter(fn commission_rate)]
     pub type CommissionRate<T: Config> = StorageValue<_, Rate, ValueQuery>;
 
+    /// Commission rate per reward source, `CommissionRate` is used if not set.
+    #[pallet::storage]
+    #[pallet::getter(fn source_commission_rate)]
+    pub type SourceCommissionRates<T: Config> =
+        StorageMap<_, Twox64Concat, RewardSource, Rate, OptionQuery>;
+
     /// ValidationData of previous block
     ///
     /// This is needed since validation data from cumulus_pallet_parachain_system
@@ -367,6 +623,11 @@ pub mod pallet {
     #[pallet::getter(fn total_reserves)]
     pub type TotalReserves<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
 
+    /// Reserves staked by the protocol, which back no liquid currency
+    #[pallet::storage]
+    #[pallet::getter(fn protocol_owned_stake)]
+    pub type ProtocolOwnedStake<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
     /// Store total stake amount and unstake amount in each era,
     /// And will update when stake/unstake occurred.
     #[pallet::storage]
@@ -383,11 +644,17 @@ pub mod pallet {
     #[pallet::getter(fn xcm_request)]
     pub type XcmRequests<T> = StorageMap<_, Blake2_128Concat, QueryId, XcmRequest<T>, OptionQuery>;
 
+    /// Era and relaychain block number at which each pending xcm request was sent
+    #[pallet::storage]
+    #[pallet::getter(fn xcm_request_created)]
+    pub type XcmRequestCreated<T> =
+        StorageMap<_, Blake2_128Concat, QueryId, (EraIndex, BlockNumberFor<T>), OptionQuery>;
+
     /// Users' fast unstake requests in liquid currency
     #[pallet::storage]
     #[pallet::getter(fn fast_unstake_requests)]
     pub type FastUnstakeRequests<T: Config> =
-        StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+        StorageMap<_, Blake2_128Concat, T::AccountId, FastUnstakeRequest<BalanceOf<T>>, ValueQuery>;
 
     /// Current era index
     /// Users can come to claim their unbonded staking assets back once this value arrived
@@ -445,16 +712,153 @@ pub mod pallet {
     #[pallet::getter(fn incentive)]
     pub type Incentive<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
 
-    #[derive(Default)]
+    /// Incentive paid to relayers in current era, reset on each new era
+    #[pallet::storage]
+    #[pallet::getter(fn incentive_spent)]
+    pub type IncentiveSpent<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+    /// Relaychain block of the last matching
+    #[pallet::storage]
+    #[pallet::getter(fn last_matching_block)]
+    pub type LastMatchingBlock<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+    /// Extra matching rounds done in current era
+    /// clear after arriving at next era
+    #[pallet::storage]
+    #[pallet::getter(fn matching_round)]
+    pub type MatchingRound<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Reward destination used instead of the one passed to `bond`
+    #[pallet::storage]
+    #[pallet::getter(fn payee_override)]
+    pub type PayeeOverrides<T: Config> =
+        StorageMap<_, Twox64Concat, DerivativeIndex, RewardDestination<T::AccountId>, OptionQuery>;
+
+    /// Minimum active bond kept on a derivative index when unbonding,
+    /// `MinNominatorBond` is used if it's higher
+    #[pallet::storage]
+    #[pallet::getter(fn per_index_min_bond)]
+    pub type PerIndexMinBond<T: Config> =
+        StorageMap<_, Twox64Concat, DerivativeIndex, BalanceOf<T>, OptionQuery>;
+
+    /// Slashing spans of each derivative index on relaychain,
+    /// `NumSlashingSpans` is used if it's not set
+    #[pallet::storage]
+    #[pallet::getter(fn slashing_spans)]
+    pub type SlashingSpans<T: Config> =
+        StorageMap<_, Twox64Concat, DerivativeIndex, u32, OptionQuery>;
+
+    /// Number of xcm requests sent in current block
+    /// clear after the block is finalized
+    #[pallet::storage]
+    #[pallet::getter(fn xcm_sent_in_block)]
+    pub type XcmSentInBlock<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Stake and unstake amounts matched in each era
+    /// pruned after `HistoryDepth` eras
+    #[pallet::storage]
+    #[pallet::getter(fn era_net_flow)]
+    pub type EraNetFlow<T: Config> =
+        StorageMap<_, Twox64Concat, EraIndex, (BalanceOf<T>, BalanceOf<T>), ValueQuery>;
+
+    /// Exchange rate at the start of each era
+    /// pruned after `HistoryDepth` eras
+    #[pallet::storage]
+    #[pallet::getter(fn era_exchange_rate)]
+    pub type EraExchangeRates<T: Config> = StorageMap<_, Twox64Concat, EraIndex, Rate, OptionQuery>;
+
+    /// Block in which the era was last advanced by `on_initialize` or `force_advance_era`
+    #[pallet::storage]
+    #[pallet::getter(fn last_era_advanced_block)]
+    pub type LastEraAdvancedBlock<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+    /// Era from which the per era history has not been pruned yet
+    #[pallet::storage]
+    #[pallet::getter(fn history_pruned_era)]
+    pub type HistoryPrunedEra<T: Config> = StorageValue<_, EraIndex, OptionQuery>;
+
+    /// Bonds left free in matching pool to be sent in next blocks, with their payee
+    #[pallet::storage]
+    #[pallet::getter(fn deferred_bonds)]
+    pub type DeferredBonds<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        DerivativeIndex,
+        (BalanceOf<T>, RewardDestination<T::AccountId>),
+        OptionQuery,
+    >;
+
+    /// Exchange rate at each era an opted in account staked or unstaked,
+    /// at most `MAX_REWARD_SNAPSHOTS` latest ones are kept
+    #[pallet::storage]
+    #[pallet::getter(fn reward_snapshots)]
+    pub type RewardSnapshots<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Vec<(EraIndex, Rate)>, OptionQuery>;
+
+    /// Last nomination targets confirmed by relaychain for each derivative index
+    #[pallet::storage]
+    #[pallet::getter(fn nominations)]
+    pub type Nominations<T: Config> =
+        StorageMap<_, Twox64Concat, DerivativeIndex, Vec<T::AccountId>, OptionQuery>;
+
+    /// Reward destination each derivative index was bonded with, updated
+    /// once relaychain confirms a set_payee
+    #[pallet::storage]
+    #[pallet::getter(fn payee)]
+    pub type Payees<T: Config> =
+        StorageMap<_, Twox64Concat, DerivativeIndex, RewardDestination<T::AccountId>, OptionQuery>;
+
+    /// Nomination targets applied by `nominate_default`
+    #[pallet::storage]
+    #[pallet::getter(fn default_nomination_targets)]
+    pub type DefaultNominationTargets<T: Config> =
+        StorageValue<_, BoundedVec<T::AccountId, T::MaxNominations>, ValueQuery>;
+
+    /// Amount borrowed from loans by fast unstakes that is not yet repaid
+    #[pallet::storage]
+    #[pallet::getter(fn loans_outstanding_borrow)]
+    pub type LoansOutstandingBorrow<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+    /// DefaultFeeReceiver is using for initialize the ProtocolFeeReceiver
+    #[pallet::type_value]
+    pub(super) fn DefaultFeeReceiver<T: Config>() -> T::AccountId {
+        T::ProtocolFeeReceiver::get()
+    }
+
+    /// Who/where to send the protocol fees
+    #[pallet::storage]
+    #[pallet::getter(fn protocol_fee_receiver)]
+    pub type ProtocolFeeReceiver<T: Config> =
+        StorageValue<_, T::AccountId, ValueQuery, DefaultFeeReceiver<T>>;
+
+    /// Commission accumulated below `MinCommissionMint`, not minted yet
+    #[pallet::storage]
+    #[pallet::getter(fn pending_commission)]
+    pub type PendingCommission<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig {
         pub exchange_rate: Rate,
         pub reserve_factor: Ratio,
     }
 
+    #[cfg(feature = "std")]
+    impl Default for GenesisConfig {
+        fn default() -> Self {
+            Self {
+                exchange_rate: Rate::one(),
+                reserve_factor: Default::default(),
+            }
+        }
+    }
+
     #[pallet::genesis_build]
     impl<T: Config> GenesisBuild<T> for GenesisConfig {
         fn build(&self) {
+            assert!(
+                !self.exchange_rate.is_zero(),
+                "LiquidStaking: genesis exchange_rate must be non-zero, staking would always fail"
+            );
             ExchangeRate::<T>::put(self.exchange_rate);
             ReserveFactor::<T>::put(self.reserve_factor);
         }
@@ -475,49 +879,7 @@ pub mod pallet {
             #[pallet::compact] amount: BalanceOf<T>,
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
-
-            ensure!(amount >= T::MinStake::get(), Error::<T>::StakeTooSmall);
-
-            let reserves = Self::reserve_factor().mul_floor(amount);
-
-            let xcm_fees = T::XcmFees::get();
-            let amount = amount
-                .checked_sub(xcm_fees)
-                .ok_or(ArithmeticError::Underflow)?;
-            T::Assets::transfer(
-                Self::staking_currency()?,
-                &who,
-                &Self::account_id(),
-                amount,
-                false,
-            )?;
-            T::XCM::add_xcm_fees(&who, xcm_fees)?;
-
-            let amount = amount
-                .checked_sub(reserves)
-                .ok_or(ArithmeticError::Underflow)?;
-            let liquid_amount =
-                Self::staking_to_liquid(amount).ok_or(Error::<T>::InvalidExchangeRate)?;
-            let liquid_currency = Self::liquid_currency()?;
-            Self::ensure_market_cap(amount)?;
-
-            T::Assets::mint_into(liquid_currency, &who, liquid_amount)?;
-
-            log::trace!(
-                target: "liquidStaking::stake",
-                "stake_amount: {:?}, liquid_amount: {:?}, reserved: {:?}",
-                &amount,
-                &liquid_amount,
-                &reserves
-            );
-
-            MatchingPool::<T>::try_mutate(|p| -> DispatchResult { p.add_stake_amount(amount) })?;
-            TotalReserves::<T>::try_mutate(|b| -> DispatchResult {
-                *b = b.checked_add(reserves).ok_or(ArithmeticError::Overflow)?;
-                Ok(())
-            })?;
-
-            Self::deposit_event(Event::<T>::Staked(who, amount));
+            Self::do_stake(&who, amount)?;
             Ok(().into())
         }
 
@@ -526,6 +888,10 @@ pub mod pallet {
         /// chain to do the `unbond` operation.
         ///
         /// - `amount`: the amount of derivative
+        /// - `min_rate`: lowest exchange rate accepted when matched through the matching pool
+        /// - `max_fee`: highest fee accepted when unstaking through the matching pool or loans
+        /// - `beneficiary`: account paid by `claim_for` when unstaking through relaychain,
+        /// the caller if not set
         #[pallet::call_index(1)]
         #[pallet::weight(<T as Config>::WeightInfo::unstake())]
         #[transactional]
@@ -533,6 +899,9 @@ pub mod pallet {
             origin: OriginFor<T>,
             #[pallet::compact] liquid_amount: BalanceOf<T>,
             unstake_provider: UnstakeProvider,
+            min_rate: Option<Rate>,
+            max_fee: Option<Rate>,
+            beneficiary: Option<<T::Lookup as StaticLookup>::Source>,
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
 
@@ -540,12 +909,43 @@ pub mod pallet {
                 liquid_amount >= T::MinUnstake::get(),
                 Error::<T>::UnstakeTooSmall
             );
+            ensure!(
+                beneficiary.is_none() || unstake_provider.is_relay_chain(),
+                Error::<T>::BeneficiaryNotSupported
+            );
+
+            if let Some(max_fee) = max_fee {
+                let fee = if unstake_provider.is_matching_pool() {
+                    T::MatchingPoolFastUnstakeFee::get()
+                } else if unstake_provider.is_loans() {
+                    T::LoansInstantUnstakeFee::get()
+                } else {
+                    Rate::zero()
+                };
+                ensure!(fee <= max_fee, Error::<T>::FeeTooHigh);
+            }
+
+            Self::do_record_reward_snapshot(&who);
+
+            // the remaining dust is rounded into this unstake
+            let liquid_balance =
+                T::Assets::reducible_balance(Self::liquid_currency()?, &who, false);
+            let residual = liquid_balance.saturating_sub(liquid_amount);
+            let liquid_amount = if !residual.is_zero() && residual < T::DustThreshold::get() {
+                liquid_amount.saturating_add(residual)
+            } else {
+                liquid_amount
+            };
 
             if unstake_provider.is_matching_pool() {
                 FastUnstakeRequests::<T>::try_mutate(&who, |b| -> DispatchResult {
                     let balance =
                         T::Assets::reducible_balance(Self::liquid_currency()?, &who, false);
-                    *b = b.saturating_add(liquid_amount).min(balance);
+                    b.amount = b.amount.saturating_add(liquid_amount).min(balance);
+                    b.min_rate = min_rate;
+                    let lifetime = T::FastUnstakeRequestLifetime::get();
+                    b.expiry_era =
+                        (!lifetime.is_zero()).then(|| Self::current_era().saturating_add(lifetime));
                     Ok(())
                 })?;
                 return Ok(().into());
@@ -555,6 +955,8 @@ pub mod pallet {
                 Self::liquid_to_staking(liquid_amount).ok_or(Error::<T>::InvalidExchangeRate)?;
             let unlockings_key = if unstake_provider.is_loans() {
                 Self::loans_account_id()
+            } else if let Some(beneficiary) = beneficiary {
+                T::Lookup::lookup(beneficiary)?
             } else {
                 who.clone()
             };
@@ -585,6 +987,7 @@ pub mod pallet {
             }
 
             MatchingPool::<T>::try_mutate(|p| p.add_unstake_amount(amount))?;
+            Self::ensure_unstake_realizable()?;
 
             log::trace!(
                 target: "liquidStaking::unstake",
@@ -768,48 +1171,7 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             Self::ensure_origin(origin)?;
             let who = T::Lookup::lookup(dest)?;
-            let current_era = Self::current_era();
-
-            Unlockings::<T>::try_mutate_exists(&who, |b| -> DispatchResult {
-                let mut amount: BalanceOf<T> = Zero::zero();
-                let chunks = b.as_mut().ok_or(Error::<T>::NoUnlockings)?;
-                chunks.retain(|chunk| {
-                    if chunk.era > current_era {
-                        true
-                    } else {
-                        amount += chunk.value;
-                        false
-                    }
-                });
-
-                let total_unclaimed = Self::get_total_unclaimed(Self::staking_currency()?);
-
-                log::trace!(
-                    target: "liquidStaking::claim_for",
-                    "current_era: {:?}, beneficiary: {:?}, total_unclaimed: {:?}, amount: {:?}",
-                    &current_era,
-                    &who,
-                    &total_unclaimed,
-                    amount
-                );
-
-                if amount.is_zero() {
-                    return Err(Error::<T>::NothingToClaim.into());
-                }
-
-                if total_unclaimed < amount {
-                    return Err(Error::<T>::NotWithdrawn.into());
-                }
-
-                Self::do_claim_for(&who, amount)?;
-
-                if chunks.is_empty() {
-                    *b = None;
-                }
-
-                Self::deposit_event(Event::<T>::ClaimedFor(who.clone(), amount));
-                Ok(())
-            })?;
+            Self::do_claim_matured(&who)?;
             Ok(().into())
         }
 
@@ -847,6 +1209,7 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             T::UpdateOrigin::ensure_origin(origin)?;
 
+            Self::note_era_advance(frame_system::Pallet::<T>::block_number())?;
             Self::do_advance_era(offset)?;
 
             Ok(().into())
@@ -897,26 +1260,18 @@ pub mod pallet {
             era: EraIndex,
             proof: Vec<Vec<u8>>,
         ) -> DispatchResultWithPostInfo {
-            let who = ensure_signed(origin)?;
+            let who = Self::ensure_proof_submitter(origin)?;
 
             let offset = era.saturating_sub(Self::current_era());
 
             let key = Self::get_current_era_key();
             let value = era.encode();
-            ensure!(
-                Self::verify_merkle_proof(key, value, proof),
-                Error::<T>::InvalidProof
-            );
+            Self::verify_merkle_proof(key, value, proof).map_err(Error::<T>::from)?;
 
-            Self::do_advance_era(offset)?;
             if !offset.is_zero() {
-                let _ = T::Assets::transfer(
-                    T::NativeCurrency::get(),
-                    &Self::account_id(),
-                    &who,
-                    Self::incentive(),
-                    false,
-                );
+                Self::note_era_advance(frame_system::Pallet::<T>::block_number())?;
+                Self::do_advance_era(offset)?;
+                Self::do_pay_incentive(&who);
             }
 
             Ok(().into())
@@ -932,7 +1287,7 @@ pub mod pallet {
             staking_ledger: StakingLedger<T::AccountId, BalanceOf<T>>,
             proof: Vec<Vec<u8>>,
         ) -> DispatchResultWithPostInfo {
-            let who = ensure_signed(origin)?;
+            let who = Self::ensure_proof_submitter(origin)?;
 
             Self::do_update_ledger(derivative_index, |ledger| {
                 ensure!(
@@ -956,19 +1311,26 @@ pub mod pallet {
                 }
                 let key = Self::get_staking_ledger_key(derivative_index);
                 let value = staking_ledger.encode();
-                ensure!(
-                    Self::verify_merkle_proof(key, value, proof),
-                    Error::<T>::InvalidProof
-                );
+                Self::verify_merkle_proof(key, value, proof).map_err(Error::<T>::from)?;
                 let rewards = staking_ledger.total.saturating_sub(ledger.total);
 
-                let inflate_liquid_amount = Self::get_inflate_liquid_amount(rewards)?;
-                if !inflate_liquid_amount.is_zero() {
+                let inflate_liquid_amount =
+                    Self::get_inflate_liquid_amount(rewards, RewardSource::RelayStaking)?;
+                let commission = Self::pending_commission().saturating_add(inflate_liquid_amount);
+                if !commission.is_zero() && commission >= T::MinCommissionMint::get() {
                     T::Assets::mint_into(
                         Self::liquid_currency()?,
-                        &T::ProtocolFeeReceiver::get(),
-                        inflate_liquid_amount,
+                        &Self::protocol_fee_receiver(),
+                        commission,
                     )?;
+                    PendingCommission::<T>::kill();
+                    Self::deposit_event(Event::<T>::CommissionMinted(
+                        derivative_index,
+                        rewards,
+                        commission,
+                    ));
+                } else if !inflate_liquid_amount.is_zero() {
+                    PendingCommission::<T>::put(commission);
                 }
 
                 log::trace!(
@@ -978,13 +1340,7 @@ pub mod pallet {
                     &staking_ledger,
                     inflate_liquid_amount,
                 );
-                let _ = T::Assets::transfer(
-                    T::NativeCurrency::get(),
-                    &Self::account_id(),
-                    &who,
-                    Self::incentive(),
-                    false,
-                );
+                Self::do_pay_incentive(&who);
                 *ledger = staking_ledger;
                 Ok(())
             })?;
@@ -1034,9 +1390,14 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
 
-            FastUnstakeRequests::<T>::try_mutate(&who, |b| -> DispatchResultWithPostInfo {
+            FastUnstakeRequests::<T>::try_mutate_exists(&who, |b| -> DispatchResultWithPostInfo {
+                let mut request = b.take().unwrap_or_default();
                 let balance = T::Assets::reducible_balance(Self::liquid_currency()?, &who, false);
-                *b = (*b).min(balance).saturating_sub(amount);
+                request.amount = request.amount.min(balance).saturating_sub(amount);
+                // drop the request once fully cancelled so its settings don't linger
+                if !request.amount.is_zero() {
+                    *b = Some(request);
+                }
 
                 // reserve two amounts in event
                 Self::deposit_event(Event::<T>::UnstakeCancelled(who.clone(), amount, amount));
@@ -1080,8 +1441,12 @@ pub mod pallet {
             unstaker_list: Vec<T::AccountId>,
         ) -> DispatchResult {
             Self::ensure_origin(origin)?;
+            ensure!(
+                unstaker_list.len() as u32 <= T::MaxUnstakersPerCall::get(),
+                Error::<T>::TooManyUnstakers
+            );
             for unstaker in unstaker_list {
-                Self::do_fast_match_unstake(&unstaker)?;
+                Self::do_fast_match_unstake(&unstaker, false)?;
             }
             Ok(())
         }
@@ -1099,53 +1464,733 @@ pub mod pallet {
             Self::deposit_event(Event::<T>::IncentiveUpdated(amount));
             Ok(())
         }
-    }
-
-    #[pallet::hooks]
-    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
-        fn on_initialize(_block_number: T::BlockNumber) -> frame_support::weights::Weight {
-            let mut weight = <T as Config>::WeightInfo::on_initialize();
-            let relaychain_block_number =
-                T::RelayChainValidationDataProvider::current_block_number();
-            let mut do_on_initialize = || -> DispatchResult {
-                if !Self::is_matched()
-                    && T::ElectionSolutionStoredOffset::get()
-                        .saturating_add(Self::era_start_block())
-                        <= relaychain_block_number
-                {
-                    weight += <T as Config>::WeightInfo::force_matching();
-                    Self::do_matching()?;
-                }
-
-                let offset = Self::offset(relaychain_block_number);
-                if offset.is_zero() {
-                    return Ok(());
-                }
-                weight += <T as Config>::WeightInfo::force_advance_era();
-                Self::do_advance_era(offset)
-            };
-            let _ = with_transaction(|| match do_on_initialize() {
-                Ok(()) => TransactionOutcome::Commit(Ok(())),
-                Err(err) => TransactionOutcome::Rollback(Err(err)),
-            });
-            weight
-        }
 
-        fn on_finalize(_n: T::BlockNumber) {
-            let _ = IsUpdated::<T>::clear(u32::max_value(), None);
-            if let Some(data) = T::RelayChainValidationDataProvider::validation_data() {
-                ValidationData::<T>::put(data);
+        /// Set or clear the reward destination override of a derivative index.
+        ///
+        /// The override is used on the initial bond. Bonding more to an already
+        /// bonded index sends `set_payee` first if the payee differs, unless a
+        /// `set_payee` request for that index is still pending.
+        #[pallet::call_index(24)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_payee_override())]
+        #[transactional]
+        pub fn set_payee_override(
+            origin: OriginFor<T>,
+            derivative_index: DerivativeIndex,
+            payee: Option<RewardDestination<T::AccountId>>,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            ensure!(
+                T::DerivativeIndexList::get().contains(&derivative_index),
+                Error::<T>::InvalidDerivativeIndex
+            );
+            if let Some(payee) = &payee {
+                Self::ensure_valid_payee(payee)?;
             }
+            PayeeOverrides::<T>::set(derivative_index, payee.clone());
+            Self::deposit_event(Event::<T>::PayeeOverrideUpdated(derivative_index, payee));
+            Ok(())
         }
-    }
 
-    impl<T: Config> Pallet<T> {
-        /// Staking pool account
-        pub fn account_id() -> T::AccountId {
-            T::PalletId::get().into_account_truncating()
-        }
+        /// Force complete a pending xcm request whose response was lost.
+        ///
+        /// If `assume_success` is true the request is settled as if the relaychain
+        /// executed it, otherwise the matching pool locks are released and the
+        /// request is dropped.
+        #[pallet::call_index(25)]
+        #[pallet::weight(<T as Config>::WeightInfo::notification_received())]
+        #[transactional]
+        pub fn force_complete_xcm_request(
+            origin: OriginFor<T>,
+            query_id: QueryId,
+            assume_success: bool,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            let request = Self::xcm_request(query_id).ok_or(Error::<T>::XcmRequestNotFound)?;
 
-        /// Loans pool account
+            log::warn!(
+                target: "liquidStaking::force_complete_xcm_request",
+                "query_id: {:?}, assume_success: {:?}",
+                &query_id,
+                &assume_success,
+            );
+
+            if assume_success {
+                Self::do_notification_received(query_id, request, None)?;
+            } else {
+                Self::do_discard_xcm_request(query_id, request)?;
+            }
+
+            Self::deposit_event(Event::<T>::XcmRequestForceCompleted(
+                query_id,
+                assume_success,
+            ));
+            Ok(())
+        }
+
+        /// Set or clear the minimum active bond of a derivative index
+        #[pallet::call_index(26)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_per_index_min_bond())]
+        #[transactional]
+        pub fn set_per_index_min_bond(
+            origin: OriginFor<T>,
+            derivative_index: DerivativeIndex,
+            min_bond: Option<BalanceOf<T>>,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            ensure!(
+                T::DerivativeIndexList::get().contains(&derivative_index),
+                Error::<T>::InvalidDerivativeIndex
+            );
+            PerIndexMinBond::<T>::set(derivative_index, min_bond);
+            Self::deposit_event(Event::<T>::PerIndexMinBondUpdated(
+                derivative_index,
+                min_bond,
+            ));
+            Ok(())
+        }
+
+        /// Stake and supply the received derivative to loans as collateral.
+        ///
+        /// - `amount`: the amount of staking assets
+        #[pallet::call_index(27)]
+        #[pallet::weight(<T as Config>::WeightInfo::stake_and_collateralize())]
+        #[transactional]
+        pub fn stake_and_collateralize(
+            origin: OriginFor<T>,
+            #[pallet::compact] amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let liquid_amount = Self::do_stake(&who, amount)?;
+            let liquid_currency = Self::liquid_currency()?;
+
+            T::Loans::do_mint(&who, liquid_currency, liquid_amount)?;
+            T::Loans::do_collateral_asset(&who, liquid_currency, true)?;
+
+            Self::deposit_event(Event::<T>::StakedAndCollateralized(
+                who,
+                amount,
+                liquid_amount,
+            ));
+            Ok(().into())
+        }
+
+        /// Set or clear the commission rate of a reward source
+        #[pallet::call_index(28)]
+        #[pallet::weight(<T as Config>::WeightInfo::update_source_commission_rate())]
+        #[transactional]
+        pub fn update_source_commission_rate(
+            origin: OriginFor<T>,
+            reward_source: RewardSource,
+            commission_rate: Option<Rate>,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+
+            ensure!(
+                commission_rate.map_or(true, |rate| rate > Rate::zero() && rate < Rate::one()),
+                Error::<T>::InvalidCommissionRate,
+            );
+
+            SourceCommissionRates::<T>::set(reward_source, commission_rate);
+            Self::deposit_event(Event::<T>::SourceCommissionRateUpdated(
+                reward_source,
+                commission_rate,
+            ));
+            Ok(())
+        }
+
+        /// Prune the given xcm requests if they were sent at least
+        /// `older_than_blocks` relaychain blocks ago, releasing their matching
+        /// pool locks. Requests that are unknown or not stale yet are skipped.
+        ///
+        /// Requests without a recorded creation block are treated as stale.
+        #[pallet::call_index(29)]
+        #[pallet::weight(<T as Config>::WeightInfo::prune_stale_xcm_requests(query_ids.len() as u32))]
+        #[transactional]
+        pub fn prune_stale_xcm_requests(
+            origin: OriginFor<T>,
+            query_ids: Vec<QueryId>,
+            older_than_blocks: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+
+            let relaychain_block_number =
+                T::RelayChainValidationDataProvider::current_block_number();
+            for query_id in query_ids {
+                let request = match Self::xcm_request(query_id) {
+                    Some(request) => request,
+                    None => continue,
+                };
+                if Self::xcm_request_created(query_id)
+                    .map_or_else(Zero::zero, |(_, created_at)| created_at)
+                    .saturating_add(older_than_blocks)
+                    > relaychain_block_number
+                {
+                    continue;
+                }
+                log::warn!(
+                    target: "liquidStaking::prune_stale_xcm_requests",
+                    "query_id: {:?}",
+                    &query_id,
+                );
+                Self::do_discard_xcm_request(query_id, request)?;
+                Self::deposit_event(Event::<T>::StaleXcmRequestPruned(query_id));
+            }
+
+            Ok(())
+        }
+
+        /// Force set the exchange rate, which must stay within
+        /// `MaxExchangeRateDeviation` of the current one.
+        #[pallet::call_index(30)]
+        #[pallet::weight(<T as Config>::WeightInfo::force_set_exchange_rate())]
+        #[transactional]
+        pub fn force_set_exchange_rate(origin: OriginFor<T>, rate: Rate) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+
+            let current_rate = Self::exchange_rate();
+            let max_deviation = Rate::from_inner(
+                T::MaxExchangeRateDeviation::get().mul_floor(current_rate.into_inner()),
+            );
+            ensure!(
+                !rate.is_zero()
+                    && rate >= current_rate.saturating_sub(max_deviation)
+                    && rate <= current_rate.saturating_add(max_deviation),
+                Error::<T>::InvalidExchangeRate
+            );
+
+            log::trace!(
+                target: "liquidStaking::force_set_exchange_rate",
+                "current_rate: {:?}, rate: {:?}",
+                &current_rate,
+                &rate,
+            );
+
+            ExchangeRate::<T>::put(rate);
+            Self::deposit_event(Event::<T>::ExchangeRateUpdated(rate, Zero::zero()));
+            T::ExchangeRateChangeHandler::on_exchange_rate_changed(&Self::liquid_currency()?, rate);
+            Ok(())
+        }
+
+        /// Opt in or out of recording the exchange rate each era the caller
+        /// stakes or unstakes. Opting out drops the recorded snapshots.
+        #[pallet::call_index(31)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_reward_snapshots())]
+        #[transactional]
+        pub fn set_reward_snapshots(origin: OriginFor<T>, enabled: bool) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            if enabled {
+                if !RewardSnapshots::<T>::contains_key(&who) {
+                    RewardSnapshots::<T>::insert(&who, Vec::<(EraIndex, Rate)>::new());
+                    Self::do_record_reward_snapshot(&who);
+                }
+            } else {
+                RewardSnapshots::<T>::remove(&who);
+            }
+
+            Self::deposit_event(Event::<T>::RewardSnapshotsToggled(who, enabled));
+            Ok(())
+        }
+
+        /// Set the nomination targets used by `nominate_default`
+        #[pallet::call_index(32)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_default_targets(targets.len() as u32))]
+        #[transactional]
+        pub fn set_default_targets(
+            origin: OriginFor<T>,
+            targets: BoundedVec<T::AccountId, T::MaxNominations>,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            ensure!(
+                !targets.is_empty()
+                    && targets
+                        .iter()
+                        .enumerate()
+                        .all(|(i, target)| !targets[..i].contains(target)),
+                Error::<T>::InvalidNominationTargets
+            );
+
+            DefaultNominationTargets::<T>::put(targets.clone());
+            Self::deposit_event(Event::<T>::DefaultNominationTargetsUpdated(
+                targets.into_inner(),
+            ));
+            Ok(())
+        }
+
+        /// Nominate the default targets on relaychain via xcm.transact
+        #[pallet::call_index(33)]
+        #[pallet::weight(<T as Config>::WeightInfo::nominate())]
+        #[transactional]
+        pub fn nominate_default(
+            origin: OriginFor<T>,
+            derivative_index: DerivativeIndex,
+        ) -> DispatchResult {
+            Self::ensure_origin(origin)?;
+
+            let targets = Self::default_nomination_targets();
+            ensure!(!targets.is_empty(), Error::<T>::NoDefaultNominationTargets);
+            Self::do_nominate(derivative_index, targets.into_inner())?;
+            Ok(())
+        }
+
+        /// Allow or disallow `fast_match_unstake` to match the caller's
+        /// pending fast unstake request
+        #[pallet::call_index(34)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_auto_match())]
+        #[transactional]
+        pub fn set_auto_match(origin: OriginFor<T>, auto_match: bool) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            FastUnstakeRequests::<T>::try_mutate_exists(&who, |b| -> DispatchResult {
+                let request = b.as_mut().ok_or(Error::<T>::NoFastUnstakeRequest)?;
+                request.auto_match = auto_match;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::<T>::AutoMatchUpdated(who, auto_match));
+            Ok(())
+        }
+
+        /// Match the caller's own fast unstake request through matching pool,
+        /// regardless of its `auto_match` setting
+        #[pallet::call_index(35)]
+        #[pallet::weight(<T as Config>::WeightInfo::fast_match_unstake(1))]
+        #[transactional]
+        pub fn match_unstake(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::do_fast_match_unstake(&who, true)?;
+            Ok(())
+        }
+
+        /// Stake as much of `amount` as fits under the market cap, the
+        /// remainder stays with the caller
+        #[pallet::call_index(36)]
+        #[pallet::weight(<T as Config>::WeightInfo::stake())]
+        #[transactional]
+        pub fn stake_up_to_cap(
+            origin: OriginFor<T>,
+            #[pallet::compact] amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let accepted_amount = amount.min(Self::max_stake_amount());
+            ensure!(!accepted_amount.is_zero(), Error::<T>::CapExceeded);
+            Self::do_stake(&who, accepted_amount)?;
+            Ok(().into())
+        }
+
+        /// Set or clear the slashing spans of a derivative index
+        #[pallet::call_index(37)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_slashing_spans())]
+        #[transactional]
+        pub fn set_slashing_spans(
+            origin: OriginFor<T>,
+            derivative_index: DerivativeIndex,
+            num_slashing_spans: Option<u32>,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            ensure!(
+                T::DerivativeIndexList::get().contains(&derivative_index),
+                Error::<T>::InvalidDerivativeIndex
+            );
+            SlashingSpans::<T>::set(derivative_index, num_slashing_spans);
+            Self::deposit_event(Event::<T>::SlashingSpansUpdated(
+                derivative_index,
+                num_slashing_spans,
+            ));
+            Ok(())
+        }
+
+        /// Stake up to `amount` of reserves through matching pool, without
+        /// minting liquid currency for it
+        #[pallet::call_index(38)]
+        #[pallet::weight(<T as Config>::WeightInfo::stake_reserves())]
+        #[transactional]
+        pub fn stake_reserves(
+            origin: OriginFor<T>,
+            #[pallet::compact] amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+
+            let amount = amount.min(Self::total_reserves());
+            Self::ensure_market_cap(amount)?;
+
+            TotalReserves::<T>::mutate(|b| *b = b.saturating_sub(amount));
+            MatchingPool::<T>::try_mutate(|p| -> DispatchResult { p.add_stake_amount(amount) })?;
+            ProtocolOwnedStake::<T>::try_mutate(|b| -> DispatchResult {
+                *b = b.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::<T>::ReservesStaked(amount));
+            Ok(())
+        }
+
+        /// Update reserve factor, commission rate, staking ledger cap and incentive
+        /// in one call, fields left as `None` are kept
+        #[pallet::call_index(39)]
+        #[pallet::weight(<T as Config>::WeightInfo::update_parameters())]
+        #[transactional]
+        pub fn update_parameters(
+            origin: OriginFor<T>,
+            params: ParametersUpdate<BalanceOf<T>>,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+
+            if let Some(reserve_factor) = params.reserve_factor {
+                ensure!(
+                    reserve_factor > Ratio::zero() && reserve_factor < Ratio::one(),
+                    Error::<T>::InvalidFactor,
+                );
+                ReserveFactor::<T>::put(reserve_factor);
+            }
+            if let Some(commission_rate) = params.commission_rate {
+                ensure!(
+                    commission_rate > Rate::zero() && commission_rate < Rate::one(),
+                    Error::<T>::InvalidCommissionRate,
+                );
+                CommissionRate::<T>::put(commission_rate);
+            }
+            if let Some(cap) = params.staking_ledger_cap {
+                ensure!(!cap.is_zero(), Error::<T>::InvalidCap);
+                StakingLedgerCap::<T>::put(cap);
+            }
+            if let Some(amount) = params.incentive {
+                Incentive::<T>::put(amount);
+            }
+
+            log::trace!(
+                target: "liquidStaking::update_parameters",
+                "params: {:?}",
+                &params,
+            );
+
+            Self::deposit_event(Event::<T>::ParametersUpdated(params));
+            Ok(())
+        }
+
+        /// Recompute the matching pool locks from the pending xcm requests,
+        /// a recovery tool for when the lock accounting drifts
+        #[pallet::call_index(40)]
+        #[pallet::weight(<T as Config>::WeightInfo::recompute_matching_locks())]
+        #[transactional]
+        pub fn recompute_matching_locks(origin: OriginFor<T>) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+
+            let (stake_amount_lock, unstake_amount_lock) = XcmRequests::<T>::iter_values().fold(
+                (BalanceOf::<T>::zero(), BalanceOf::<T>::zero()),
+                |(stake, unstake), request| match request {
+                    XcmRequest::Bond { amount, .. }
+                    | XcmRequest::BondExtra { amount, .. }
+                    | XcmRequest::Rebond { amount, .. } => (stake.saturating_add(amount), unstake),
+                    XcmRequest::Unbond { amount, .. } => (stake, unstake.saturating_add(amount)),
+                    XcmRequest::WithdrawUnbonded { .. }
+                    | XcmRequest::Nominate { .. }
+                    | XcmRequest::SetPayee { .. } => (stake, unstake),
+                },
+            );
+
+            MatchingPool::<T>::try_mutate(|p| -> DispatchResult {
+                p.total_stake_amount.reserved = Zero::zero();
+                p.total_unstake_amount.reserved = Zero::zero();
+                p.set_stake_amount_lock(stake_amount_lock)?;
+                p.set_unstake_amount_lock(unstake_amount_lock)
+            })?;
+
+            log::trace!(
+                target: "liquidStaking::recompute_matching_locks",
+                "stake_amount_lock: {:?}, unstake_amount_lock: {:?}",
+                &stake_amount_lock,
+                &unstake_amount_lock,
+            );
+
+            Self::deposit_event(Event::<T>::MatchingLocksRepaired(
+                stake_amount_lock,
+                unstake_amount_lock,
+            ));
+            Ok(())
+        }
+
+        /// Update where the protocol fees are sent
+        #[pallet::call_index(41)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_protocol_fee_receiver())]
+        #[transactional]
+        pub fn set_protocol_fee_receiver(
+            origin: OriginFor<T>,
+            receiver: T::AccountId,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            ProtocolFeeReceiver::<T>::put(receiver.clone());
+            Self::deposit_event(Event::<T>::ProtocolFeeReceiverUpdated(receiver));
+            Ok(())
+        }
+
+        /// Consolidate the matured unlock chunks of a staking ledger and mint
+        /// the withdrawn amount, a recovery tool for when the `WithdrawUnbonded`
+        /// notification was lost.
+        ///
+        /// Fails if there is nothing to consolidate, so chunks can't be credited twice.
+        #[pallet::call_index(42)]
+        #[pallet::weight(<T as Config>::WeightInfo::force_consolidate_ledger())]
+        #[transactional]
+        pub fn force_consolidate_ledger(
+            origin: OriginFor<T>,
+            derivative_index: DerivativeIndex,
+        ) -> DispatchResult {
+            T::UpdateOrigin::ensure_origin(origin)?;
+
+            let mut amount = Zero::zero();
+            Self::do_update_ledger(derivative_index, |ledger| {
+                let total = ledger.total;
+                ledger.consolidate_unlocked(Self::current_era());
+                amount = total.saturating_sub(ledger.total);
+                ensure!(!amount.is_zero(), Error::<T>::NothingToConsolidate);
+                T::Assets::mint_into(Self::staking_currency()?, &Self::account_id(), amount)?;
+                Ok(())
+            })?;
+
+            log::trace!(
+                target: "liquidStaking::force_consolidate_ledger",
+                "index: {:?}, amount: {:?}",
+                &derivative_index,
+                &amount,
+            );
+
+            Self::deposit_event(Event::<T>::LedgerConsolidated(derivative_index, amount));
+            Ok(())
+        }
+
+        /// Add reserves by transferring from the caller.
+        ///
+        /// Reserves aren't counted as backing, so the exchange rate isn't affected.
+        /// They can only be taken out by `UpdateOrigin` through `reduce_reserves`,
+        /// so any account may top them up.
+        #[pallet::call_index(43)]
+        #[pallet::weight(<T as Config>::WeightInfo::add_reserves())]
+        #[transactional]
+        pub fn add_reserves(
+            origin: OriginFor<T>,
+            #[pallet::compact] add_amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let payer = ensure_signed(origin)?;
+
+            T::Assets::transfer(
+                Self::staking_currency()?,
+                &payer,
+                &Self::account_id(),
+                add_amount,
+                false,
+            )?;
+
+            TotalReserves::<T>::try_mutate(|b| -> DispatchResult {
+                *b = b.checked_add(add_amount).ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::<T>::ReservesAdded(payer, add_amount));
+
+            Ok(().into())
+        }
+
+        /// Nominate the same targets on relaychain for every bonded derivative index
+        #[pallet::call_index(44)]
+        #[pallet::weight(<T as Config>::WeightInfo::nominate()
+            .saturating_mul(T::DerivativeIndexList::get().len() as u64))]
+        #[transactional]
+        pub fn nominate_all(origin: OriginFor<T>, targets: Vec<T::AccountId>) -> DispatchResult {
+            Self::ensure_origin(origin)?;
+
+            let derivative_index_list = T::DerivativeIndexList::get();
+            let indexes: Vec<DerivativeIndex> = StakingLedgers::<T>::iter_keys()
+                .filter(|index| derivative_index_list.contains(index))
+                .collect();
+            ensure!(!indexes.is_empty(), Error::<T>::NotBonded);
+
+            for &derivative_index in indexes.iter() {
+                Self::do_nominate(derivative_index, targets.clone())?;
+            }
+
+            Self::deposit_event(Event::<T>::NominatingAll(targets, indexes.len() as u32));
+            Ok(())
+        }
+
+        /// Claim the caller's own assets back when current era index arrived
+        /// at target era
+        #[pallet::call_index(45)]
+        #[pallet::weight(<T as Config>::WeightInfo::claim_for())]
+        #[transactional]
+        pub fn claim(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::do_claim_matured(&who)?;
+            Ok(().into())
+        }
+
+        /// Execute a batch of staking operations on relaychain via xcm.transact,
+        /// either all of them are sent or none
+        #[pallet::call_index(46)]
+        #[pallet::weight(Pallet::<T>::staking_ops_weight(ops))]
+        #[transactional]
+        pub fn execute_staking_ops(origin: OriginFor<T>, ops: Vec<StakingOp<T>>) -> DispatchResult {
+            T::RelayOrigin::ensure_origin(origin)?;
+
+            for op in ops {
+                match op {
+                    StakingOp::Bond {
+                        index,
+                        amount,
+                        payee,
+                    } => Self::do_bond(index, amount, payee)?,
+                    StakingOp::BondExtra { index, amount } => Self::do_bond_extra(index, amount)?,
+                    StakingOp::Unbond { index, amount } => Self::do_unbond(index, amount)?,
+                    StakingOp::Rebond { index, amount } => Self::do_rebond(index, amount)?,
+                    StakingOp::Nominate { index, targets } => Self::do_nominate(index, targets)?,
+                }
+            }
+            Ok(())
+        }
+
+        /// Apply a slash of the relaychain ledger by providing storage proof
+        /// of the slashed ledger, the exchange rate is recomputed and may decrease.
+        ///
+        /// Matured unlocking chunks missing from the proven ledger were
+        /// withdrawn and don't count towards the slash.
+        #[pallet::call_index(47)]
+        #[pallet::weight(<T as Config>::WeightInfo::report_slash())]
+        #[transactional]
+        pub fn report_slash(
+            origin: OriginFor<T>,
+            derivative_index: DerivativeIndex,
+            staking_ledger: StakingLedger<T::AccountId, BalanceOf<T>>,
+            proof: Vec<Vec<u8>>,
+        ) -> DispatchResultWithPostInfo {
+            Self::ensure_proof_submitter(origin)?;
+
+            let current_era = Self::current_era();
+            let mut slashed_amount: BalanceOf<T> = Zero::zero();
+            Self::do_update_ledger(derivative_index, |ledger| {
+                let expected_total = ledger
+                    .unlocking
+                    .iter()
+                    .filter(|chunk| {
+                        chunk.era > current_era
+                            || staking_ledger.unlocking.iter().any(|c| c.era == chunk.era)
+                    })
+                    .fold(ledger.active, |acc, chunk| acc.saturating_add(chunk.value));
+                ensure!(
+                    staking_ledger.stash == ledger.stash
+                        && staking_ledger.total < expected_total
+                        && staking_ledger.active <= ledger.active,
+                    Error::<T>::InvalidSlashAmount
+                );
+                let key = Self::get_staking_ledger_key(derivative_index);
+                let value = staking_ledger.encode();
+                Self::verify_merkle_proof(key, value, proof).map_err(Error::<T>::from)?;
+
+                slashed_amount = expected_total.saturating_sub(staking_ledger.total);
+                *ledger = staking_ledger;
+                Ok(())
+            })?;
+            Self::do_update_exchange_rate(true)?;
+
+            Self::deposit_event(Event::<T>::SlashReported(
+                derivative_index,
+                slashed_amount,
+                Self::exchange_rate(),
+            ));
+            Ok(().into())
+        }
+    }
+
+    impl<T> From<ProofError> for Error<T> {
+        fn from(e: ProofError) -> Self {
+            match e {
+                ProofError::NoValidationData => Error::<T>::NoValidationData,
+                ProofError::KeyNotFound => Error::<T>::ProofKeyNotFound,
+                ProofError::ValueMismatch => Error::<T>::ProofValueMismatch,
+                ProofError::MalformedProof => Error::<T>::InvalidProof,
+            }
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_initialize(block_number: T::BlockNumber) -> frame_support::weights::Weight {
+            let mut weight = <T as Config>::WeightInfo::on_initialize();
+            // events deposited at genesis are dropped
+            if block_number.is_one() {
+                weight += T::DbWeight::get().reads(1);
+                Self::deposit_event(Event::<T>::ExchangeRateInitialized(Self::exchange_rate()));
+            }
+            let relaychain_block_number =
+                T::RelayChainValidationDataProvider::current_block_number();
+            let mut step = OnInitializeStep::DeferredBonds;
+            let mut do_on_initialize = || -> DispatchResult {
+                if DeferredBonds::<T>::iter_keys().next().is_some() {
+                    weight += <T as Config>::WeightInfo::force_matching();
+                    Self::do_deferred_bonds();
+                }
+
+                step = OnInitializeStep::Matching;
+                if Self::matching_status() == MatchingStatus::Due {
+                    weight += <T as Config>::WeightInfo::force_matching();
+                    Self::do_matching()?;
+                } else if Self::is_intra_era_matching_due()? {
+                    weight += <T as Config>::WeightInfo::force_matching();
+                    Self::do_intra_era_matching()?;
+                }
+
+                let offset = Self::offset(relaychain_block_number);
+                if offset.is_zero() {
+                    return Ok(());
+                }
+                step = OnInitializeStep::AdvanceEra;
+                weight += <T as Config>::WeightInfo::force_advance_era();
+                Self::note_era_advance(block_number)?;
+
+                let max_offset = T::MaxEraAdvancePerBlock::get();
+                if offset <= max_offset {
+                    return Self::do_advance_era(offset);
+                }
+
+                // start the era from where the applied offset ends, so that
+                // the remaining offset is advanced in the next blocks
+                let era_start_block = Self::era_start_block()
+                    .saturating_add(T::EraLength::get().saturating_mul(max_offset.into()));
+                Self::do_advance_era(max_offset)?;
+                EraStartBlock::<T>::put(era_start_block);
+                Self::deposit_event(Event::<T>::EraAdvanceClamped(offset, max_offset));
+                Ok(())
+            };
+            if let Err(err) = with_transaction(|| match do_on_initialize() {
+                Ok(()) => TransactionOutcome::Commit(Ok(())),
+                Err(err) => TransactionOutcome::Rollback(Err(err)),
+            }) {
+                log::error!(
+                    target: "liquidStaking::on_initialize",
+                    "{:?} failed and was rolled back: {:?}",
+                    &step,
+                    &err,
+                );
+                Self::deposit_event(Event::<T>::OnInitializeFailed(step, err));
+            }
+            weight
+        }
+
+        fn on_finalize(_n: T::BlockNumber) {
+            let _ = IsUpdated::<T>::clear(u32::max_value(), None);
+            XcmSentInBlock::<T>::kill();
+            if let Some(data) = T::RelayChainValidationDataProvider::validation_data() {
+                ValidationData::<T>::put(data);
+            }
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Staking pool account
+        pub fn account_id() -> T::AccountId {
+            T::PalletId::get().into_account_truncating()
+        }
+
+        /// Loans pool account
         pub fn loans_account_id() -> T::AccountId {
             T::LoansPalletId::get().into_account_truncating()
         }
@@ -1155,24 +2200,86 @@ pub mod pallet {
             T::SelfParaId::get().into_account_truncating()
         }
 
+        /// Whether matching is due or has already run in current era
+        pub fn matching_status() -> MatchingStatus {
+            if Self::is_matched() {
+                MatchingStatus::Completed
+            } else if T::ElectionSolutionStoredOffset::get().saturating_add(Self::era_start_block())
+                <= T::RelayChainValidationDataProvider::current_block_number()
+            {
+                MatchingStatus::Due
+            } else {
+                MatchingStatus::NotDue
+            }
+        }
+
+        /// Whether the era's matching is done, `MatchingInterval` has passed since and
+        /// enough was staked or unstaked in the meantime to match again
+        fn is_intra_era_matching_due() -> Result<bool, DispatchError> {
+            let interval = T::MatchingInterval::get();
+            if !Self::is_matched()
+                || interval.is_zero()
+                || Self::last_matching_block().saturating_add(interval)
+                    > T::RelayChainValidationDataProvider::current_block_number()
+            {
+                return Ok(false);
+            }
+
+            let matching_pool = Self::matching_pool();
+            let free_amount = matching_pool
+                .total_stake_amount
+                .free()?
+                .saturating_add(matching_pool.total_unstake_amount.free()?);
+            Ok(free_amount >= T::IntraEraMatchingThreshold::get())
+        }
+
+        /// Liquid amount minted, reserves taken and xcm fees charged when staking
+        /// `amount`, failing the same way `stake` would
+        pub fn preview_stake(
+            amount: BalanceOf<T>,
+        ) -> Result<(BalanceOf<T>, BalanceOf<T>, BalanceOf<T>), DispatchError> {
+            ensure!(amount >= T::MinStake::get(), Error::<T>::StakeTooSmall);
+
+            let reserves = Self::reserve_factor().mul_floor(amount);
+            let xcm_fees = T::XcmFees::get();
+            let stake_amount = amount
+                .checked_sub(xcm_fees)
+                .and_then(|r| r.checked_sub(reserves))
+                .ok_or(ArithmeticError::Underflow)?;
+            let liquid_amount =
+                Self::staking_to_liquid(stake_amount).ok_or(Error::<T>::InvalidExchangeRate)?;
+            Self::ensure_market_cap(stake_amount)?;
+
+            Ok((liquid_amount, reserves, xcm_fees))
+        }
+
         /// Target era_index if users unstake in current_era
         pub fn target_era() -> EraIndex {
-            // TODO: check if we can bond before the next era
-            // so that the one era's delay can be removed
-            Self::current_era() + T::BondingDuration::get() + 1
+            Self::current_era() + T::BondingDuration::get() + T::UnbondingEraBuffer::get()
+        }
+
+        /// Largest amount `stake` accepts before the market cap is exceeded
+        pub fn max_stake_amount() -> BalanceOf<T> {
+            let remaining_cap = Self::get_market_cap().saturating_sub(Self::get_total_bonded());
+            if remaining_cap.is_zero() {
+                return Zero::zero();
+            }
+            Ratio::one()
+                .saturating_sub(Self::reserve_factor())
+                .saturating_reciprocal_mul_floor(remaining_cap.saturating_add(T::XcmFees::get()))
         }
 
         /// Get staking currency or return back an error
         pub fn staking_currency() -> Result<AssetIdOf<T>, DispatchError> {
             Self::get_staking_currency()
-                .ok_or(Error::<T>::InvalidStakingCurrency)
+                .ok_or(Error::<T>::CurrencyDecimalNotRegistered)
                 .map_err(Into::into)
         }
 
         /// Get liquid currency or return back an error
         pub fn liquid_currency() -> Result<AssetIdOf<T>, DispatchError> {
             Self::get_liquid_currency()
-                .ok_or(Error::<T>::InvalidLiquidCurrency)
+                .ok_or(Error::<T>::CurrencyDecimalNotRegistered)
                 .map_err(Into::into)
         }
 
@@ -1183,6 +2290,12 @@ pub mod pallet {
                 .saturating_sub(Self::matching_pool().total_stake_amount.total)
         }
 
+        /// Liquid currency issuance including the commission not minted yet
+        fn liquid_issuance() -> Result<BalanceOf<T>, DispatchError> {
+            Ok(T::Assets::total_issuance(Self::liquid_currency()?)
+                .saturating_add(Self::pending_commission()))
+        }
+
         /// Derivative of parachain's account
         pub fn derivative_sovereign_account_id(index: DerivativeIndex) -> T::AccountId {
             let para_account = Self::sovereign_account_id();
@@ -1205,12 +2318,26 @@ pub mod pallet {
             Self::staking_ledger(index).map_or(Zero::zero(), |ledger| ledger.active)
         }
 
+        fn commission_rate_of(reward_source: RewardSource) -> Rate {
+            Self::source_commission_rate(reward_source).unwrap_or_else(Self::commission_rate)
+        }
+
+        fn min_bond_of(index: DerivativeIndex) -> BalanceOf<T> {
+            Self::per_index_min_bond(index).map_or(T::MinNominatorBond::get(), |min_bond| {
+                min_bond.max(T::MinNominatorBond::get())
+            })
+        }
+
         fn unbonding_of(index: DerivativeIndex) -> BalanceOf<T> {
             Self::staking_ledger(index).map_or(Zero::zero(), |ledger| {
                 ledger.total.saturating_sub(ledger.active)
             })
         }
 
+        fn slashing_spans_of(index: DerivativeIndex) -> u32 {
+            Self::slashing_spans(index).unwrap_or_else(T::NumSlashingSpans::get)
+        }
+
         fn unbonded_of(index: DerivativeIndex) -> BalanceOf<T> {
             let current_era = Self::current_era();
             Self::staking_ledger(index).map_or(Zero::zero(), |ledger| {
@@ -1241,11 +2368,84 @@ pub mod pallet {
             })
         }
 
+        /// Pending unstake of the matching pool can only be paid out of
+        /// active bonded plus pending stake
+        fn ensure_unstake_realizable() -> DispatchResult {
+            let matching_ledger = Self::matching_pool();
+            ensure!(
+                matching_ledger.total_unstake_amount.total
+                    <= Self::get_total_active_bonded()
+                        .saturating_add(matching_ledger.total_stake_amount.total),
+                Error::<T>::InsufficientBonded
+            );
+            Ok(())
+        }
+
         fn get_market_cap() -> BalanceOf<T> {
             Self::staking_ledger_cap()
                 .saturating_mul(T::DerivativeIndexList::get().len() as BalanceOf<T>)
         }
 
+        #[require_transactional]
+        fn do_stake(
+            who: &T::AccountId,
+            amount: BalanceOf<T>,
+        ) -> Result<BalanceOf<T>, DispatchError> {
+            let (liquid_amount, reserves, xcm_fees) = Self::preview_stake(amount)?;
+
+            let amount = amount.saturating_sub(xcm_fees);
+            T::Assets::transfer(
+                Self::staking_currency()?,
+                who,
+                &Self::account_id(),
+                amount,
+                false,
+            )?;
+            T::XCM::add_xcm_fees(who, xcm_fees)?;
+
+            let amount = amount.saturating_sub(reserves);
+            T::Assets::mint_into(Self::liquid_currency()?, who, liquid_amount)?;
+
+            log::trace!(
+                target: "liquidStaking::stake",
+                "stake_amount: {:?}, liquid_amount: {:?}, reserved: {:?}",
+                &amount,
+                &liquid_amount,
+                &reserves
+            );
+
+            MatchingPool::<T>::try_mutate(|p| -> DispatchResult { p.add_stake_amount(amount) })?;
+            TotalReserves::<T>::try_mutate(|b| -> DispatchResult {
+                *b = b.checked_add(reserves).ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            })?;
+
+            if !reserves.is_zero() {
+                Self::deposit_event(Event::<T>::ReservesAccrued(reserves));
+            }
+            Self::do_record_reward_snapshot(who);
+
+            Self::deposit_event(Event::<T>::Staked(who.clone(), amount));
+            Ok(liquid_amount)
+        }
+
+        /// Record the current exchange rate for an account which opted in
+        /// to reward snapshots, one per era
+        fn do_record_reward_snapshot(who: &T::AccountId) {
+            RewardSnapshots::<T>::mutate_exists(who, |snapshots| {
+                if let Some(snapshots) = snapshots {
+                    let snapshot = (Self::current_era(), Self::exchange_rate());
+                    match snapshots.last_mut() {
+                        Some(last) if last.0 == snapshot.0 => *last = snapshot,
+                        _ => snapshots.push(snapshot),
+                    }
+                    if snapshots.len() > MAX_REWARD_SNAPSHOTS {
+                        snapshots.remove(0);
+                    }
+                }
+            });
+        }
+
         #[require_transactional]
         fn do_bond(
             derivative_index: DerivativeIndex,
@@ -1253,10 +2453,22 @@ pub mod pallet {
             payee: RewardDestination<T::AccountId>,
         ) -> DispatchResult {
             if amount.is_zero() {
+                log::debug!(
+                    target: "liquidStaking::bond",
+                    "skipped zero amount, index: {:?}",
+                    &derivative_index,
+                );
                 return Ok(());
             }
 
             if StakingLedgers::<T>::contains_key(derivative_index) {
+                let payee = Self::payee_override(derivative_index).unwrap_or(payee);
+                // ledgers bonded before payees were tracked are left untouched
+                if Self::payee(derivative_index).map_or(false, |current| current != payee)
+                    && !Self::is_set_payee_pending(derivative_index)
+                {
+                    Self::do_set_payee(derivative_index, payee)?;
+                }
                 return Self::do_bond_extra(derivative_index, amount);
             }
 
@@ -1281,6 +2493,8 @@ pub mod pallet {
                 p.set_stake_amount_lock(amount)
             })?;
 
+            let payee = Self::payee_override(derivative_index).unwrap_or(payee);
+            Self::ensure_valid_payee(&payee)?;
             let derivative_account_id = Self::derivative_sovereign_account_id(derivative_index);
             let query_id = T::XCM::do_bond(
                 amount,
@@ -1289,8 +2503,9 @@ pub mod pallet {
                 derivative_index,
                 Self::notify_placeholder(),
             )?;
+            Payees::<T>::insert(derivative_index, payee.clone());
 
-            XcmRequests::<T>::insert(
+            Self::insert_xcm_request(
                 query_id,
                 XcmRequest::Bond {
                     index: derivative_index,
@@ -1314,6 +2529,11 @@ pub mod pallet {
             amount: BalanceOf<T>,
         ) -> DispatchResult {
             if amount.is_zero() {
+                log::debug!(
+                    target: "liquidStaking::bond_extra",
+                    "skipped zero amount, index: {:?}",
+                    &derivative_index,
+                );
                 return Ok(());
             }
 
@@ -1345,7 +2565,7 @@ pub mod pallet {
                 Self::notify_placeholder(),
             )?;
 
-            XcmRequests::<T>::insert(
+            Self::insert_xcm_request(
                 query_id,
                 XcmRequest::BondExtra {
                     index: derivative_index,
@@ -1358,9 +2578,50 @@ pub mod pallet {
             Ok(())
         }
 
+        #[require_transactional]
+        fn do_set_payee(
+            derivative_index: DerivativeIndex,
+            payee: RewardDestination<T::AccountId>,
+        ) -> DispatchResult {
+            Self::ensure_valid_payee(&payee)?;
+
+            log::trace!(
+                target: "liquidStaking::set_payee",
+                "index: {:?}, payee: {:?}",
+                &derivative_index,
+                &payee,
+            );
+
+            let query_id =
+                T::XCM::do_set_payee(payee.clone(), derivative_index, Self::notify_placeholder())?;
+
+            Self::insert_xcm_request(
+                query_id,
+                XcmRequest::SetPayee {
+                    index: derivative_index,
+                    payee: payee.clone(),
+                },
+            );
+
+            Self::deposit_event(Event::<T>::PayeeUpdated(derivative_index, payee));
+
+            Ok(())
+        }
+
+        fn is_set_payee_pending(derivative_index: DerivativeIndex) -> bool {
+            XcmRequests::<T>::iter_values().any(|request| {
+                matches!(request, XcmRequest::SetPayee { index, .. } if index == derivative_index)
+            })
+        }
+
         #[require_transactional]
         fn do_unbond(derivative_index: DerivativeIndex, amount: BalanceOf<T>) -> DispatchResult {
             if amount.is_zero() {
+                log::debug!(
+                    target: "liquidStaking::unbond",
+                    "skipped zero amount, index: {:?}",
+                    &derivative_index,
+                );
                 return Ok(());
             }
 
@@ -1376,7 +2637,7 @@ pub mod pallet {
                 Error::<T>::NoMoreChunks
             );
             ensure!(
-                ledger.active.saturating_sub(amount) >= T::MinNominatorBond::get(),
+                ledger.active.saturating_sub(amount) >= Self::min_bond_of(derivative_index),
                 Error::<T>::InsufficientBond
             );
 
@@ -1393,7 +2654,7 @@ pub mod pallet {
 
             let query_id = T::XCM::do_unbond(amount, derivative_index, Self::notify_placeholder())?;
 
-            XcmRequests::<T>::insert(
+            Self::insert_xcm_request(
                 query_id,
                 XcmRequest::Unbond {
                     index: derivative_index,
@@ -1409,6 +2670,11 @@ pub mod pallet {
         #[require_transactional]
         fn do_rebond(derivative_index: DerivativeIndex, amount: BalanceOf<T>) -> DispatchResult {
             if amount.is_zero() {
+                log::debug!(
+                    target: "liquidStaking::rebond",
+                    "skipped zero amount, index: {:?}",
+                    &derivative_index,
+                );
                 return Ok(());
             }
 
@@ -1434,7 +2700,7 @@ pub mod pallet {
 
             let query_id = T::XCM::do_rebond(amount, derivative_index, Self::notify_placeholder())?;
 
-            XcmRequests::<T>::insert(
+            Self::insert_xcm_request(
                 query_id,
                 XcmRequest::Rebond {
                     index: derivative_index,
@@ -1479,7 +2745,7 @@ pub mod pallet {
                 Self::notify_placeholder(),
             )?;
 
-            XcmRequests::<T>::insert(
+            Self::insert_xcm_request(
                 query_id,
                 XcmRequest::WithdrawUnbonded {
                     index: derivative_index,
@@ -1515,13 +2781,29 @@ pub mod pallet {
                 &derivative_index,
             );
 
+            if let Some(nominations) = Self::nominations(derivative_index) {
+                let changed_targets = targets
+                    .iter()
+                    .filter(|target| !nominations.contains(target))
+                    .count() as u32;
+                let max_changed_targets =
+                    T::MaxNominationChurnPerEra::get().mul_floor(targets.len() as u32);
+                if changed_targets > max_changed_targets {
+                    Self::deposit_event(Event::<T>::NominationChurnRejected(
+                        derivative_index,
+                        changed_targets,
+                    ));
+                    return Ok(());
+                }
+            }
+
             let query_id = T::XCM::do_nominate(
                 targets.clone(),
                 derivative_index,
                 Self::notify_placeholder(),
             )?;
 
-            XcmRequests::<T>::insert(
+            Self::insert_xcm_request(
                 query_id,
                 XcmRequest::Nominate {
                     index: derivative_index,
@@ -1540,6 +2822,7 @@ pub mod pallet {
             payee: RewardDestination<T::AccountId>,
         ) -> DispatchResult {
             if total_amount.is_zero() {
+                log::debug!(target: "liquidStaking::multi_bond", "skipped zero amount");
                 return Ok(());
             }
 
@@ -1561,22 +2844,96 @@ pub mod pallet {
                 T::MinNominatorBond::get(),
             );
 
-            for (index, amount) in distributions.into_iter() {
-                Self::do_bond(index, amount, payee.clone())?;
+            for (index, amount) in distributions.into_iter() {
+                if amount < T::MinXcmBondAmount::get() {
+                    Self::deposit_event(Event::<T>::DustBondSkipped(index, amount));
+                    continue;
+                }
+                let amount = Self::split_bond_amount(index, amount);
+                if Self::xcm_sent_in_block() >= T::MaxXcmPerBlock::get() {
+                    DeferredBonds::<T>::mutate(index, |b| {
+                        let deferred = b.take().map_or(amount, |(a, _)| a.saturating_add(amount));
+                        *b = Some((deferred, payee.clone()));
+                    });
+                    Self::deposit_event(Event::<T>::XcmDeferred(index, amount));
+                    continue;
+                }
+                Self::do_bond(index, amount, payee.clone())?;
+            }
+
+            Ok(())
+        }
+
+        fn split_bond_amount(index: DerivativeIndex, amount: BalanceOf<T>) -> BalanceOf<T> {
+            let active_bonded = Self::active_bonded_of(index);
+            if !T::SplitLargeBonds::get() || active_bonded.is_zero() {
+                return amount;
             }
+            let max_amount = T::BondSplitThreshold::get().saturating_mul_int(active_bonded);
+            if amount <= max_amount {
+                return amount;
+            }
+            Self::deposit_event(Event::<T>::BondSplit(
+                index,
+                max_amount,
+                amount.saturating_sub(max_amount),
+            ));
+            max_amount
+        }
 
-            Ok(())
+        /// Send the bonds deferred by `do_multi_bond` within `MaxXcmPerBlock`,
+        /// as much as is still free in matching pool.
+        ///
+        /// Each bond is sent in its own transaction. A failed one is dropped, its
+        /// amount stays free in matching pool and is bonded by the next matching.
+        fn do_deferred_bonds() {
+            let deferred_bonds: Vec<_> = DeferredBonds::<T>::iter().collect();
+            for (index, (amount, payee)) in deferred_bonds.into_iter() {
+                if Self::xcm_sent_in_block() >= T::MaxXcmPerBlock::get() {
+                    break;
+                }
+                DeferredBonds::<T>::remove(index);
+                if let Err(err) = with_transaction(|| {
+                    let result = Self::matching_pool()
+                        .total_stake_amount
+                        .free()
+                        .and_then(|free| Self::do_bond(index, amount.min(free), payee));
+                    match result {
+                        Ok(()) => TransactionOutcome::Commit(Ok(())),
+                        Err(err) => TransactionOutcome::Rollback(Err(err)),
+                    }
+                }) {
+                    log::error!(
+                        target: "liquidStaking::deferred_bonds",
+                        "index: {:?}, amount: {:?}, error: {:?}",
+                        &index,
+                        &amount,
+                        &err,
+                    );
+                    Self::deposit_event(Event::<T>::DeferredBondFailed(index, amount, err));
+                }
+            }
         }
 
         #[require_transactional]
         fn do_multi_unbond(total_amount: BalanceOf<T>) -> DispatchResult {
             if total_amount.is_zero() {
+                log::debug!(target: "liquidStaking::multi_unbond", "skipped zero amount");
                 return Ok(());
             }
 
+            // hide the part of active bond kept by `PerIndexMinBond` from the strategy,
+            // which only knows about `MinNominatorBond`
             let amounts: Vec<(DerivativeIndex, BalanceOf<T>)> = T::DerivativeIndexList::get()
                 .iter()
-                .map(|&index| (index, Self::active_bonded_of(index)))
+                .map(|&index| {
+                    let extra_min_bond =
+                        Self::min_bond_of(index).saturating_sub(T::MinNominatorBond::get());
+                    (
+                        index,
+                        Self::active_bonded_of(index).saturating_sub(extra_min_bond),
+                    )
+                })
                 .collect();
             let distributions = T::DistributionStrategy::get_unbond_distributions(
                 amounts,
@@ -1585,6 +2942,10 @@ pub mod pallet {
             );
 
             for (index, amount) in distributions.into_iter() {
+                if amount < T::MinXcmBondAmount::get() {
+                    Self::deposit_event(Event::<T>::DustBondSkipped(index, amount));
+                    continue;
+                }
                 Self::do_unbond(index, amount)?;
             }
 
@@ -1594,6 +2955,7 @@ pub mod pallet {
         #[require_transactional]
         fn do_multi_rebond(total_amount: BalanceOf<T>) -> DispatchResult {
             if total_amount.is_zero() {
+                log::debug!(target: "liquidStaking::multi_rebond", "skipped zero amount");
                 return Ok(());
             }
 
@@ -1612,9 +2974,12 @@ pub mod pallet {
         }
 
         #[require_transactional]
-        fn do_multi_withdraw_unbonded(num_slashing_spans: u32) -> DispatchResult {
+        fn do_multi_withdraw_unbonded() -> DispatchResult {
             for derivative_index in StakingLedgers::<T>::iter_keys() {
-                Self::do_withdraw_unbonded(derivative_index, num_slashing_spans)?;
+                Self::do_withdraw_unbonded(
+                    derivative_index,
+                    Self::slashing_spans_of(derivative_index),
+                )?;
             }
 
             Ok(())
@@ -1712,39 +3077,465 @@ pub mod pallet {
                         Ok(())
                     })?;
                 }
-                Nominate { targets: _, .. } => {}
+                Nominate {
+                    index: derivative_index,
+                    targets,
+                } => {
+                    Nominations::<T>::insert(derivative_index, targets);
+                }
+                SetPayee {
+                    index: derivative_index,
+                    payee,
+                } => {
+                    Payees::<T>::insert(derivative_index, payee);
+                }
             }
-            XcmRequests::<T>::remove(query_id);
+            Self::remove_xcm_request(query_id);
+            Ok(())
+        }
+
+        #[require_transactional]
+        fn do_discard_xcm_request(query_id: QueryId, req: XcmRequest<T>) -> DispatchResult {
+            use XcmRequest::*;
+
+            match req {
+                Bond { amount, .. } | BondExtra { amount, .. } | Rebond { amount, .. } => {
+                    MatchingPool::<T>::try_mutate(|p| -> DispatchResult {
+                        p.remove_stake_amount_lock(amount)
+                    })?;
+                }
+                Unbond { amount, .. } => {
+                    MatchingPool::<T>::try_mutate(|p| -> DispatchResult {
+                        p.remove_unstake_amount_lock(amount)
+                    })?;
+                }
+                WithdrawUnbonded { .. } | Nominate { .. } | SetPayee { .. } => {}
+            }
+            Self::remove_xcm_request(query_id);
             Ok(())
         }
 
+        /// Recompute the exchange rate, which only goes up unless `allow_decrease`
+        /// is set for proven slashes
         #[require_transactional]
-        fn do_update_exchange_rate() -> DispatchResult {
+        fn do_update_exchange_rate(allow_decrease: bool) -> DispatchResult {
             let matching_ledger = Self::matching_pool();
             let total_active_bonded = Self::get_total_active_bonded();
-            let issuance = T::Assets::total_issuance(Self::liquid_currency()?);
+            let slashing_discount = Self::slashing_discount(total_active_bonded);
+            let total_active_bonded = total_active_bonded.saturating_sub(slashing_discount);
+            let liquid_currency = Self::liquid_currency()?;
+            let issuance = Self::liquid_issuance()?;
             if issuance.is_zero() {
-                return Ok(());
+                return Self::do_reset_exchange_rate(
+                    total_active_bonded,
+                    matching_ledger.total_stake_amount.total,
+                    matching_ledger.total_unstake_amount.total,
+                );
             }
             // TODO: when one era has big amount of stakes, the exchange rate
             // will not look great
-            let new_exchange_rate = Rate::checked_from_rational(
-                total_active_bonded
-                    .checked_add(matching_ledger.total_stake_amount.total)
-                    .and_then(|r| r.checked_sub(matching_ledger.total_unstake_amount.total))
-                    .ok_or(ArithmeticError::Overflow)?,
+            // protocol owned stake is excluded as it backs no liquid currency
+            let new_exchange_rate = Self::compute_exchange_rate(
+                total_active_bonded,
+                matching_ledger.total_stake_amount.total,
+                matching_ledger
+                    .total_unstake_amount
+                    .total
+                    .saturating_add(Self::protocol_owned_stake()),
                 issuance,
             )
             .ok_or(Error::<T>::InvalidExchangeRate)?;
-            // slashes should be handled properly offchain
+            // unproven slashes should be handled properly offchain
             // by doing `bond_extra` using OrmlXcm or PolkadotXcm
-            if new_exchange_rate > Self::exchange_rate() {
+            let current_exchange_rate = Self::exchange_rate();
+            if new_exchange_rate > current_exchange_rate
+                || (allow_decrease && new_exchange_rate < current_exchange_rate)
+            {
                 ExchangeRate::<T>::put(new_exchange_rate);
-                Self::deposit_event(Event::<T>::ExchangeRateUpdated(new_exchange_rate));
+                Self::deposit_event(Event::<T>::ExchangeRateUpdated(
+                    new_exchange_rate,
+                    slashing_discount,
+                ));
+                T::ExchangeRateChangeHandler::on_exchange_rate_changed(
+                    &liquid_currency,
+                    new_exchange_rate,
+                );
+            }
+            Ok(())
+        }
+
+        /// With no liquid currency left, whatever still backs it belongs to nobody.
+        /// It's accounted as protocol owned stake, so that the next staker doesn't
+        /// capture it, and the exchange rate starts over from `ZeroIssuanceExchangeRate`.
+        fn do_reset_exchange_rate(
+            total_active_bonded: BalanceOf<T>,
+            matching_stake: BalanceOf<T>,
+            matching_unstake: BalanceOf<T>,
+        ) -> DispatchResult {
+            let residual = total_active_bonded
+                .saturating_add(matching_stake)
+                .saturating_sub(matching_unstake)
+                .saturating_sub(Self::protocol_owned_stake());
+            let exchange_rate = T::ZeroIssuanceExchangeRate::get();
+            if residual.is_zero() && exchange_rate == Self::exchange_rate() {
+                return Ok(());
+            }
+
+            ProtocolOwnedStake::<T>::try_mutate(|b| -> DispatchResult {
+                *b = b.checked_add(residual).ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            })?;
+            ExchangeRate::<T>::put(exchange_rate);
+            Self::deposit_event(Event::<T>::ExchangeRateReset(exchange_rate, residual));
+            T::ExchangeRateChangeHandler::on_exchange_rate_changed(
+                &Self::liquid_currency()?,
+                exchange_rate,
+            );
+            Ok(())
+        }
+
+        /// Computes the exchange rate as
+        /// `(total_active_bonded + matching_stake - matching_unstake) / issuance`.
+        ///
+        /// Returns `None` on overflow, underflow or zero issuance.
+        pub fn compute_exchange_rate(
+            total_active_bonded: BalanceOf<T>,
+            matching_stake: BalanceOf<T>,
+            matching_unstake: BalanceOf<T>,
+            issuance: BalanceOf<T>,
+        ) -> Option<Rate> {
+            let total_bonded = total_active_bonded
+                .checked_add(matching_stake)?
+                .checked_sub(matching_unstake)?;
+            Rate::checked_from_rational(total_bonded, issuance)
+        }
+
+        /// Pending xcm requests with the era they were sent in, so that
+        /// off-chain workers can find the stale ones.
+        pub fn pending_xcm_requests() -> Vec<(QueryId, XcmRequestInfo<BalanceOf<T>>)> {
+            XcmRequests::<T>::iter()
+                .map(|(query_id, request)| {
+                    let (kind, index, amount) = match request {
+                        XcmRequest::Bond { index, amount } => (XcmCall::Bond, index, amount),
+                        XcmRequest::BondExtra { index, amount } => {
+                            (XcmCall::BondExtra, index, amount)
+                        }
+                        XcmRequest::Unbond { index, amount } => (XcmCall::Unbond, index, amount),
+                        XcmRequest::Rebond { index, amount } => (XcmCall::Rebond, index, amount),
+                        XcmRequest::WithdrawUnbonded { index, .. } => {
+                            (XcmCall::WithdrawUnbonded, index, Zero::zero())
+                        }
+                        XcmRequest::Nominate { index, .. } => {
+                            (XcmCall::Nominate, index, Zero::zero())
+                        }
+                        XcmRequest::SetPayee { index, .. } => {
+                            (XcmCall::SetPayee, index, Zero::zero())
+                        }
+                    };
+                    (
+                        query_id,
+                        XcmRequestInfo {
+                            kind,
+                            index,
+                            amount,
+                            created_era: Self::xcm_request_created(query_id).map(|(era, _)| era),
+                        },
+                    )
+                })
+                .collect()
+        }
+
+        /// Relaychain storage keys of the staking ledger of `derivative_index`
+        /// and of the current era, whose proofs are required by
+        /// `set_staking_ledger` and `set_current_era`
+        pub fn relay_storage_keys(derivative_index: DerivativeIndex) -> (Vec<u8>, Vec<u8>) {
+            (
+                Self::get_staking_ledger_key(derivative_index),
+                Self::get_current_era_key(),
+            )
+        }
+
+        /// Amount of `who`'s unlockings matured by current era, capped by what is
+        /// withdrawn to the pallet and not yet claimed. `claim_for` goes through
+        /// only when the cap doesn't apply
+        pub fn claimable_now(who: &T::AccountId) -> BalanceOf<T> {
+            let current_era = Self::current_era();
+            let matured = Self::unlockings(who).map_or(Zero::zero(), |chunks| {
+                chunks
+                    .iter()
+                    .filter(|chunk| chunk.era <= current_era)
+                    .fold(Zero::zero(), |acc: BalanceOf<T>, chunk| {
+                        acc.saturating_add(chunk.value)
+                    })
+            });
+            let total_unclaimed =
+                Self::staking_currency().map_or(Zero::zero(), Self::get_total_unclaimed);
+            matured.min(total_unclaimed)
+        }
+
+        /// Part of active bonded held back against unreported slashes
+        fn slashing_discount(total_active_bonded: BalanceOf<T>) -> BalanceOf<T> {
+            T::SlashingSafetyBuffer::get().mul_floor(total_active_bonded)
+        }
+
+        /// Liquid currency issuance alongside each input of its backing, so
+        /// that off-chain verifiers can audit the exchange rate
+        pub fn liquid_backing() -> Result<LiquidBackingInfo<BalanceOf<T>>, DispatchError> {
+            let matching_ledger = Self::matching_pool();
+            let total_active_bonded = Self::get_total_active_bonded();
+            let slashing_discount = Self::slashing_discount(total_active_bonded);
+            let liquid_issuance = Self::liquid_issuance()?;
+            let protocol_owned_stake = Self::protocol_owned_stake();
+            Ok(LiquidBackingInfo {
+                liquid_issuance,
+                total_active_bonded,
+                slashing_discount,
+                matching_stake: matching_ledger.total_stake_amount.total,
+                matching_unstake: matching_ledger.total_unstake_amount.total,
+                protocol_owned_stake,
+                backing_ratio: Self::compute_exchange_rate(
+                    total_active_bonded.saturating_sub(slashing_discount),
+                    matching_ledger.total_stake_amount.total,
+                    matching_ledger
+                        .total_unstake_amount
+                        .total
+                        .saturating_add(protocol_owned_stake),
+                    liquid_issuance,
+                ),
+            })
+        }
+
+        /// Check the accounting invariants of the pallet, so that off-chain
+        /// monitoring can catch drift early:
+        /// - backing per liquid currency is within `MaxExchangeRateDeviation`
+        ///   of the exchange rate
+        /// - active bonded of the derivative indexes adds up to the total
+        /// - pending xcm requests don't exceed the matching pool locks
+        pub fn check_invariants() -> Result<(), InvariantError> {
+            let liquid_backing =
+                Self::liquid_backing().map_err(|_| InvariantError::CurrencyNotRegistered)?;
+            if let Some(backing_ratio) = liquid_backing.backing_ratio {
+                let current_rate = Self::exchange_rate();
+                let max_deviation = Rate::from_inner(
+                    T::MaxExchangeRateDeviation::get().mul_floor(current_rate.into_inner()),
+                );
+                if backing_ratio < current_rate.saturating_sub(max_deviation)
+                    || backing_ratio > current_rate.saturating_add(max_deviation)
+                {
+                    return Err(InvariantError::ExchangeRateDeviated);
+                }
+            }
+
+            let indexed_active_bonded =
+                T::DerivativeIndexList::get()
+                    .iter()
+                    .fold(BalanceOf::<T>::zero(), |acc, &index| {
+                        acc.saturating_add(
+                            Self::staking_ledger(index)
+                                .map_or(Zero::zero(), |ledger| ledger.active),
+                        )
+                    });
+            if indexed_active_bonded != liquid_backing.total_active_bonded {
+                return Err(InvariantError::ActiveBondedMismatch);
+            }
+
+            let (stake_locked, unstake_locked) = XcmRequests::<T>::iter_values().fold(
+                (BalanceOf::<T>::zero(), BalanceOf::<T>::zero()),
+                |(stake, unstake), request| match request {
+                    XcmRequest::Bond { amount, .. }
+                    | XcmRequest::BondExtra { amount, .. }
+                    | XcmRequest::Rebond { amount, .. } => (stake.saturating_add(amount), unstake),
+                    XcmRequest::Unbond { amount, .. } => (stake, unstake.saturating_add(amount)),
+                    _ => (stake, unstake),
+                },
+            );
+            let matching_ledger = Self::matching_pool();
+            if stake_locked > matching_ledger.total_stake_amount.reserved
+                || unstake_locked > matching_ledger.total_unstake_amount.reserved
+            {
+                return Err(InvariantError::XcmRequestLocksExceeded);
             }
+
             Ok(())
         }
 
+        /// Amount becoming withdrawable at each era for `derivative_index`
+        pub fn unbonding_schedule(
+            derivative_index: DerivativeIndex,
+        ) -> Vec<(EraIndex, BalanceOf<T>)> {
+            Self::staking_ledger(derivative_index)
+                .map(|ledger| {
+                    ledger
+                        .unlocking
+                        .iter()
+                        .map(|chunk| (chunk.era, chunk.value))
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+
+        /// Staking ledgers of all bonded derivative indexes, ordered by index
+        pub fn all_staking_ledgers() -> Vec<(
+            DerivativeIndex,
+            StakingLedgerInfo<T::AccountId, BalanceOf<T>>,
+        )> {
+            let mut ledgers: Vec<_> = StakingLedgers::<T>::iter()
+                .map(|(derivative_index, ledger)| {
+                    (
+                        derivative_index,
+                        StakingLedgerInfo {
+                            stash: ledger.stash,
+                            total: ledger.total,
+                            active: ledger.active,
+                            unlocking: ledger
+                                .unlocking
+                                .iter()
+                                .map(|chunk| (chunk.era, chunk.value))
+                                .collect(),
+                            claimed_rewards: ledger.claimed_rewards,
+                            is_updated: Self::is_updated(derivative_index),
+                        },
+                    )
+                })
+                .collect();
+            ledgers.sort_by_key(|(derivative_index, _)| *derivative_index);
+            ledgers
+        }
+
+        /// Stake and unstake amounts matched in each retained era, ordered by era
+        pub fn era_net_flows() -> Vec<(EraIndex, BalanceOf<T>, BalanceOf<T>)> {
+            let mut flows: Vec<_> = EraNetFlow::<T>::iter()
+                .map(|(era, (stake, unstake))| (era, stake, unstake))
+                .collect();
+            flows.sort_by_key(|(era, _, _)| *era);
+            flows
+        }
+
+        /// Annualized growth of the exchange rate over the last `lookback_eras` eras.
+        ///
+        /// Commission is minted as liquid currency, so the growth is already net
+        /// of it. Returns `None` if the history doesn't cover the lookback window.
+        pub fn estimated_apy(lookback_eras: EraIndex) -> Option<Rate> {
+            if lookback_eras.is_zero() {
+                return None;
+            }
+            let current_era = Self::current_era();
+            let start_rate = Self::era_exchange_rate(current_era.checked_sub(lookback_eras)?)?;
+            let end_rate = Self::era_exchange_rate(current_era)?;
+            let growth = end_rate
+                .checked_div(&start_rate)?
+                .saturating_sub(Rate::one());
+
+            // relaychain produces a block every 6 seconds
+            let era_length = TryInto::<u64>::try_into(T::EraLength::get()).ok()?;
+            let eras_per_year = (SECONDS_PER_YEAR / 6).checked_div(era_length)?;
+            let annualize = Rate::checked_from_rational(eras_per_year, lookback_eras)?;
+            Some(growth.saturating_mul(annualize))
+        }
+
+        /// Amount each derivative index can still bond before reaching
+        /// `StakingLedgerCap`, ordered as `DerivativeIndexList`
+        pub fn ledger_cap_headroom() -> Vec<(DerivativeIndex, BalanceOf<T>)> {
+            let cap = Self::staking_ledger_cap();
+            T::DerivativeIndexList::get()
+                .into_iter()
+                .map(|derivative_index| {
+                    (
+                        derivative_index,
+                        cap.saturating_sub(Self::total_bonded_of(derivative_index)),
+                    )
+                })
+                .collect()
+        }
+
+        /// Amount becoming withdrawable at each era across all derivative
+        /// indexes, ordered by era
+        pub fn total_unbonding_schedule() -> Vec<(EraIndex, BalanceOf<T>)> {
+            let mut schedule = BTreeMap::<EraIndex, BalanceOf<T>>::new();
+            for ledger in StakingLedgers::<T>::iter_values() {
+                for chunk in ledger.unlocking.iter() {
+                    let value = schedule.entry(chunk.era).or_default();
+                    *value = value.saturating_add(chunk.value);
+                }
+            }
+            schedule.into_iter().collect()
+        }
+
+        /// Reward snapshots of an account, empty if it didn't opt in
+        pub fn account_reward_snapshots(who: &T::AccountId) -> Vec<(EraIndex, Rate)> {
+            Self::reward_snapshots(who).unwrap_or_default()
+        }
+
+        /// Relaychain sovereign account of each derivative index, ordered as
+        /// `DerivativeIndexList`
+        pub fn derivative_accounts() -> Vec<(DerivativeIndex, T::AccountId)> {
+            T::DerivativeIndexList::get()
+                .into_iter()
+                .map(|derivative_index| {
+                    (
+                        derivative_index,
+                        Self::derivative_sovereign_account_id(derivative_index),
+                    )
+                })
+                .collect()
+        }
+
+        /// Unlocking chunks, claimable amount and fast unstake request of `who`
+        pub fn full_pending(who: &T::AccountId) -> FullPending<BalanceOf<T>> {
+            let unlockings = Self::unlockings(who)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|chunk| (chunk.era, chunk.value))
+                .collect();
+            FullPending {
+                unlockings,
+                claimable: Self::claimable_now(who),
+                fast_unstake_amount: Self::fast_unstake_requests(who).amount,
+            }
+        }
+
+        /// Free and locked portions of the stake and unstake amounts in matching pool
+        pub fn matching_pool_state() -> MatchingPoolState<BalanceOf<T>> {
+            let matching_ledger = Self::matching_pool();
+            let (stake, unstake) = (
+                matching_ledger.total_stake_amount,
+                matching_ledger.total_unstake_amount,
+            );
+            MatchingPoolState {
+                stake_free: stake.total.saturating_sub(stake.reserved),
+                stake_locked: stake.reserved,
+                unstake_free: unstake.total.saturating_sub(unstake.reserved),
+                unstake_locked: unstake.reserved,
+            }
+        }
+
+        /// Liquid amount which can be matched by fast unstake right now
+        pub fn fast_unstake_capacity() -> BalanceOf<T> {
+            Self::matching_pool()
+                .total_stake_amount
+                .free()
+                .ok()
+                .and_then(Self::staking_to_liquid)
+                .unwrap_or_default()
+        }
+
+        fn insert_xcm_request(query_id: QueryId, request: XcmRequest<T>) {
+            XcmSentInBlock::<T>::mutate(|n| *n = n.saturating_add(1));
+            XcmRequests::<T>::insert(query_id, request);
+            XcmRequestCreated::<T>::insert(
+                query_id,
+                (
+                    Self::current_era(),
+                    T::RelayChainValidationDataProvider::current_block_number(),
+                ),
+            );
+        }
+
+        fn remove_xcm_request(query_id: QueryId) {
+            XcmRequests::<T>::remove(query_id);
+            XcmRequestCreated::<T>::remove(query_id);
+        }
+
         #[require_transactional]
         fn do_update_ledger(
             derivative_index: DerivativeIndex,
@@ -1777,13 +3568,15 @@ pub mod pallet {
             );
 
             IsMatched::<T>::put(true);
+            LastMatchingBlock::<T>::put(T::RelayChainValidationDataProvider::current_block_number());
+            Self::do_record_net_flow()?;
 
             Self::do_multi_bond(bond_amount, RewardDestination::Staked)?;
             Self::do_multi_rebond(rebond_amount)?;
 
             Self::do_multi_unbond(unbond_amount)?;
 
-            Self::do_multi_withdraw_unbonded(T::NumSlashingSpans::get())?;
+            Self::do_multi_withdraw_unbonded()?;
 
             Self::deposit_event(Event::<T>::Matching(
                 bond_amount,
@@ -1794,6 +3587,118 @@ pub mod pallet {
             Ok(())
         }
 
+        fn do_record_net_flow() -> DispatchResult {
+            let matching_ledger = Self::matching_pool();
+            let stake_amount = matching_ledger.total_stake_amount.free()?;
+            let unstake_amount = matching_ledger.total_unstake_amount.free()?;
+            let current_era = Self::current_era();
+            if !stake_amount.is_zero() || !unstake_amount.is_zero() {
+                EraNetFlow::<T>::mutate(current_era, |(stake, unstake)| {
+                    *stake = stake.saturating_add(stake_amount);
+                    *unstake = unstake.saturating_add(unstake_amount);
+                });
+            }
+            Ok(())
+        }
+
+        fn do_record_exchange_rate() {
+            EraExchangeRates::<T>::insert(Self::current_era(), Self::exchange_rate());
+        }
+
+        /// Remove per era history older than `HistoryDepth` eras, at most
+        /// `MAX_HISTORY_PRUNE_PER_ERA` eras are pruned per call and the rest
+        /// is left to the next era advances
+        fn do_prune_history() {
+            let oldest_era = Self::current_era().saturating_sub(T::HistoryDepth::get());
+            // nothing older than `oldest_era` was recorded before the first prune
+            let start = Self::history_pruned_era().unwrap_or(oldest_era);
+            let end = oldest_era.min(start.saturating_add(MAX_HISTORY_PRUNE_PER_ERA));
+            for era in start..end {
+                EraNetFlow::<T>::remove(era);
+                EraExchangeRates::<T>::remove(era);
+            }
+            HistoryPrunedEra::<T>::put(end.max(start));
+        }
+
+        #[require_transactional]
+        fn do_intra_era_matching() -> DispatchResult {
+            let round = Self::matching_round().saturating_add(1);
+            MatchingRound::<T>::put(round);
+            Self::do_matching()?;
+            Self::deposit_event(Event::<T>::IntraEraMatching(Self::current_era(), round));
+            Ok(())
+        }
+
+        fn staking_ops_weight(ops: &[StakingOp<T>]) -> Weight {
+            ops.iter().fold(Weight::zero(), |weight, op| {
+                weight.saturating_add(match op {
+                    StakingOp::Bond { .. } => <T as Config>::WeightInfo::bond(),
+                    StakingOp::BondExtra { .. } => <T as Config>::WeightInfo::bond_extra(),
+                    StakingOp::Unbond { .. } => <T as Config>::WeightInfo::unbond(),
+                    StakingOp::Rebond { .. } => <T as Config>::WeightInfo::rebond(),
+                    StakingOp::Nominate { .. } => <T as Config>::WeightInfo::nominate(),
+                })
+            })
+        }
+
+        /// Claim all unlock chunks of `who` that have matured by current era
+        #[require_transactional]
+        fn do_claim_matured(who: &T::AccountId) -> DispatchResult {
+            let current_era = Self::current_era();
+
+            Unlockings::<T>::try_mutate_exists(who, |b| -> DispatchResult {
+                let mut amount: BalanceOf<T> = Zero::zero();
+                let chunks = b.as_mut().ok_or(Error::<T>::NoUnlockings)?;
+                chunks.retain(|chunk| {
+                    if chunk.era > current_era {
+                        true
+                    } else {
+                        amount += chunk.value;
+                        false
+                    }
+                });
+
+                let total_unclaimed = Self::get_total_unclaimed(Self::staking_currency()?);
+
+                log::trace!(
+                    target: "liquidStaking::claim_for",
+                    "current_era: {:?}, beneficiary: {:?}, total_unclaimed: {:?}, amount: {:?}",
+                    &current_era,
+                    &who,
+                    &total_unclaimed,
+                    amount
+                );
+
+                if amount.is_zero() {
+                    return Err(Error::<T>::NothingToClaim.into());
+                }
+
+                if total_unclaimed < amount {
+                    return Err(Error::<T>::NotWithdrawn.into());
+                }
+
+                Self::do_claim_for(who, amount)?;
+
+                if chunks.is_empty() {
+                    *b = None;
+                }
+
+                Self::deposit_event(Event::<T>::ClaimedFor(who.clone(), amount));
+                Ok(())
+            })
+        }
+
+        /// Record the era advance in `block_number`, era can only be advanced
+        /// once per block
+        fn note_era_advance(block_number: T::BlockNumber) -> DispatchResult {
+            ensure!(
+                Self::last_era_advanced_block() != Some(block_number),
+                Error::<T>::EraAlreadyAdvanced
+            );
+            LastEraAdvancedBlock::<T>::put(block_number);
+            Ok(())
+        }
+
         #[require_transactional]
         pub fn do_advance_era(offset: EraIndex) -> DispatchResult {
             if offset.is_zero() {
@@ -1810,15 +3715,42 @@ pub mod pallet {
             CurrentEra::<T>::mutate(|e| *e = e.saturating_add(offset));
 
             // ignore error
-            if let Err(e) = Self::do_update_exchange_rate() {
+            if let Err(e) = Self::do_update_exchange_rate(false) {
                 log::error!(target: "liquidStaking::do_advance_era", "advance era error caught: {:?}", &e);
             }
+            Self::do_record_exchange_rate();
+            Self::do_prune_history();
 
             IsMatched::<T>::put(false);
+            MatchingRound::<T>::kill();
+            IncentiveSpent::<T>::kill();
             Self::deposit_event(Event::<T>::NewEra(Self::current_era()));
             Ok(())
         }
 
+        fn do_pay_incentive(who: &T::AccountId) {
+            let incentive = Self::incentive();
+            if incentive.is_zero() {
+                return;
+            }
+            let incentive_spent = Self::incentive_spent().saturating_add(incentive);
+            if incentive_spent > T::IncentiveBudgetPerEra::get() {
+                Self::deposit_event(Event::<T>::IncentiveBudgetExhausted(Self::current_era()));
+                return;
+            }
+            if T::Assets::transfer(
+                T::NativeCurrency::get(),
+                &Self::account_id(),
+                who,
+                incentive,
+                false,
+            )
+            .is_ok()
+            {
+                IncentiveSpent::<T>::put(incentive_spent);
+            }
+        }
+
         #[require_transactional]
         fn do_claim_for(who: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
             let module_id = Self::account_id();
@@ -1828,11 +3760,9 @@ pub mod pallet {
             if who == &Self::loans_account_id() {
                 let account_borrows =
                     T::Loans::get_current_borrow_balance(&module_id, staking_currency)?;
-                T::Loans::do_repay_borrow(
-                    &module_id,
-                    staking_currency,
-                    min(account_borrows, amount),
-                )?;
+                let repay_amount = min(account_borrows, amount);
+                T::Loans::do_repay_borrow(&module_id, staking_currency, repay_amount)?;
+                LoansOutstandingBorrow::<T>::mutate(|b| *b = b.saturating_sub(repay_amount));
                 let redeem_amount = T::Loans::get_market_info(collateral_currency)?
                     .collateral_factor
                     .saturating_reciprocal_mul_ceil(amount);
@@ -1864,15 +3794,24 @@ pub mod pallet {
             T::Loans::do_mint(&module_id, collateral_currency, mint_amount)?;
             let _ = T::Loans::do_collateral_asset(&module_id, collateral_currency, true);
             T::Loans::do_borrow(&module_id, staking_currency, borrow_amount)?;
+            LoansOutstandingBorrow::<T>::try_mutate(|b| -> DispatchResult {
+                *b = b
+                    .checked_add(borrow_amount)
+                    .ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            })?;
             T::Assets::transfer(staking_currency, &module_id, who, borrow_amount, false)?;
 
             Ok(())
         }
 
         // liquid_amount_to_fee=TotalLiquidCurrency * (commission_rate*total_rewards/(TotalStakeCurrency+(1-commission_rate)*total_rewards))
-        fn get_inflate_liquid_amount(rewards: BalanceOf<T>) -> Result<BalanceOf<T>, DispatchError> {
-            let issuance = T::Assets::total_issuance(Self::liquid_currency()?);
-            let commission_rate = Self::commission_rate();
+        pub(crate) fn get_inflate_liquid_amount(
+            rewards: BalanceOf<T>,
+            reward_source: RewardSource,
+        ) -> Result<BalanceOf<T>, DispatchError> {
+            let issuance = Self::liquid_issuance()?;
+            let commission_rate = Self::commission_rate_of(reward_source);
             if issuance.is_zero() || commission_rate.is_zero() || rewards.is_zero() {
                 return Ok(Zero::zero());
             }
@@ -1898,23 +3837,63 @@ pub mod pallet {
         }
 
         #[require_transactional]
-        fn do_fast_match_unstake(unstaker: &T::AccountId) -> DispatchResult {
+        fn do_fast_match_unstake(unstaker: &T::AccountId, self_submitted: bool) -> DispatchResult {
             FastUnstakeRequests::<T>::try_mutate_exists(unstaker, |b| -> DispatchResult {
-                if b.is_none() {
+                let request = match b.take() {
+                    Some(request) => request,
+                    None => return Ok(()),
+                };
+                if request
+                    .expiry_era
+                    .map_or(false, |expiry_era| Self::current_era() >= expiry_era)
+                {
+                    Self::deposit_event(Event::<T>::FastUnstakeExpired(
+                        unstaker.clone(),
+                        request.amount,
+                    ));
+                    return Ok(());
+                }
+                if !request.auto_match && !self_submitted {
+                    log::trace!(
+                        target: "liquidStaking::do_fast_match_unstake",
+                        "unstaker: {:?}, skipped as auto match is disabled",
+                        unstaker,
+                    );
+                    *b = Some(request);
+                    return Ok(());
+                }
+                if request
+                    .min_rate
+                    .map_or(false, |min_rate| Self::exchange_rate() < min_rate)
+                {
+                    log::trace!(
+                        target: "liquidStaking::do_fast_match_unstake",
+                        "unstaker: {:?}, skipped below min_rate: {:?}",
+                        unstaker,
+                        request.min_rate,
+                    );
+                    *b = Some(request);
                     return Ok(());
                 }
                 let current_liquid_amount =
                     T::Assets::reducible_balance(Self::liquid_currency()?, unstaker, false);
-                let request_liquid_amount = b
-                    .take()
-                    .expect("Could not be none, qed;")
-                    .min(current_liquid_amount);
+                let request_liquid_amount = request.amount.min(current_liquid_amount);
 
                 let available_liquid_amount =
                     Self::staking_to_liquid(Self::matching_pool().total_stake_amount.free()?)
                         .ok_or(Error::<T>::InvalidExchangeRate)?;
 
                 let matched_liquid_amount = request_liquid_amount.min(available_liquid_amount);
+                if matched_liquid_amount < T::MinFastUnstakeMatch::get() {
+                    log::trace!(
+                        target: "liquidStaking::do_fast_match_unstake",
+                        "unstaker: {:?}, skipped dust match: {:?}",
+                        unstaker,
+                        matched_liquid_amount,
+                    );
+                    *b = Some(request);
+                    return Ok(());
+                }
 
                 if !matched_liquid_amount.is_zero() {
                     let matched_fee = T::MatchingPoolFastUnstakeFee::get()
@@ -1924,7 +3903,7 @@ pub mod pallet {
                     T::Assets::transfer(
                         Self::liquid_currency()?,
                         unstaker,
-                        &T::ProtocolFeeReceiver::get(),
+                        &Self::protocol_fee_receiver(),
                         matched_fee,
                         false,
                     )?;
@@ -1951,7 +3930,10 @@ pub mod pallet {
 
                 let unmatched_amount = request_liquid_amount.saturating_sub(matched_liquid_amount);
                 if !unmatched_amount.is_zero() {
-                    *b = Some(unmatched_amount);
+                    *b = Some(FastUnstakeRequest {
+                        amount: unmatched_amount,
+                        ..request
+                    });
                 }
 
                 log::trace!(
@@ -1977,6 +3959,27 @@ pub mod pallet {
             Ok(())
         }
 
+        fn ensure_proof_submitter(origin: OriginFor<T>) -> Result<T::AccountId, DispatchError> {
+            let who = ensure_signed(origin)?;
+            if T::RestrictProofSubmitters::get() && !T::Members::contains(&who) {
+                return Err(BadOrigin.into());
+            }
+            Ok(who)
+        }
+
+        fn ensure_valid_payee(payee: &RewardDestination<T::AccountId>) -> DispatchResult {
+            if let RewardDestination::Account(account) = payee {
+                ensure!(
+                    T::RewardDestinationAllowlist::contains(account)
+                        || T::DerivativeIndexList::get().iter().any(|&index| {
+                            Self::derivative_sovereign_account_id(index) == *account
+                        }),
+                    Error::<T>::InvalidRewardDestination
+                );
+            }
+            Ok(())
+        }
+
         fn ensure_market_cap(amount: BalanceOf<T>) -> DispatchResult {
             ensure!(
                 Self::get_total_bonded().saturating_add(amount) <= Self::get_market_cap(),
@@ -2008,16 +4011,12 @@ pub mod pallet {
             key: Vec<u8>,
             value: Vec<u8>,
             proof: Vec<Vec<u8>>,
-        ) -> bool {
-            let validation_data = Self::validation_data();
-            if validation_data.is_none() {
-                return false;
-            }
+        ) -> Result<(), ProofError> {
             let PersistedValidationData {
                 relay_parent_number,
                 relay_parent_storage_root,
                 ..
-            } = validation_data.expect("Could not be none, qed;");
+            } = Self::validation_data().ok_or(ProofError::NoValidationData)?;
             log::trace!(
                 target: "liquidStaking::verify_merkle_proof",
                 "relay_parent_number: {:?}, relay_parent_storage_root: {:?}",
@@ -2025,16 +4024,17 @@ pub mod pallet {
             );
             let relay_proof = StorageProof::new(proof);
             let db = relay_proof.into_memory_db();
-            if let Ok(Some(result)) = sp_trie::read_trie_value::<sp_trie::LayoutV1<BlakeTwo256>, _>(
+            let result = sp_trie::read_trie_value::<sp_trie::LayoutV1<BlakeTwo256>, _>(
                 &db,
                 &relay_parent_storage_root,
                 &key,
                 None,
                 None,
-            ) {
-                return result == value;
-            }
-            false
+            )
+            .map_err(|_| ProofError::MalformedProof)?
+            .ok_or(ProofError::KeyNotFound)?;
+            ensure!(result == value, ProofError::ValueMismatch);
+            Ok(())
         }
 
         pub(crate) fn get_staking_ledger_key(derivative_index: DerivativeIndex) -> Vec<u8> {
@@ -111,3 +111,80 @@ pub mod v3 {
         Ok(())
     }
 }
+
+pub mod v4 {
+    use crate::{
+        types::FastUnstakeRequest, BalanceOf, Config, FastUnstakeRequests, StorageVersion, Versions,
+    };
+    #[cfg(feature = "try-runtime")]
+    use codec::{Decode, Encode};
+    use frame_support::{
+        log,
+        traits::{Get, OnRuntimeUpgrade},
+        weights::Weight,
+    };
+    use sp_std::marker::PhantomData;
+    #[cfg(feature = "try-runtime")]
+    use sp_std::vec::Vec;
+
+    /// Wrap the bare amount of each fast unstake request into `FastUnstakeRequest`
+    pub struct MigrateToV4<T>(PhantomData<T>);
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            // v3 only bumped the version, so chains still on v2 are migrated as well
+            frame_support::ensure!(
+                matches!(StorageVersion::<T>::get(), Versions::V2 | Versions::V3),
+                "must upgrade linearly"
+            );
+            let count = FastUnstakeRequests::<T>::iter_keys().count() as u64;
+            log::info!(
+                target: "liquidStaking::migrations",
+                "{:?} fast unstake requests need to migrate",
+                count
+            );
+            Ok(count.encode())
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            if !matches!(StorageVersion::<T>::get(), Versions::V2 | Versions::V3) {
+                log::warn!(target: "liquidStaking::migrations", "already migrated to v4");
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut count = 0u64;
+            FastUnstakeRequests::<T>::translate::<BalanceOf<T>, _>(|_, amount| {
+                count += 1;
+                Some(FastUnstakeRequest {
+                    amount,
+                    min_rate: None,
+                    auto_match: true,
+                    expiry_era: None,
+                })
+            });
+
+            StorageVersion::<T>::put(Versions::V4);
+            log::info!(
+                target: "liquidStaking::migrations",
+                "migrated {:?} fast unstake requests to v4",
+                count
+            );
+
+            T::DbWeight::get().reads_writes(count + 1, count + 1)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+            frame_support::ensure!(
+                StorageVersion::<T>::get() == Versions::V4,
+                "must upgrade to v4"
+            );
+            let count: u64 = Decode::decode(&mut &state[..]).map_err(|_| "invalid state")?;
+            frame_support::ensure!(
+                FastUnstakeRequests::<T>::iter_values().count() as u64 == count,
+                "every fast unstake request must be migrated"
+            );
+            Ok(())
+        }
+    }
+}
@@ -4,7 +4,7 @@ use frame_support::{
     pallet_prelude::*,
     parameter_types, sp_io,
     traits::{
-        tokens::BalanceConversion, AsEnsureOriginWithArg, EitherOfDiverse, Everything,
+        tokens::BalanceConversion, AsEnsureOriginWithArg, Contains, EitherOfDiverse, Everything,
         GenesisBuild, Nothing, OriginTrait, SortedMembers,
     },
     weights::constants::WEIGHT_REF_TIME_PER_SECOND,
@@ -18,7 +18,8 @@ use polkadot_parachain::primitives::{IsSystem, Sibling};
 
 use pallet_loans::{InterestRateModel, JumpModel, Market, MarketState};
 use pallet_traits::{
-    xcm::MultiCurrencyAdapter, DecimalProvider, PriceFeeder, ValidationDataProvider,
+    xcm::MultiCurrencyAdapter, DecimalProvider, ExchangeRateChangeHandler, PriceFeeder,
+    ValidationDataProvider,
 };
 use polkadot_runtime_parachains::configuration::HostConfiguration;
 use primitives::{
@@ -333,6 +334,7 @@ parameter_types! {
     pub const LoansPalletId: PalletId = PalletId(*b"par/loan");
     pub const RewardAssetId: CurrencyId = HKO;
     pub const LiquidationFreeAssetId: CurrencyId = KSM;
+    pub const CollateralFactorDelay: BlockNumber = 10;
 }
 
 impl pallet_loans::Config for Test {
@@ -346,6 +348,7 @@ impl pallet_loans::Config for Test {
     type Assets = Assets;
     type RewardAssetId = RewardAssetId;
     type LiquidationFreeAssetId = LiquidationFreeAssetId;
+    type CollateralFactorDelay = CollateralFactorDelay;
 }
 
 parameter_types! {
@@ -458,6 +461,13 @@ impl SortedMembers<AccountId> for BobOrigin {
     }
 }
 
+pub struct RewardDestinationAllowlist;
+impl Contains<AccountId> for RewardDestinationAllowlist {
+    fn contains(who: &AccountId) -> bool {
+        *who == BOB
+    }
+}
+
 pub type RelayOrigin =
     EitherOfDiverse<EnsureRoot<AccountId>, EnsureSignedBy<AliceOrigin, AccountId>>;
 pub type UpdateOrigin =
@@ -559,19 +569,48 @@ parameter_types! {
     pub SelfParaId: ParaId = para_a_id();
     pub const MinStake: Balance = 0;
     pub const MinUnstake: Balance = 0;
+    pub static DustThreshold: Balance = 0;
+    pub static SlashingSafetyBuffer: Ratio = Ratio::zero();
+    pub static MinCommissionMint: Balance = 0;
+    pub ZeroIssuanceExchangeRate: Rate = Rate::one();
     pub const StakingCurrency: CurrencyId = KSM;
-    pub const LiquidCurrency: CurrencyId = SKSM;
+    pub static LiquidCurrency: CurrencyId = SKSM;
     pub const CollateralCurrency: CurrencyId = KSM_U;
     pub const XcmFees: Balance = 0;
     pub LoansInstantUnstakeFee: Rate = Rate::saturating_from_rational(8u32, 1000u32);
     pub MatchingPoolFastUnstakeFee: Rate = Rate::saturating_from_rational(1u32, 1000u32);
+    pub static MinFastUnstakeMatch: Balance = 0;
+    pub static RestrictProofSubmitters: bool = false;
+    pub static FastUnstakeRequestLifetime: EraIndex = 0;
     pub const BondingDuration: EraIndex = 3;
+    pub static UnbondingEraBuffer: EraIndex = 1;
     pub const MinNominatorBond: Balance = 0;
+    pub static MinXcmBondAmount: Balance = 0;
+    pub const MaxExchangeRateDeviation: Ratio = Ratio::from_percent(10);
+    pub const MaxNominationChurnPerEra: Ratio = Ratio::from_percent(50);
+    pub const MaxEraAdvancePerBlock: EraIndex = 3;
+    pub static MaxXcmPerBlock: u32 = 100;
+    pub static MaxUnstakersPerCall: u32 = 50;
+    pub const MaxNominations: u32 = 24;
+    pub const IncentiveBudgetPerEra: Balance = 1_000_000_000_000;
     pub const NumSlashingSpans: u32 = 0;
     pub static DerivativeIndexList: Vec<u16> = vec![0];
     pub static RelayChainValidationDataProvider: BlockNumber = 0;
     pub const ElectionSolutionStoredOffset: BlockNumber = 10;
+    pub static MatchingInterval: BlockNumber = 0;
+    pub static IntraEraMatchingThreshold: Balance = 0;
+    pub const HistoryDepth: EraIndex = 3;
+    pub static SplitLargeBonds: bool = false;
+    pub static BondSplitThreshold: Rate = Rate::one();
     pub const DefaultProtocolFeeReceiver: AccountId32 = AccountId32::new([100u8; 32]);
+    pub static NotifiedExchangeRate: Option<(CurrencyId, Rate)> = None;
+}
+
+pub struct MockExchangeRateChangeHandler;
+impl ExchangeRateChangeHandler<CurrencyId> for MockExchangeRateChangeHandler {
+    fn on_exchange_rate_changed(liquid_currency: &CurrencyId, new_exchange_rate: Rate) {
+        NotifiedExchangeRate::set(Some((*liquid_currency, new_exchange_rate)));
+    }
 }
 
 impl crate::Config for Test {
@@ -590,23 +629,46 @@ impl crate::Config for Test {
     type XcmFees = XcmFees;
     type LoansInstantUnstakeFee = LoansInstantUnstakeFee;
     type MatchingPoolFastUnstakeFee = MatchingPoolFastUnstakeFee;
+    type MinFastUnstakeMatch = MinFastUnstakeMatch;
+    type FastUnstakeRequestLifetime = FastUnstakeRequestLifetime;
+    type RestrictProofSubmitters = RestrictProofSubmitters;
+    type RewardDestinationAllowlist = RewardDestinationAllowlist;
     type Assets = CurrencyAdapter;
     type RelayOrigin = RelayOrigin;
     type EraLength = EraLength;
     type MinStake = MinStake;
     type MinUnstake = MinUnstake;
+    type DustThreshold = DustThreshold;
+    type SlashingSafetyBuffer = SlashingSafetyBuffer;
+    type MinCommissionMint = MinCommissionMint;
+    type ZeroIssuanceExchangeRate = ZeroIssuanceExchangeRate;
     type XCM = XcmHelper;
     type BondingDuration = BondingDuration;
+    type UnbondingEraBuffer = UnbondingEraBuffer;
     type MinNominatorBond = MinNominatorBond;
+    type MinXcmBondAmount = MinXcmBondAmount;
+    type MaxExchangeRateDeviation = MaxExchangeRateDeviation;
+    type MaxNominationChurnPerEra = MaxNominationChurnPerEra;
+    type MaxEraAdvancePerBlock = MaxEraAdvancePerBlock;
+    type MaxXcmPerBlock = MaxXcmPerBlock;
+    type MaxUnstakersPerCall = MaxUnstakersPerCall;
+    type MaxNominations = MaxNominations;
+    type IncentiveBudgetPerEra = IncentiveBudgetPerEra;
     type RelayChainValidationDataProvider = RelayChainValidationDataProvider;
     type Loans = Loans;
     type Members = BobOrigin;
     type NumSlashingSpans = NumSlashingSpans;
     type DistributionStrategy = AverageDistribution;
     type ElectionSolutionStoredOffset = ElectionSolutionStoredOffset;
+    type MatchingInterval = MatchingInterval;
+    type IntraEraMatchingThreshold = IntraEraMatchingThreshold;
+    type HistoryDepth = HistoryDepth;
+    type SplitLargeBonds = SplitLargeBonds;
+    type BondSplitThreshold = BondSplitThreshold;
     type ProtocolFeeReceiver = DefaultProtocolFeeReceiver;
     type Decimal = Decimal;
     type NativeCurrency = NativeCurrencyId;
+    type ExchangeRateChangeHandler = MockExchangeRateChangeHandler;
 }
 
 pub struct Decimal;
@@ -778,6 +840,8 @@ pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
         Loans::activate_market(RuntimeOrigin::root(), KSM).unwrap();
         Loans::add_market(RuntimeOrigin::root(), KSM_U, market_mock(PKSM_U)).unwrap();
         Loans::activate_market(RuntimeOrigin::root(), KSM_U).unwrap();
+        Loans::add_market(RuntimeOrigin::root(), SKSM, market_mock(PSKSM)).unwrap();
+        Loans::activate_market(RuntimeOrigin::root(), SKSM).unwrap();
 
         System::set_block_number(1);
         Timestamp::set_timestamp(6000);
@@ -907,6 +971,8 @@ pub fn para_ext(para_id: u32) -> sp_io::TestExternalities {
         Loans::activate_market(RuntimeOrigin::root(), KSM).unwrap();
         Loans::add_market(RuntimeOrigin::root(), KSM_U, market_mock(PKSM_U)).unwrap();
         Loans::activate_market(RuntimeOrigin::root(), KSM_U).unwrap();
+        Loans::add_market(RuntimeOrigin::root(), SKSM, market_mock(PSKSM)).unwrap();
+        Loans::activate_market(RuntimeOrigin::root(), SKSM).unwrap();
         LiquidStaking::update_staking_ledger_cap(RuntimeOrigin::signed(BOB), ksm(10000f64))
             .unwrap();
 
@@ -3,7 +3,10 @@ use frame_support::{
     dispatch::DispatchResult,
     error::BadOrigin,
     storage::with_transaction,
-    traits::{fungibles::Inspect, Hooks},
+    traits::{
+        fungibles::{Inspect, Mutate},
+        GenesisBuild, Hooks,
+    },
 };
 use sp_runtime::{
     traits::{BlakeTwo256, One, Saturating, Zero},
@@ -14,9 +17,12 @@ use sp_runtime::{
 use sp_trie::StorageProof;
 use xcm_simulator::TestExt;
 
-use pallet_traits::ump::RewardDestination;
+use pallet_traits::ump::{
+    FullPending, InvariantError, LiquidBackingInfo, MatchingPoolState, MatchingStatus,
+    RewardDestination, StakingLedgerInfo, XcmCall, XcmRequestInfo,
+};
 use primitives::{
-    tokens::{KSM, SKSM},
+    tokens::{HKO, KSM, SKSM},
     Balance, Rate, Ratio,
 };
 
@@ -143,7 +149,10 @@ fn unstake_should_work() {
         assert_ok!(LiquidStaking::unstake(
             RuntimeOrigin::signed(ALICE),
             ksm(6f64),
-            Default::default()
+            Default::default(),
+            None,
+            None,
+            None
         ));
 
         // Check storage is correct
@@ -208,7 +217,10 @@ fn unstake_should_work() {
         assert_ok!(LiquidStaking::unstake(
             RuntimeOrigin::signed(ALICE),
             ksm(3.95f64),
-            Default::default()
+            Default::default(),
+            None,
+            None,
+            None
         ));
 
         assert_eq!(
@@ -266,10 +278,15 @@ impl StakeOp {
             Self::Stake(amount) => {
                 LiquidStaking::stake(RuntimeOrigin::signed(ALICE), amount).unwrap()
             }
-            Self::Unstake(amount) => {
-                LiquidStaking::unstake(RuntimeOrigin::signed(ALICE), amount, Default::default())
-                    .unwrap()
-            }
+            Self::Unstake(amount) => LiquidStaking::unstake(
+                RuntimeOrigin::signed(ALICE),
+                amount,
+                Default::default(),
+                None,
+                None,
+                None,
+            )
+            .unwrap(),
         };
     }
 }
@@ -321,716 +338,3693 @@ fn test_matching_should_work() {
 }
 
 #[test]
-fn test_transact_bond_work() {
+fn stake_should_accrue_reserves() {
     TestNet::reset();
-    let derivative_index = 0u16;
     ParaA::execute_with(|| {
         assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(ALICE),
-            ksm(2000f64),
+            ksm(10f64)
         ));
-        assert_ok!(LiquidStaking::bond(
-            RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            ksm(3f64),
-            RewardDestination::Staked
+        assert_eq!(LiquidStaking::total_reserves(), ksm(0.05f64));
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::ReservesAccrued(ksm(0.05f64)),
         ));
-
-        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(crate::Event::Bonding(
-            derivative_index,
-            LiquidStaking::derivative_sovereign_account_id(derivative_index),
-            ksm(3f64),
-            RewardDestination::Staked,
-        )));
     });
+}
 
-    Relay::execute_with(|| {
-        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Bonded {
-            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
-            amount: ksm(3f64),
-        }));
-        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
-            derivative_index,
-        ))
-        .unwrap();
-        assert_eq!(ledger.total, ksm(3f64));
+#[test]
+fn stake_should_fail_if_currency_decimal_not_registered() {
+    new_test_ext().execute_with(|| {
+        LiquidCurrency::set(4242);
+        assert_noop!(
+            LiquidStaking::liquid_currency(),
+            Error::<Test>::CurrencyDecimalNotRegistered
+        );
+        assert_noop!(
+            LiquidStaking::stake(RuntimeOrigin::signed(ALICE), ksm(10f64)),
+            Error::<Test>::CurrencyDecimalNotRegistered
+        );
     });
 }
 
 #[test]
-fn test_transact_bond_extra_work() {
-    TestNet::reset();
-    let derivative_index = 0u16;
-    ParaA::execute_with(|| {
+fn stake_and_collateralize_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake_and_collateralize(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        // Liquid currency goes straight into loans instead of user's balance
+        assert_eq!(<Test as Config>::Assets::balance(SKSM, &ALICE), ksm(100f64));
+        assert_eq!(
+            <Test as Config>::Loans::get_current_collateral_balance(&ALICE, SKSM),
+            Ok(ksm(9.95f64))
+        );
+        assert!(Loans::account_deposits(SKSM, ALICE).is_collateral);
+
+        // Whole call is rolled back if loans refuses the deposit
+        assert_ok!(Loans::update_market(
+            RuntimeOrigin::root(),
+            SKSM,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(ksm(1f64)),
+            None,
+        ));
+        assert_noop!(
+            LiquidStaking::stake_and_collateralize(RuntimeOrigin::signed(ALICE), ksm(10f64)),
+            pallet_loans::Error::<Test>::SupplyCapacityExceeded
+        );
+    })
+}
+
+#[test]
+fn unstake_should_follow_unbonding_era_buffer() {
+    new_test_ext().execute_with(|| {
         assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(ALICE),
-            ksm(4000f64),
+            ksm(20f64)
         ));
-        let bond_amount = ksm(2f64);
-        assert_ok!(LiquidStaking::bond(
+        UnbondingEraBuffer::set(0);
+        assert_eq!(LiquidStaking::target_era(), 3);
+        assert_ok!(LiquidStaking::unstake(
             RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            bond_amount,
-            RewardDestination::Staked
+            ksm(6f64),
+            Default::default(),
+            None,
+            None,
+            None
         ));
-        assert_ok!(LiquidStaking::notification_received(
-            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
-            0,
-            Response::ExecutionResult(None),
+        assert_eq!(
+            Unlockings::<Test>::get(ALICE).unwrap(),
+            vec![UnlockChunk {
+                value: ksm(6f64),
+                era: 3
+            }]
+        );
+
+        UnbondingEraBuffer::set(2);
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(4f64),
+            Default::default(),
+            None,
+            None,
+            None
         ));
+        assert_eq!(
+            Unlockings::<Test>::get(ALICE).unwrap(),
+            vec![
+                UnlockChunk {
+                    value: ksm(6f64),
+                    era: 3
+                },
+                UnlockChunk {
+                    value: ksm(4f64),
+                    era: 5
+                }
+            ]
+        );
+    })
+}
 
-        assert_ok!(LiquidStaking::bond_extra(
+#[test]
+fn preview_stake_should_match_stake() {
+    new_test_ext().execute_with(|| {
+        // reserve factor is 0.5%, xcm fees are 0 in mock
+        assert_eq!(
+            LiquidStaking::preview_stake(ksm(10f64)),
+            Ok((ksm(9.95f64), ksm(0.05f64), 0))
+        );
+
+        let liquid_amount = <Test as Config>::Assets::balance(SKSM, &ALICE);
+        assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            ksm(3f64)
+            ksm(10f64)
         ));
-    });
+        assert_eq!(
+            <Test as Config>::Assets::balance(SKSM, &ALICE) - liquid_amount,
+            ksm(9.95f64)
+        );
+        assert_eq!(LiquidStaking::total_reserves(), ksm(0.05f64));
 
-    Relay::execute_with(|| {
-        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
-            derivative_index,
-        ))
-        .unwrap();
-        assert_eq!(ledger.total, ksm(5f64));
-    });
+        assert_ok!(LiquidStaking::update_staking_ledger_cap(
+            RuntimeOrigin::signed(BOB),
+            ksm(10f64)
+        ));
+        assert_noop!(
+            LiquidStaking::preview_stake(ksm(10f64)),
+            Error::<Test>::CapExceeded
+        );
+    })
 }
 
 #[test]
-fn test_transact_unbond_work() {
+fn stake_up_to_cap_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::update_staking_ledger_cap(
+            RuntimeOrigin::signed(BOB),
+            ksm(10f64)
+        ));
+        assert_noop!(
+            LiquidStaking::stake(RuntimeOrigin::signed(ALICE), ksm(20f64)),
+            Error::<Test>::CapExceeded
+        );
+
+        let balance = <Test as Config>::Assets::balance(KSM, &ALICE);
+        // 10 / (1 - 0.005)
+        assert_eq!(LiquidStaking::max_stake_amount(), 10050251256281u128);
+        assert_ok!(LiquidStaking::stake_up_to_cap(
+            RuntimeOrigin::signed(ALICE),
+            ksm(20f64)
+        ));
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(crate::Event::Staked(
+            ALICE,
+            ksm(10f64),
+        )));
+        assert_eq!(
+            <Test as Config>::Assets::balance(KSM, &ALICE),
+            balance - 10050251256281u128
+        );
+    })
+}
+
+#[test]
+fn dust_bond_should_be_skipped() {
     TestNet::reset();
-    let derivative_index = 0u16;
     ParaA::execute_with(|| {
+        MinXcmBondAmount::set(ksm(100f64));
         assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(ALICE),
-            ksm(6000f64),
+            ksm(10f64)
         ));
-        assert_ok!(LiquidStaking::unstake(
-            RuntimeOrigin::signed(ALICE),
-            ksm(1000f64),
-            Default::default()
+        let bond_amount = LiquidStaking::matching_pool()
+            .total_stake_amount
+            .free()
+            .unwrap();
+
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                LiquidStaking::do_advance_era(1).unwrap();
+                LiquidStaking::do_matching().unwrap();
+                TransactionOutcome::Commit(Ok(()))
+            }
         ));
-        let bond_amount = ksm(5f64);
 
-        assert_ok!(LiquidStaking::bond(
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::DustBondSkipped(0, bond_amount),
+        ));
+        assert_eq!(XcmRequests::<Test>::iter().count(), 0);
+        // The dust stays in the matching pool for the next round
+        assert_eq!(
+            LiquidStaking::matching_pool().total_stake_amount,
+            ReservableAmount {
+                total: bond_amount,
+                reserved: 0
+            }
+        );
+    });
+}
+
+#[test]
+fn pending_xcm_requests_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            bond_amount,
-            RewardDestination::Staked
+            ksm(10f64)
+        ));
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                LiquidStaking::do_advance_era(1).unwrap();
+                LiquidStaking::do_matching().unwrap();
+                TransactionOutcome::Commit(Ok(()))
+            }
         ));
+        assert_eq!(
+            LiquidStaking::pending_xcm_requests(),
+            vec![(
+                0,
+                XcmRequestInfo {
+                    kind: XcmCall::Bond,
+                    index: 0,
+                    amount: ksm(9.95f64),
+                    created_era: Some(1),
+                }
+            )]
+        );
 
         assert_ok!(LiquidStaking::notification_received(
             pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
             0,
             Response::ExecutionResult(None),
         ));
-        assert_ok!(LiquidStaking::unbond(
-            RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            ksm(2f64)
-        ));
-    });
+        assert!(LiquidStaking::pending_xcm_requests().is_empty());
+        assert_eq!(LiquidStaking::xcm_request_created(0), None);
+    })
+}
 
-    Relay::execute_with(|| {
-        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Bonded {
-            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
-            amount: ksm(5f64),
-        }));
-        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Unbonded {
-            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
-            amount: ksm(2f64),
-        }));
-        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
-            derivative_index,
-        ))
-        .unwrap();
-        assert_eq!(ledger.total, ksm(5f64));
-        assert_eq!(ledger.active, ksm(3f64));
-    });
+#[test]
+fn matching_status_should_work() {
+    new_test_ext().execute_with(|| {
+        RelayChainValidationDataProvider::set(5);
+        assert_eq!(LiquidStaking::matching_status(), MatchingStatus::NotDue);
+
+        RelayChainValidationDataProvider::set(10);
+        assert_eq!(LiquidStaking::matching_status(), MatchingStatus::Due);
+
+        assert_ok!(LiquidStaking::force_matching(RuntimeOrigin::root()));
+        assert_eq!(LiquidStaking::matching_status(), MatchingStatus::Completed);
+    })
 }
 
 #[test]
-fn test_transact_withdraw_unbonded_work() {
-    TestNet::reset();
-    let derivative_index = 0u16;
-    ParaA::execute_with(|| {
+fn intra_era_matching_should_work() {
+    new_test_ext().execute_with(|| {
+        MatchingInterval::set(5);
+        IntraEraMatchingThreshold::set(ksm(5f64));
+
         assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(ALICE),
-            ksm(6000f64),
+            ksm(10f64)
         ));
-        assert_ok!(LiquidStaking::unstake(
-            RuntimeOrigin::signed(ALICE),
-            ksm(2000f64),
-            Default::default()
+        RelayChainValidationDataProvider::set(100);
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                LiquidStaking::do_advance_era(1).unwrap();
+                LiquidStaking::do_matching().unwrap();
+                TransactionOutcome::Commit(Ok(()))
+            }
         ));
-        let bond_amount = ksm(5f64);
-        let unbond_amount = ksm(2f64);
-        assert_ok!(LiquidStaking::bond(
+        assert_eq!(LiquidStaking::last_matching_block(), 100);
+
+        assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            bond_amount,
-            RewardDestination::Staked
+            ksm(10f64)
         ));
-        assert_ok!(LiquidStaking::notification_received(
-            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
-            0,
-            Response::ExecutionResult(None),
+        // before the interval has passed
+        RelayChainValidationDataProvider::set(104);
+        LiquidStaking::on_initialize(1);
+        assert_eq!(LiquidStaking::matching_round(), 0);
+
+        RelayChainValidationDataProvider::set(105);
+        LiquidStaking::on_initialize(2);
+        assert_eq!(LiquidStaking::matching_round(), 1);
+        assert_eq!(LiquidStaking::last_matching_block(), 105);
+        assert_eq!(
+            MatchingPool::<Test>::get().total_stake_amount,
+            ReservableAmount {
+                total: ksm(19.9f64),
+                reserved: ksm(19.9f64)
+            }
+        );
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::IntraEraMatching(1, 1),
         ));
-        assert_ok!(LiquidStaking::unbond(
+
+        // below the threshold
+        assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            unbond_amount
-        ));
-        assert_ok!(LiquidStaking::notification_received(
-            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
-            1,
-            Response::ExecutionResult(None),
+            ksm(1f64)
         ));
-    });
+        RelayChainValidationDataProvider::set(109);
+        LiquidStaking::on_initialize(3);
+        assert_eq!(LiquidStaking::matching_round(), 1);
 
-    Relay::execute_with(|| {
-        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
-            derivative_index,
-        ))
-        .unwrap();
-        assert_eq!(ledger.total, ksm(5f64));
-        assert_eq!(ledger.active, ksm(3f64));
-        assert_eq!(ledger.unlocking.len(), 1);
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                LiquidStaking::do_advance_era(1).unwrap();
+                TransactionOutcome::Commit(Ok(()))
+            }
+        ));
+        assert_eq!(LiquidStaking::matching_round(), 0);
+    })
+}
 
-        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Bonded {
-            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
-            amount: ksm(5f64),
-        }));
-        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Unbonded {
-            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
-            amount: ksm(2f64),
-        }));
+#[test]
+fn update_parameters_should_work() {
+    new_test_ext().execute_with(|| {
+        let params = ParametersUpdate {
+            reserve_factor: Some(Ratio::from_percent(1)),
+            commission_rate: None,
+            staking_ledger_cap: Some(ksm(1000f64)),
+            incentive: Some(ksm(1f64)),
+        };
+        assert_noop!(
+            LiquidStaking::update_parameters(RuntimeOrigin::signed(ALICE), params.clone()),
+            BadOrigin
+        );
 
-        pallet_staking::CurrentEra::<KusamaRuntime>::put(
-            <KusamaRuntime as pallet_staking::Config>::BondingDuration::get(),
+        // nothing is applied if any field is out of bounds
+        let commission_rate = LiquidStaking::commission_rate();
+        assert_noop!(
+            LiquidStaking::update_parameters(
+                RuntimeOrigin::signed(BOB),
+                ParametersUpdate {
+                    commission_rate: Some(Rate::one()),
+                    ..params.clone()
+                }
+            ),
+            Error::<Test>::InvalidCommissionRate
         );
-    });
 
-    ParaA::execute_with(|| {
-        assert_ok!(LiquidStaking::force_set_current_era(
-            RuntimeOrigin::root(),
-            <KusamaRuntime as pallet_staking::Config>::BondingDuration::get(),
+        assert_ok!(LiquidStaking::update_parameters(
+            RuntimeOrigin::signed(BOB),
+            params.clone()
         ));
-
-        assert_ok!(LiquidStaking::withdraw_unbonded(
-            RuntimeOrigin::root(),
-            derivative_index,
-            0
+        assert_eq!(LiquidStaking::reserve_factor(), Ratio::from_percent(1));
+        assert_eq!(LiquidStaking::commission_rate(), commission_rate);
+        assert_eq!(LiquidStaking::staking_ledger_cap(), ksm(1000f64));
+        assert_eq!(LiquidStaking::incentive(), ksm(1f64));
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::ParametersUpdated(params),
         ));
-    });
-
-    Relay::execute_with(|| {
-        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
-            derivative_index,
-        ))
-        .unwrap();
-        assert_eq!(ledger.total, ksm(3f64));
-        assert_eq!(ledger.active, ksm(3f64));
-        assert_eq!(ledger.unlocking.len(), 0);
-    });
+    })
 }
 
 #[test]
-fn test_transact_rebond_work() {
-    TestNet::reset();
-    let derivative_index = 0u16;
-    ParaA::execute_with(|| {
+fn large_bond_should_be_split() {
+    new_test_ext().execute_with(|| {
+        SplitLargeBonds::set(true);
+        BondSplitThreshold::set(Rate::saturating_from_rational(1, 2));
         assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(ALICE),
-            ksm(6000f64),
-        ));
-        assert_ok!(LiquidStaking::unstake(
-            RuntimeOrigin::signed(ALICE),
-            ksm(1000f64),
-            Default::default()
+            ksm(10f64)
         ));
-        let bond_amount = ksm(10f64);
-        assert_ok!(LiquidStaking::bond(
-            RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            bond_amount,
-            RewardDestination::Staked
+        StakingLedgers::<Test>::insert(
+            0,
+            <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+                LiquidStaking::derivative_sovereign_account_id(0),
+                ksm(4f64),
+            ),
+        );
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                LiquidStaking::do_advance_era(1).unwrap();
+                LiquidStaking::do_matching().unwrap();
+                TransactionOutcome::Commit(Ok(()))
+            }
         ));
 
-        assert_ok!(LiquidStaking::notification_received(
-            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+        // only half of the active bonded is bonded extra, the rest stays free
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(crate::Event::BondSplit(
             0,
-            Response::ExecutionResult(None),
-        ));
-        assert_ok!(LiquidStaking::unbond(
+            ksm(2f64),
+            ksm(7.95f64),
+        )));
+        assert_eq!(
+            MatchingPool::<Test>::get().total_stake_amount,
+            ReservableAmount {
+                total: ksm(9.95f64),
+                reserved: ksm(2f64)
+            }
+        );
+    })
+}
+
+#[test]
+fn bond_should_be_deferred_when_max_xcm_per_block_reached() {
+    new_test_ext().execute_with(|| {
+        DerivativeIndexList::set(vec![0, 1]);
+        MaxXcmPerBlock::set(1);
+        assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            ksm(5f64)
+            ksm(10f64)
         ));
-        assert_ok!(LiquidStaking::rebond(
-            RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            ksm(3f64)
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                LiquidStaking::do_advance_era(1).unwrap();
+                LiquidStaking::do_matching().unwrap();
+                TransactionOutcome::Commit(Ok(()))
+            }
         ));
-    });
 
-    Relay::execute_with(|| {
-        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Bonded {
-            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
-            amount: ksm(10f64),
-        }));
-        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Unbonded {
-            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
-            amount: ksm(5f64),
-        }));
-        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Bonded {
-            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
-            amount: ksm(3f64),
-        }));
-        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
-            derivative_index,
-        ))
-        .unwrap();
-        assert_eq!(ledger.total, ksm(10f64));
-        assert_eq!(ledger.active, ksm(8f64));
-    });
+        // 9.95 KSM is split between two indexes, only index 0 is bonded
+        assert_eq!(LiquidStaking::xcm_sent_in_block(), 1);
+        assert_eq!(
+            LiquidStaking::deferred_bonds(1),
+            Some((ksm(4.975f64), RewardDestination::Staked))
+        );
+        assert_eq!(
+            MatchingPool::<Test>::get().total_stake_amount,
+            ReservableAmount {
+                total: ksm(9.95f64),
+                reserved: ksm(4.975f64)
+            }
+        );
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::XcmDeferred(1, ksm(4.975f64)),
+        ));
+
+        LiquidStaking::on_finalize(1);
+        assert_eq!(LiquidStaking::xcm_sent_in_block(), 0);
+        LiquidStaking::on_initialize(2);
+        assert!(DeferredBonds::<Test>::iter().next().is_none());
+        assert_eq!(
+            MatchingPool::<Test>::get().total_stake_amount,
+            ReservableAmount {
+                total: ksm(9.95f64),
+                reserved: ksm(9.95f64)
+            }
+        );
+        assert_eq!(
+            XcmRequests::<Test>::get(1),
+            Some(XcmRequest::Bond {
+                index: 1,
+                amount: ksm(4.975f64)
+            })
+        );
+    })
 }
 
 #[test]
-fn test_transact_nominate_work() {
-    TestNet::reset();
-    let derivative_index = 0u16;
-    ParaA::execute_with(|| {
+fn failed_deferred_bond_should_be_dropped() {
+    new_test_ext().execute_with(|| {
         assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(ALICE),
-            ksm(4000f64),
+            ksm(10f64)
         ));
-        let bond_amount = ksm(10f64);
-        assert_ok!(LiquidStaking::bond(
+        // index 5 is not in `DerivativeIndexList`
+        DeferredBonds::<Test>::insert(5, (ksm(1f64), RewardDestination::Staked));
+
+        LiquidStaking::on_initialize(2);
+        assert!(DeferredBonds::<Test>::iter().next().is_none());
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::DeferredBondFailed(
+                5,
+                ksm(1f64),
+                Error::<Test>::InvalidDerivativeIndex.into(),
+            ),
+        ));
+        assert_eq!(
+            MatchingPool::<Test>::get().total_stake_amount,
+            ReservableAmount {
+                total: ksm(9.95f64),
+                reserved: 0
+            }
+        );
+        assert!(!ParaSystem::events().iter().any(|record| matches!(
+            record.event,
+            mock::RuntimeEvent::LiquidStaking(crate::Event::OnInitializeFailed(
+                OnInitializeStep::DeferredBonds,
+                _
+            ))
+        )));
+    })
+}
+#[test]
+fn force_complete_xcm_request_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            bond_amount,
-            RewardDestination::Staked
+            ksm(10f64)
+        ));
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                LiquidStaking::do_advance_era(1).unwrap();
+                LiquidStaking::do_matching().unwrap();
+                TransactionOutcome::Commit(Ok(()))
+            }
         ));
+        assert_eq!(
+            MatchingPool::<Test>::get().total_stake_amount,
+            ReservableAmount {
+                total: ksm(9.95f64),
+                reserved: ksm(9.95f64)
+            }
+        );
 
-        assert_ok!(LiquidStaking::notification_received(
-            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+        assert_noop!(
+            LiquidStaking::force_complete_xcm_request(RuntimeOrigin::signed(ALICE), 0, false),
+            BadOrigin
+        );
+        assert_noop!(
+            LiquidStaking::force_complete_xcm_request(RuntimeOrigin::signed(BOB), 1, false),
+            Error::<Test>::XcmRequestNotFound
+        );
+
+        // discarding releases the lock and keeps the stake in matching pool
+        assert_ok!(LiquidStaking::force_complete_xcm_request(
+            RuntimeOrigin::signed(BOB),
             0,
-            Response::ExecutionResult(None),
+            false
         ));
+        assert!(XcmRequests::<Test>::get(0).is_none());
+        assert_eq!(
+            MatchingPool::<Test>::get().total_stake_amount,
+            ReservableAmount {
+                total: ksm(9.95f64),
+                reserved: 0
+            }
+        );
+        assert!(StakingLedgers::<Test>::get(0).is_none());
 
-        assert_ok!(LiquidStaking::nominate(
-            RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            vec![ALICE, BOB],
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                LiquidStaking::do_advance_era(1).unwrap();
+                LiquidStaking::do_matching().unwrap();
+                TransactionOutcome::Commit(Ok(()))
+            }
         ));
-    });
 
-    Relay::execute_with(|| {
-        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
-            derivative_index,
-        ))
-        .unwrap();
-        assert_eq!(ledger.total, ksm(10f64));
-        let nominators = RelayStaking::nominators(LiquidStaking::derivative_sovereign_account_id(
-            derivative_index,
-        ))
-        .unwrap();
-        assert_eq!(nominators.targets, vec![ALICE, BOB]);
-    });
+        // assuming success settles the request like a relaychain response
+        assert_ok!(LiquidStaking::force_complete_xcm_request(
+            RuntimeOrigin::signed(BOB),
+            1,
+            true
+        ));
+        assert!(XcmRequests::<Test>::get(1).is_none());
+        assert_eq!(
+            MatchingPool::<Test>::get(),
+            MatchingLedger {
+                total_stake_amount: Default::default(),
+                total_unstake_amount: Default::default(),
+            }
+        );
+        assert_eq!(StakingLedgers::<Test>::get(0).unwrap().active, ksm(9.95f64));
+    })
 }
 
 #[test]
-fn test_transfer_bond() {
-    TestNet::reset();
-    let xcm_transfer_amount = ksm(10f64);
-    let derivative_index = 0u16;
-    ParaA::execute_with(|| {
+fn prune_stale_xcm_requests_works() {
+    new_test_ext().execute_with(|| {
+        RelayChainValidationDataProvider::set(100);
         assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(ALICE),
-            ksm(2000f64),
+            ksm(10f64)
         ));
-        assert_ok!(LiquidStaking::bond(
-            RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            xcm_transfer_amount,
-            RewardDestination::Staked
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                LiquidStaking::do_advance_era(1).unwrap();
+                LiquidStaking::do_matching().unwrap();
+                TransactionOutcome::Commit(Ok(()))
+            }
+        ));
+        assert_eq!(
+            LiquidStaking::xcm_request_created(0).map(|(_, created_at)| created_at),
+            Some(100)
+        );
+
+        assert_noop!(
+            LiquidStaking::prune_stale_xcm_requests(RuntimeOrigin::signed(ALICE), vec![0], 10),
+            BadOrigin
+        );
+
+        // request is not old enough yet
+        RelayChainValidationDataProvider::set(109);
+        assert_ok!(LiquidStaking::prune_stale_xcm_requests(
+            RuntimeOrigin::signed(BOB),
+            vec![0, 1],
+            10
+        ));
+        assert!(XcmRequests::<Test>::get(0).is_some());
+
+        RelayChainValidationDataProvider::set(110);
+        assert_ok!(LiquidStaking::prune_stale_xcm_requests(
+            RuntimeOrigin::signed(BOB),
+            vec![0, 1],
+            10
+        ));
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::StaleXcmRequestPruned(0),
+        ));
+        assert!(XcmRequests::<Test>::get(0).is_none());
+        assert_eq!(LiquidStaking::xcm_request_created(0), None);
+        assert_eq!(
+            MatchingPool::<Test>::get().total_stake_amount,
+            ReservableAmount {
+                total: ksm(9.95f64),
+                reserved: 0
+            }
+        );
+    })
+}
+
+#[test]
+fn recompute_matching_locks_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                LiquidStaking::do_advance_era(1).unwrap();
+                LiquidStaking::do_matching().unwrap();
+                TransactionOutcome::Commit(Ok(()))
+            }
+        ));
+        assert_eq!(
+            MatchingPool::<Test>::get().total_stake_amount.reserved,
+            ksm(9.95f64)
+        );
+
+        // locks drifted away from the pending requests
+        MatchingPool::<Test>::mutate(|p| p.total_stake_amount.reserved = 0);
+        assert_noop!(
+            LiquidStaking::recompute_matching_locks(RuntimeOrigin::signed(ALICE)),
+            BadOrigin
+        );
+        assert_ok!(LiquidStaking::recompute_matching_locks(
+            RuntimeOrigin::signed(BOB)
+        ));
+        assert_eq!(
+            MatchingPool::<Test>::get().total_stake_amount,
+            ReservableAmount {
+                total: ksm(9.95f64),
+                reserved: ksm(9.95f64)
+            }
+        );
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::MatchingLocksRepaired(ksm(9.95f64), 0),
+        ));
+    })
+}
+
+#[test]
+fn test_transact_bond_work() {
+    TestNet::reset();
+    let derivative_index = 0u16;
+    ParaA::execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(2000f64),
+        ));
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            ksm(3f64),
+            RewardDestination::Staked
+        ));
+
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(crate::Event::Bonding(
+            derivative_index,
+            LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            ksm(3f64),
+            RewardDestination::Staked,
+        )));
+    });
+
+    Relay::execute_with(|| {
+        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Bonded {
+            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            amount: ksm(3f64),
+        }));
+        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
+            derivative_index,
+        ))
+        .unwrap();
+        assert_eq!(ledger.total, ksm(3f64));
+    });
+}
+
+#[test]
+fn bond_should_use_payee_override() {
+    TestNet::reset();
+    let derivative_index = 0u16;
+    ParaA::execute_with(|| {
+        assert_noop!(
+            LiquidStaking::set_payee_override(
+                RuntimeOrigin::signed(BOB),
+                1u16,
+                Some(RewardDestination::Account(BOB)),
+            ),
+            Error::<Test>::InvalidDerivativeIndex
+        );
+        assert_ok!(LiquidStaking::set_payee_override(
+            RuntimeOrigin::signed(BOB),
+            derivative_index,
+            Some(RewardDestination::Account(BOB)),
+        ));
+        assert_eq!(
+            LiquidStaking::payee_override(derivative_index),
+            Some(RewardDestination::Account(BOB))
+        );
+
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(2000f64),
+        ));
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            ksm(3f64),
+            RewardDestination::Staked
+        ));
+
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(crate::Event::Bonding(
+            derivative_index,
+            LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            ksm(3f64),
+            RewardDestination::Account(BOB),
+        )));
+
+        assert_ok!(LiquidStaking::set_payee_override(
+            RuntimeOrigin::signed(BOB),
+            derivative_index,
+            None,
+        ));
+        assert_eq!(LiquidStaking::payee_override(derivative_index), None);
+    });
+}
+
+#[test]
+fn bond_should_set_payee_when_changed() {
+    new_test_ext().execute_with(|| {
+        let derivative_index = 0u16;
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            ksm(3f64),
+            RewardDestination::Staked
+        ));
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        ));
+        assert_eq!(
+            LiquidStaking::payee(derivative_index),
+            Some(RewardDestination::Staked)
+        );
+
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            ksm(1f64),
+            RewardDestination::Stash
+        ));
+        assert_eq!(
+            XcmRequests::<Test>::get(1),
+            Some(XcmRequest::SetPayee {
+                index: derivative_index,
+                payee: RewardDestination::Stash,
+            })
+        );
+        assert_eq!(
+            XcmRequests::<Test>::get(2),
+            Some(XcmRequest::BondExtra {
+                index: derivative_index,
+                amount: ksm(1f64),
+            })
+        );
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::PayeeUpdated(derivative_index, RewardDestination::Stash),
+        ));
+
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            1,
+            Response::ExecutionResult(None),
+        ));
+        assert_eq!(
+            LiquidStaking::payee(derivative_index),
+            Some(RewardDestination::Stash)
+        );
+
+        // unchanged payee only bonds extra
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            ksm(1f64),
+            RewardDestination::Stash
+        ));
+        assert_eq!(
+            XcmRequests::<Test>::get(3),
+            Some(XcmRequest::BondExtra {
+                index: derivative_index,
+                amount: ksm(1f64),
+            })
+        );
+        assert_eq!(XcmRequests::<Test>::get(4), None);
+    })
+}
+
+#[test]
+fn bond_should_not_resend_pending_set_payee() {
+    new_test_ext().execute_with(|| {
+        let derivative_index = 0u16;
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            ksm(3f64),
+            RewardDestination::Staked
+        ));
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        ));
+
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            ksm(1f64),
+            RewardDestination::Stash
+        ));
+        assert_eq!(
+            XcmRequests::<Test>::get(1),
+            Some(XcmRequest::SetPayee {
+                index: derivative_index,
+                payee: RewardDestination::Stash,
+            })
+        );
+
+        // set_payee is still pending so it's not sent again
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            ksm(1f64),
+            RewardDestination::Stash
+        ));
+        assert_eq!(
+            XcmRequests::<Test>::get(3),
+            Some(XcmRequest::BondExtra {
+                index: derivative_index,
+                amount: ksm(1f64),
+            })
+        );
+        assert_eq!(XcmRequests::<Test>::get(4), None);
+    })
+}
+
+#[test]
+fn execute_staking_ops_should_work() {
+    new_test_ext().execute_with(|| {
+        let derivative_index = 0u16;
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        assert_noop!(
+            LiquidStaking::execute_staking_ops(RuntimeOrigin::signed(BOB), vec![]),
+            BadOrigin
+        );
+        assert_ok!(LiquidStaking::execute_staking_ops(
+            RuntimeOrigin::signed(ALICE),
+            vec![StakingOp::Bond {
+                index: derivative_index,
+                amount: ksm(3f64),
+                payee: RewardDestination::Staked,
+            }]
+        ));
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        ));
+
+        // nothing is sent if any op fails
+        assert_noop!(
+            LiquidStaking::execute_staking_ops(
+                RuntimeOrigin::signed(ALICE),
+                vec![
+                    StakingOp::BondExtra {
+                        index: derivative_index,
+                        amount: ksm(1f64),
+                    },
+                    StakingOp::BondExtra {
+                        index: 1,
+                        amount: ksm(1f64),
+                    },
+                ]
+            ),
+            Error::<Test>::InvalidDerivativeIndex
+        );
+
+        assert_ok!(LiquidStaking::execute_staking_ops(
+            RuntimeOrigin::signed(ALICE),
+            vec![
+                StakingOp::BondExtra {
+                    index: derivative_index,
+                    amount: ksm(1f64),
+                },
+                StakingOp::Nominate {
+                    index: derivative_index,
+                    targets: vec![ALICE, BOB],
+                },
+            ]
+        ));
+        assert_eq!(
+            XcmRequests::<Test>::get(1),
+            Some(XcmRequest::BondExtra {
+                index: derivative_index,
+                amount: ksm(1f64),
+            })
+        );
+        assert_eq!(
+            XcmRequests::<Test>::get(2),
+            Some(XcmRequest::Nominate {
+                index: derivative_index,
+                targets: vec![ALICE, BOB],
+            })
+        );
+    })
+}
+
+#[test]
+fn bond_should_validate_reward_destination() {
+    TestNet::reset();
+    let derivative_index = 0u16;
+    ParaA::execute_with(|| {
+        assert_noop!(
+            LiquidStaking::set_payee_override(
+                RuntimeOrigin::signed(BOB),
+                derivative_index,
+                Some(RewardDestination::Account(ALICE)),
+            ),
+            Error::<Test>::InvalidRewardDestination
+        );
+
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(2000f64),
+        ));
+        assert_noop!(
+            LiquidStaking::bond(
+                RuntimeOrigin::signed(ALICE),
+                derivative_index,
+                ksm(3f64),
+                RewardDestination::Account(ALICE)
+            ),
+            Error::<Test>::InvalidRewardDestination
+        );
+
+        let sovereign_account = LiquidStaking::derivative_sovereign_account_id(derivative_index);
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            ksm(3f64),
+            RewardDestination::Account(sovereign_account.clone())
+        ));
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(crate::Event::Bonding(
+            derivative_index,
+            sovereign_account.clone(),
+            ksm(3f64),
+            RewardDestination::Account(sovereign_account),
+        )));
+    });
+}
+
+#[test]
+fn test_transact_bond_extra_work() {
+    TestNet::reset();
+    let derivative_index = 0u16;
+    ParaA::execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(4000f64),
+        ));
+        let bond_amount = ksm(2f64);
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            bond_amount,
+            RewardDestination::Staked
+        ));
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        ));
+
+        assert_ok!(LiquidStaking::bond_extra(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            ksm(3f64)
+        ));
+    });
+
+    Relay::execute_with(|| {
+        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
+            derivative_index,
+        ))
+        .unwrap();
+        assert_eq!(ledger.total, ksm(5f64));
+    });
+}
+
+#[test]
+fn unbond_should_respect_per_index_min_bond() {
+    TestNet::reset();
+    let derivative_index = 0u16;
+    ParaA::execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(6000f64),
+        ));
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(1000f64),
+            Default::default(),
+            None,
+            None,
+            None
+        ));
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            ksm(5f64),
+            RewardDestination::Staked
+        ));
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        ));
+
+        assert_ok!(LiquidStaking::set_per_index_min_bond(
+            RuntimeOrigin::signed(BOB),
+            derivative_index,
+            Some(ksm(4f64)),
+        ));
+        assert_noop!(
+            LiquidStaking::unbond(RuntimeOrigin::signed(ALICE), derivative_index, ksm(2f64)),
+            Error::<Test>::InsufficientBond
+        );
+        assert_ok!(LiquidStaking::unbond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            ksm(1f64)
+        ));
+
+        assert_ok!(LiquidStaking::set_per_index_min_bond(
+            RuntimeOrigin::signed(BOB),
+            derivative_index,
+            None,
+        ));
+        assert_eq!(LiquidStaking::per_index_min_bond(derivative_index), None);
+    });
+}
+
+#[test]
+fn matching_should_withdraw_unbonded_with_slashing_spans() {
+    TestNet::reset();
+    ParaA::execute_with(|| {
+        let derivative_index = 0u16;
+        let mut staking_ledger = <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+            LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            ksm(10f64),
+        );
+        staking_ledger.unbond(ksm(1f64), 0);
+        StakingLedgers::<Test>::insert(derivative_index, staking_ledger);
+
+        assert_noop!(
+            LiquidStaking::set_slashing_spans(RuntimeOrigin::signed(BOB), 1, Some(2)),
+            Error::<Test>::InvalidDerivativeIndex
+        );
+        assert_ok!(LiquidStaking::set_slashing_spans(
+            RuntimeOrigin::signed(BOB),
+            derivative_index,
+            Some(2)
+        ));
+        assert_eq!(LiquidStaking::slashing_spans(derivative_index), Some(2));
+
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                assert_ok!(LiquidStaking::do_matching());
+                TransactionOutcome::Commit(Ok(()))
+            }
+        ));
+        assert!(XcmRequests::<Test>::iter_values().any(|request| request
+            == XcmRequest::WithdrawUnbonded {
+                index: derivative_index,
+                num_slashing_spans: 2
+            }));
+    })
+}
+
+#[test]
+fn test_transact_unbond_work() {
+    TestNet::reset();
+    let derivative_index = 0u16;
+    ParaA::execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(6000f64),
+        ));
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(1000f64),
+            Default::default(),
+            None,
+            None,
+            None
+        ));
+        let bond_amount = ksm(5f64);
+
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            bond_amount,
+            RewardDestination::Staked
+        ));
+
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        ));
+        assert_ok!(LiquidStaking::unbond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            ksm(2f64)
+        ));
+    });
+
+    Relay::execute_with(|| {
+        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Bonded {
+            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            amount: ksm(5f64),
+        }));
+        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Unbonded {
+            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            amount: ksm(2f64),
+        }));
+        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
+            derivative_index,
+        ))
+        .unwrap();
+        assert_eq!(ledger.total, ksm(5f64));
+        assert_eq!(ledger.active, ksm(3f64));
+    });
+}
+
+#[test]
+fn test_transact_withdraw_unbonded_work() {
+    TestNet::reset();
+    let derivative_index = 0u16;
+    ParaA::execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(6000f64),
+        ));
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(2000f64),
+            Default::default(),
+            None,
+            None,
+            None
+        ));
+        let bond_amount = ksm(5f64);
+        let unbond_amount = ksm(2f64);
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            bond_amount,
+            RewardDestination::Staked
+        ));
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        ));
+        assert_ok!(LiquidStaking::unbond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            unbond_amount
+        ));
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            1,
+            Response::ExecutionResult(None),
+        ));
+    });
+
+    Relay::execute_with(|| {
+        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
+            derivative_index,
+        ))
+        .unwrap();
+        assert_eq!(ledger.total, ksm(5f64));
+        assert_eq!(ledger.active, ksm(3f64));
+        assert_eq!(ledger.unlocking.len(), 1);
+
+        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Bonded {
+            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            amount: ksm(5f64),
+        }));
+        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Unbonded {
+            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            amount: ksm(2f64),
+        }));
+
+        pallet_staking::CurrentEra::<KusamaRuntime>::put(
+            <KusamaRuntime as pallet_staking::Config>::BondingDuration::get(),
+        );
+    });
+
+    ParaA::execute_with(|| {
+        assert_ok!(LiquidStaking::force_set_current_era(
+            RuntimeOrigin::root(),
+            <KusamaRuntime as pallet_staking::Config>::BondingDuration::get(),
+        ));
+
+        assert_ok!(LiquidStaking::withdraw_unbonded(
+            RuntimeOrigin::root(),
+            derivative_index,
+            0
+        ));
+    });
+
+    Relay::execute_with(|| {
+        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
+            derivative_index,
+        ))
+        .unwrap();
+        assert_eq!(ledger.total, ksm(3f64));
+        assert_eq!(ledger.active, ksm(3f64));
+        assert_eq!(ledger.unlocking.len(), 0);
+    });
+}
+
+#[test]
+fn test_transact_rebond_work() {
+    TestNet::reset();
+    let derivative_index = 0u16;
+    ParaA::execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(6000f64),
+        ));
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(1000f64),
+            Default::default(),
+            None,
+            None,
+            None
+        ));
+        let bond_amount = ksm(10f64);
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            bond_amount,
+            RewardDestination::Staked
+        ));
+
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        ));
+        assert_ok!(LiquidStaking::unbond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            ksm(5f64)
+        ));
+        assert_ok!(LiquidStaking::rebond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            ksm(3f64)
+        ));
+    });
+
+    Relay::execute_with(|| {
+        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Bonded {
+            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            amount: ksm(10f64),
+        }));
+        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Unbonded {
+            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            amount: ksm(5f64),
+        }));
+        RelaySystem::assert_has_event(RelayEvent::Staking(RelayStakingEvent::Bonded {
+            stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            amount: ksm(3f64),
+        }));
+        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
+            derivative_index,
+        ))
+        .unwrap();
+        assert_eq!(ledger.total, ksm(10f64));
+        assert_eq!(ledger.active, ksm(8f64));
+    });
+}
+
+#[test]
+fn test_transact_nominate_work() {
+    TestNet::reset();
+    let derivative_index = 0u16;
+    ParaA::execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(4000f64),
+        ));
+        let bond_amount = ksm(10f64);
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            bond_amount,
+            RewardDestination::Staked
+        ));
+
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        ));
+
+        assert_ok!(LiquidStaking::nominate(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            vec![ALICE, BOB],
+        ));
+    });
+
+    Relay::execute_with(|| {
+        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
+            derivative_index,
+        ))
+        .unwrap();
+        assert_eq!(ledger.total, ksm(10f64));
+        let nominators = RelayStaking::nominators(LiquidStaking::derivative_sovereign_account_id(
+            derivative_index,
+        ))
+        .unwrap();
+        assert_eq!(nominators.targets, vec![ALICE, BOB]);
+    });
+}
+
+#[test]
+fn test_transfer_bond() {
+    TestNet::reset();
+    let xcm_transfer_amount = ksm(10f64);
+    let derivative_index = 0u16;
+    ParaA::execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(2000f64),
+        ));
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            xcm_transfer_amount,
+            RewardDestination::Staked
+        ));
+        // print_events::<Test>("ParaA");
+    });
+    Relay::execute_with(|| {
+        // print_events::<kusama_runtime::Runtime>("Relay");
+        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
+            derivative_index,
+        ))
+        .unwrap();
+        assert_eq!(ledger.total, xcm_transfer_amount);
+        assert_eq!(
+            RelayBalances::free_balance(LiquidStaking::derivative_sovereign_account_id(
+                derivative_index
+            )),
+            xcm_transfer_amount
+        );
+        assert_eq!(
+            RelayBalances::usable_balance(LiquidStaking::derivative_sovereign_account_id(
+                derivative_index
+            )),
+            0
+        );
+    });
+}
+
+#[test]
+fn update_staking_ledger_cap_should_not_work_if_with_invalid_param() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            LiquidStaking::update_staking_ledger_cap(RuntimeOrigin::root(), Zero::zero()),
+            Error::<Test>::InvalidCap
+        );
+    })
+}
+
+#[test]
+fn update_reserve_factor_should_not_work_if_with_invalid_param() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            LiquidStaking::update_reserve_factor(RuntimeOrigin::root(), Ratio::zero()),
+            Error::<Test>::InvalidFactor
+        );
+        assert_noop!(
+            LiquidStaking::update_reserve_factor(RuntimeOrigin::root(), Ratio::one()),
+            Error::<Test>::InvalidFactor
+        );
+    })
+}
+
+#[test]
+fn compute_exchange_rate_works() {
+    // (1000 + 200 - 100) / 1000
+    assert_eq!(
+        LiquidStaking::compute_exchange_rate(ksm(1000f64), ksm(200f64), ksm(100f64), ksm(1000f64)),
+        Some(Rate::saturating_from_rational(11, 10))
+    );
+    assert_eq!(
+        LiquidStaking::compute_exchange_rate(ksm(1000f64), 0, 0, 0),
+        None
+    );
+    assert_eq!(
+        LiquidStaking::compute_exchange_rate(ksm(100f64), 0, ksm(200f64), ksm(1000f64)),
+        None
+    );
+    assert_eq!(
+        LiquidStaking::compute_exchange_rate(Balance::MAX, 1, 0, ksm(1000f64)),
+        None
+    );
+}
+
+#[test]
+fn force_set_exchange_rate_should_respect_deviation() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(LiquidStaking::exchange_rate(), Rate::one());
+
+        assert_noop!(
+            LiquidStaking::force_set_exchange_rate(
+                RuntimeOrigin::signed(ALICE),
+                Rate::saturating_from_rational(105, 100)
+            ),
+            BadOrigin
+        );
+        assert_noop!(
+            LiquidStaking::force_set_exchange_rate(
+                RuntimeOrigin::root(),
+                Rate::saturating_from_rational(111, 100)
+            ),
+            Error::<Test>::InvalidExchangeRate
+        );
+        assert_noop!(
+            LiquidStaking::force_set_exchange_rate(
+                RuntimeOrigin::root(),
+                Rate::saturating_from_rational(89, 100)
+            ),
+            Error::<Test>::InvalidExchangeRate
+        );
+
+        assert_ok!(LiquidStaking::force_set_exchange_rate(
+            RuntimeOrigin::root(),
+            Rate::saturating_from_rational(90, 100)
+        ));
+        assert_eq!(
+            LiquidStaking::exchange_rate(),
+            Rate::saturating_from_rational(90, 100)
+        );
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::ExchangeRateUpdated(Rate::saturating_from_rational(90, 100), 0),
+        ));
+    })
+}
+
+#[test]
+fn exchange_rate_change_should_notify_handler() {
+    new_test_ext().execute_with(|| {
+        StakingLedgers::<Test>::insert(
+            0,
+            <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+                LiquidStaking::derivative_sovereign_account_id(0),
+                ksm(200f64),
+            ),
+        );
+
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                LiquidStaking::do_advance_era(1).unwrap();
+                TransactionOutcome::Commit(Ok(()))
+            }
+        ));
+        assert_eq!(
+            LiquidStaking::exchange_rate(),
+            Rate::saturating_from_rational(2, 1)
+        );
+        assert_eq!(
+            NotifiedExchangeRate::get(),
+            Some((SKSM, LiquidStaking::exchange_rate()))
+        );
+
+        assert_ok!(LiquidStaking::force_set_exchange_rate(
+            RuntimeOrigin::root(),
+            Rate::saturating_from_rational(19, 10)
+        ));
+        assert_eq!(
+            NotifiedExchangeRate::get(),
+            Some((SKSM, Rate::saturating_from_rational(19, 10)))
+        );
+    })
+}
+
+#[test]
+fn exchange_rate_should_apply_slashing_buffer() {
+    new_test_ext().execute_with(|| {
+        SlashingSafetyBuffer::set(Ratio::from_percent(10));
+        StakingLedgers::<Test>::insert(
+            0,
+            <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+                LiquidStaking::derivative_sovereign_account_id(0),
+                ksm(200f64),
+            ),
+        );
+
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                LiquidStaking::do_advance_era(1).unwrap();
+                TransactionOutcome::Commit(Ok(()))
+            }
+        ));
+        // 10% of active bonded is held back against unreported slashes
+        assert_eq!(
+            LiquidStaking::exchange_rate(),
+            Rate::saturating_from_rational(18, 10)
+        );
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::ExchangeRateUpdated(Rate::saturating_from_rational(18, 10), ksm(20f64)),
+        ));
+        // backing is discounted the same way, so it doesn't deviate from the exchange rate
+        let liquid_backing = LiquidStaking::liquid_backing().unwrap();
+        assert_eq!(liquid_backing.slashing_discount, ksm(20f64));
+        assert_eq!(
+            liquid_backing.backing_ratio,
+            Some(Rate::saturating_from_rational(18, 10))
+        );
+        assert_eq!(LiquidStaking::check_invariants(), Ok(()));
+    })
+}
+
+#[test]
+fn exchange_rate_should_reset_when_issuance_is_zero() {
+    new_test_ext().execute_with(|| {
+        let advance_era = || {
+            assert_ok!(with_transaction(
+                || -> TransactionOutcome<DispatchResult> {
+                    LiquidStaking::do_advance_era(1).unwrap();
+                    TransactionOutcome::Commit(Ok(()))
+                }
+            ));
+        };
+        StakingLedgers::<Test>::insert(
+            0,
+            <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+                LiquidStaking::derivative_sovereign_account_id(0),
+                ksm(200f64),
+            ),
+        );
+        advance_era();
+        assert_eq!(
+            LiquidStaking::exchange_rate(),
+            Rate::saturating_from_rational(2, 1)
+        );
+
+        // every holder exits
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(100f64),
+            UnstakeProvider::RelayChain,
+            None,
+            None,
+            None
+        ));
+        assert_eq!(<Test as Config>::Assets::total_issuance(SKSM), 0);
+
+        // rewards arrived after the last exchange rate update
+        StakingLedgers::<Test>::mutate(0, |ledger| {
+            let ledger = ledger.as_mut().unwrap();
+            ledger.active += ksm(10f64);
+            ledger.total += ksm(10f64);
+        });
+        advance_era();
+        assert_eq!(LiquidStaking::exchange_rate(), Rate::one());
+        assert_eq!(LiquidStaking::protocol_owned_stake(), ksm(10f64));
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::ExchangeRateReset(Rate::one(), ksm(10f64)),
+        ));
+
+        // the next staker doesn't capture the residual
+        assert_ok!(LiquidStaking::stake(RuntimeOrigin::signed(BOB), ksm(10f64)));
+        advance_era();
+        assert_eq!(LiquidStaking::exchange_rate(), Rate::one());
+    })
+}
+
+#[test]
+fn nominate_should_respect_churn_limit() {
+    new_test_ext().execute_with(|| {
+        let derivative_index = 0u16;
+        let charlie = AccountId::new([3u8; 32]);
+        let dave = AccountId::new([4u8; 32]);
+        StakingLedgers::<Test>::insert(
+            derivative_index,
+            <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+                LiquidStaking::derivative_sovereign_account_id(derivative_index),
+                ksm(10f64),
+            ),
+        );
+        Nominations::<Test>::insert(derivative_index, vec![ALICE, BOB]);
+
+        // both targets changed, more than 50%
+        assert_ok!(LiquidStaking::nominate(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            vec![charlie.clone(), dave],
+        ));
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::NominationChurnRejected(derivative_index, 2),
+        ));
+        assert!(XcmRequests::<Test>::get(0).is_none());
+
+        assert_ok!(LiquidStaking::nominate(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            vec![ALICE, charlie.clone()],
+        ));
+        assert_eq!(
+            XcmRequests::<Test>::get(0),
+            Some(XcmRequest::Nominate {
+                index: derivative_index,
+                targets: vec![ALICE, charlie.clone()],
+            })
+        );
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        ));
+        assert_eq!(
+            LiquidStaking::nominations(derivative_index),
+            Some(vec![ALICE, charlie])
+        );
+    })
+}
+
+#[test]
+fn nominate_default_should_work() {
+    new_test_ext().execute_with(|| {
+        let derivative_index = 0u16;
+        StakingLedgers::<Test>::insert(
+            derivative_index,
+            <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+                LiquidStaking::derivative_sovereign_account_id(derivative_index),
+                ksm(10f64),
+            ),
+        );
+
+        assert_noop!(
+            LiquidStaking::nominate_default(RuntimeOrigin::signed(ALICE), derivative_index),
+            Error::<Test>::NoDefaultNominationTargets
+        );
+
+        assert_noop!(
+            LiquidStaking::set_default_targets(
+                RuntimeOrigin::signed(AccountId::new([3u8; 32])),
+                vec![ALICE, BOB].try_into().unwrap()
+            ),
+            BadOrigin
+        );
+        assert_noop!(
+            LiquidStaking::set_default_targets(RuntimeOrigin::root(), Default::default()),
+            Error::<Test>::InvalidNominationTargets
+        );
+        assert_noop!(
+            LiquidStaking::set_default_targets(
+                RuntimeOrigin::root(),
+                vec![ALICE, BOB, ALICE].try_into().unwrap()
+            ),
+            Error::<Test>::InvalidNominationTargets
+        );
+        assert_ok!(LiquidStaking::set_default_targets(
+            RuntimeOrigin::root(),
+            vec![ALICE, BOB].try_into().unwrap()
+        ));
+        assert_eq!(
+            LiquidStaking::default_nomination_targets().into_inner(),
+            vec![ALICE, BOB]
+        );
+
+        assert_ok!(LiquidStaking::nominate_default(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index
+        ));
+        assert_eq!(
+            XcmRequests::<Test>::get(0),
+            Some(XcmRequest::Nominate {
+                index: derivative_index,
+                targets: vec![ALICE, BOB],
+            })
+        );
+    })
+}
+
+#[test]
+fn nominate_all_should_work() {
+    new_test_ext().execute_with(|| {
+        DerivativeIndexList::set(vec![0, 1, 2]);
+        assert_noop!(
+            LiquidStaking::nominate_all(RuntimeOrigin::signed(ALICE), vec![ALICE, BOB]),
+            Error::<Test>::NotBonded
+        );
+
+        for derivative_index in [0u16, 1u16] {
+            StakingLedgers::<Test>::insert(
+                derivative_index,
+                <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+                    LiquidStaking::derivative_sovereign_account_id(derivative_index),
+                    ksm(10f64),
+                ),
+            );
+        }
+
+        assert_noop!(
+            LiquidStaking::nominate_all(
+                RuntimeOrigin::signed(AccountId::new([3u8; 32])),
+                vec![ALICE, BOB]
+            ),
+            BadOrigin
+        );
+        assert_ok!(LiquidStaking::nominate_all(
+            RuntimeOrigin::signed(ALICE),
+            vec![ALICE, BOB]
+        ));
+
+        let mut nominated: Vec<u16> = XcmRequests::<Test>::iter_values()
+            .map(|request| match request {
+                XcmRequest::Nominate { index, targets } => {
+                    assert_eq!(targets, vec![ALICE, BOB]);
+                    index
+                }
+                _ => unreachable!(),
+            })
+            .collect();
+        nominated.sort();
+        assert_eq!(nominated, vec![0, 1]);
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::NominatingAll(vec![ALICE, BOB], 2),
+        ));
+    })
+}
+
+#[test]
+fn liquid_backing_should_report_each_component() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        // genesis liquid currency backed by a bonded ledger
+        StakingLedgers::<Test>::insert(
+            0,
+            <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+                LiquidStaking::derivative_sovereign_account_id(0),
+                ksm(100f64),
+            ),
+        );
+
+        assert_eq!(
+            LiquidStaking::liquid_backing(),
+            Ok(LiquidBackingInfo {
+                liquid_issuance: ksm(100f64) + ksm(9.95f64),
+                total_active_bonded: ksm(100f64),
+                slashing_discount: 0,
+                matching_stake: ksm(9.95f64),
+                matching_unstake: 0,
+                protocol_owned_stake: 0,
+                backing_ratio: Some(Rate::one()),
+            })
+        );
+    })
+}
+
+#[test]
+fn matching_pool_state_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(1f64),
+            Default::default(),
+            None,
+            None,
+            None
+        ));
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            0,
+            ksm(3f64),
+            RewardDestination::Staked
+        ));
+
+        assert_eq!(
+            LiquidStaking::matching_pool_state(),
+            MatchingPoolState {
+                stake_free: ksm(6.95f64),
+                stake_locked: ksm(3f64),
+                unstake_free: ksm(1f64),
+                unstake_locked: 0,
+            }
+        );
+        assert_eq!(
+            LiquidStaking::fast_unstake_capacity(),
+            LiquidStaking::matching_pool_state().stake_free
+        );
+    })
+}
+
+#[test]
+fn stake_reserves_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        assert_eq!(LiquidStaking::total_reserves(), ksm(0.05f64));
+        // genesis liquid currency backed by a bonded ledger
+        StakingLedgers::<Test>::insert(
+            0,
+            <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+                LiquidStaking::derivative_sovereign_account_id(0),
+                ksm(100f64),
+            ),
+        );
+
+        assert_noop!(
+            LiquidStaking::stake_reserves(RuntimeOrigin::signed(ALICE), ksm(1f64)),
+            BadOrigin
+        );
+        assert_ok!(LiquidStaking::stake_reserves(
+            RuntimeOrigin::signed(BOB),
+            ksm(1f64)
+        ));
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::ReservesStaked(ksm(0.05f64)),
+        ));
+        assert_eq!(LiquidStaking::total_reserves(), 0);
+        assert_eq!(LiquidStaking::protocol_owned_stake(), ksm(0.05f64));
+        assert_eq!(
+            LiquidStaking::matching_pool().total_stake_amount.total,
+            ksm(10f64)
+        );
+
+        // Protocol owned stake doesn't raise the exchange rate
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                assert_ok!(LiquidStaking::do_advance_era(1));
+                TransactionOutcome::Commit(Ok(()))
+            }
+        ));
+        assert_eq!(LiquidStaking::exchange_rate(), Rate::one());
+        assert_eq!(
+            LiquidStaking::liquid_backing().unwrap().backing_ratio,
+            Some(Rate::one())
+        );
+    })
+}
+
+#[test]
+fn relay_storage_keys_should_work() {
+    new_test_ext().execute_with(|| {
+        let (ledger_key, current_era_key) = LiquidStaking::relay_storage_keys(0);
+        // twox128("Staking") ++ twox128("CurrentEra")
+        assert_eq!(
+            current_era_key,
+            hex::decode("5f3e4907f716ac89b6347d15ececedca0b6a45321efae92aea15e0740ec7afe7")
+                .unwrap()
+        );
+        // twox128("Staking") ++ twox128("Ledger") ++ blake2_128_concat(account)
+        assert_eq!(ledger_key, hex::decode(MOCK_KEY).unwrap());
+    })
+}
+
+#[test]
+fn reward_snapshots_should_be_opt_in() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        assert_eq!(LiquidStaking::reward_snapshots(ALICE), None);
+
+        assert_ok!(LiquidStaking::set_reward_snapshots(
+            RuntimeOrigin::signed(ALICE),
+            true
+        ));
+        assert_eq!(
+            LiquidStaking::account_reward_snapshots(&ALICE),
+            vec![(0, Rate::one())]
+        );
+
+        // one snapshot per era, the latest rate wins
+        ExchangeRate::<Test>::put(Rate::saturating_from_rational(11, 10));
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(1f64)
+        ));
+        assert_eq!(
+            LiquidStaking::account_reward_snapshots(&ALICE),
+            vec![(0, Rate::saturating_from_rational(11, 10))]
+        );
+
+        CurrentEra::<Test>::put(1);
+        ExchangeRate::<Test>::put(Rate::saturating_from_rational(12, 10));
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(1f64),
+            Default::default(),
+            None,
+            None,
+            None
+        ));
+        assert_eq!(
+            LiquidStaking::account_reward_snapshots(&ALICE),
+            vec![
+                (0, Rate::saturating_from_rational(11, 10)),
+                (1, Rate::saturating_from_rational(12, 10))
+            ]
+        );
+
+        assert_ok!(LiquidStaking::set_reward_snapshots(
+            RuntimeOrigin::signed(ALICE),
+            false
+        ));
+        assert!(LiquidStaking::account_reward_snapshots(&ALICE).is_empty());
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::RewardSnapshotsToggled(ALICE, false),
+        ));
+    })
+}
+
+#[test]
+fn unstake_more_than_bonded_should_fail() {
+    new_test_ext().execute_with(|| {
+        // liquid currency minted at genesis isn't backed by any stake
+        assert_noop!(
+            LiquidStaking::unstake(
+                RuntimeOrigin::signed(ALICE),
+                ksm(1f64),
+                Default::default(),
+                None,
+                None,
+                None
+            ),
+            Error::<Test>::InsufficientBonded
+        );
+
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(9.95f64),
+            Default::default(),
+            None,
+            None,
+            None
+        ));
+        assert_noop!(
+            LiquidStaking::unstake(
+                RuntimeOrigin::signed(ALICE),
+                ksm(1f64),
+                Default::default(),
+                None,
+                None,
+                None
+            ),
+            Error::<Test>::InsufficientBonded
+        );
+    })
+}
+
+#[test]
+fn claim_for_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        assert_eq!(<Test as Config>::Assets::balance(KSM, &ALICE), ksm(90f64));
+
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(1f64),
+            Default::default(),
+            None,
+            None,
+            None
+        ));
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(3.95f64),
+            Default::default(),
+            None,
+            None,
+            None
+        ));
+        assert_eq!(
+            Unlockings::<Test>::get(ALICE).unwrap(),
+            vec![UnlockChunk {
+                value: ksm(4.95f64),
+                era: 4
+            },]
+        );
+
+        assert_noop!(
+            LiquidStaking::claim_for(RuntimeOrigin::signed(BOB), Id(ALICE)),
+            Error::<Test>::NothingToClaim
+        );
+        assert_eq!(LiquidStaking::claimable_now(&ALICE), 0);
+
+        let derivative_index = 0u16;
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                assert_ok!(LiquidStaking::do_advance_era(4));
+                assert_ok!(LiquidStaking::do_matching());
+                TransactionOutcome::Commit(Ok(()))
+            }
+        ));
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        ));
+        assert_ok!(LiquidStaking::withdraw_unbonded(
+            RuntimeOrigin::root(),
+            derivative_index,
+            0
+        ));
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            1,
+            Response::ExecutionResult(None),
+        ));
+
+        assert_eq!(LiquidStaking::claimable_now(&ALICE), ksm(4.95f64));
+        assert_ok!(LiquidStaking::claim_for(
+            RuntimeOrigin::signed(BOB),
+            Id(ALICE)
+        ));
+        assert_eq!(
+            <Test as Config>::Assets::balance(KSM, &ALICE),
+            ksm(90f64) + ksm(4.95f64)
+        );
+        assert_eq!(LiquidStaking::claimable_now(&ALICE), 0);
+
+        assert!(Unlockings::<Test>::get(ALICE).is_none());
+    })
+}
+
+#[test]
+fn claim_should_work() {
+    new_test_ext().execute_with(|| {
+        let charlie = AccountId::new([3u8; 32]);
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        assert_ok!(Assets::transfer(
+            RuntimeOrigin::signed(ALICE),
+            SKSM.into(),
+            Id(charlie.clone()),
+            ksm(4.95f64)
+        ));
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(charlie.clone()),
+            ksm(4.95f64),
+            Default::default(),
+            None,
+            None,
+            None
+        ));
+
+        assert_noop!(
+            LiquidStaking::claim(RuntimeOrigin::signed(ALICE)),
+            Error::<Test>::NoUnlockings
+        );
+        assert_noop!(
+            LiquidStaking::claim(RuntimeOrigin::signed(charlie.clone())),
+            Error::<Test>::NothingToClaim
+        );
+
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                assert_ok!(LiquidStaking::do_advance_era(4));
+                assert_ok!(LiquidStaking::do_matching());
+                TransactionOutcome::Commit(Ok(()))
+            }
+        ));
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        ));
+        assert_ok!(LiquidStaking::withdraw_unbonded(
+            RuntimeOrigin::root(),
+            0,
+            0
+        ));
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            1,
+            Response::ExecutionResult(None),
+        ));
+
+        // no relayer is needed to claim matured funds
+        assert_noop!(
+            LiquidStaking::claim_for(RuntimeOrigin::signed(charlie.clone()), Id(charlie.clone())),
+            BadOrigin
+        );
+        assert_ok!(LiquidStaking::claim(RuntimeOrigin::signed(charlie.clone())));
+        assert_eq!(
+            <Test as Config>::Assets::balance(KSM, &charlie),
+            ksm(4.95f64)
+        );
+        assert!(Unlockings::<Test>::get(&charlie).is_none());
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(crate::Event::ClaimedFor(
+            charlie,
+            ksm(4.95f64),
+        )));
+    })
+}
+
+#[test]
+fn unstake_to_beneficiary_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        assert_noop!(
+            LiquidStaking::unstake(
+                RuntimeOrigin::signed(ALICE),
+                ksm(1f64),
+                UnstakeProvider::MatchingPool,
+                None,
+                None,
+                Some(Id(BOB))
+            ),
+            Error::<Test>::BeneficiaryNotSupported
+        );
+
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(1f64),
+            Default::default(),
+            None,
+            None,
+            Some(Id(BOB))
+        ));
+        assert!(Unlockings::<Test>::get(ALICE).is_none());
+        assert_eq!(
+            Unlockings::<Test>::get(BOB).unwrap(),
+            vec![UnlockChunk {
+                value: ksm(1f64),
+                era: LiquidStaking::target_era()
+            }]
+        );
+    })
+}
+
+#[test]
+fn on_initialize_failure_should_be_reported() {
+    new_test_ext().execute_with(|| {
+        // matching fails as more is reserved than staked
+        MatchingPool::<Test>::put(MatchingLedger {
+            total_stake_amount: ReservableAmount {
+                total: 0,
+                reserved: ksm(1f64),
+            },
+            total_unstake_amount: Default::default(),
+        });
+        RelayChainValidationDataProvider::set(10);
+        LiquidStaking::on_initialize(1);
+
+        // era isn't advanced either
+        assert!(!LiquidStaking::is_matched());
+        assert_eq!(LiquidStaking::current_era(), 0);
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::OnInitializeFailed(OnInitializeStep::Matching, Underflow.into()),
+        ));
+    })
+}
+
+#[test]
+fn test_on_initialize_work() {
+    new_test_ext().execute_with(|| {
+        let derivative_index = 0u16;
+        let xcm_fees = XcmFees::get();
+        let reserve_factor = LiquidStaking::reserve_factor();
+
+        // 1.1 stake
+        let bond_amount = ksm(10f64);
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            bond_amount
+        ));
+        let total_stake_amount = bond_amount - xcm_fees - reserve_factor.mul_floor(bond_amount);
+
+        // 1.2 on_initialize_bond
+        let total_era_blocknumbers = <Test as Config>::EraLength::get();
+        assert_eq!(total_era_blocknumbers, 10);
+        RelayChainValidationDataProvider::set(total_era_blocknumbers);
+        LiquidStaking::on_initialize(System::block_number());
+        assert_eq!(EraStartBlock::<Test>::get(), total_era_blocknumbers);
+        assert_eq!(CurrentEra::<Test>::get(), 1);
+        assert_eq!(LiquidStaking::staking_ledger(derivative_index), None);
+        assert_eq!(
+            LiquidStaking::matching_pool(),
+            MatchingLedger {
+                total_stake_amount: ReservableAmount {
+                    total: total_stake_amount,
+                    reserved: total_stake_amount
+                },
+                total_unstake_amount: Default::default(),
+            }
+        );
+
+        // 1.3 notification_received bond
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        ));
+
+        let staking_ledger = <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+            LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            total_stake_amount,
+        );
+        assert_eq!(
+            LiquidStaking::staking_ledger(derivative_index).unwrap(),
+            staking_ledger
+        );
+
+        assert_eq!(LiquidStaking::matching_pool(), MatchingLedger::default());
+    })
+}
+
+#[test]
+fn test_set_staking_ledger_work() {
+    new_test_ext().execute_with(|| {
+        let derivative_index = 0u16;
+        let bond_amount = 100;
+        let bond_extra_amount = 50;
+        let mut staking_ledger = <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+            LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            bond_amount,
+        );
+        assert_noop!(
+            LiquidStaking::set_staking_ledger(
+                RuntimeOrigin::signed(ALICE),
+                derivative_index,
+                staking_ledger.clone(),
+                get_mock_proof_bytes()
+            ),
+            Error::<Test>::NotBonded
+        );
+        StakingLedgers::<Test>::insert(derivative_index, staking_ledger.clone());
+        assert_eq!(
+            LiquidStaking::staking_ledger(derivative_index).unwrap(),
+            staking_ledger.clone()
+        );
+        staking_ledger.bond_extra(bond_extra_amount);
+        assert_noop!(
+            LiquidStaking::set_staking_ledger(
+                RuntimeOrigin::signed(ALICE),
+                derivative_index,
+                staking_ledger.clone(),
+                get_mock_proof_bytes()
+            ),
+            Error::<Test>::NoValidationData
+        );
+        LiquidStaking::on_finalize(1);
+        assert_noop!(
+            LiquidStaking::set_staking_ledger(
+                RuntimeOrigin::signed(ALICE),
+                derivative_index,
+                staking_ledger.clone(),
+                get_mock_proof_bytes()
+            ),
+            Error::<Test>::ProofValueMismatch
+        );
+        assert_ok!(LiquidStaking::set_staking_ledger(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            get_mock_staking_ledger(derivative_index),
+            get_mock_proof_bytes()
+        ));
+
+        assert_noop!(
+            LiquidStaking::set_staking_ledger(
+                RuntimeOrigin::signed(ALICE),
+                derivative_index,
+                staking_ledger.clone(),
+                get_mock_proof_bytes()
+            ),
+            Error::<Test>::StakingLedgerLocked
+        );
+
+        LiquidStaking::on_finalize(1);
+        assert_eq!(
+            LiquidStaking::staking_ledger(derivative_index)
+                .unwrap()
+                .total,
+            MOCK_LEDGER_AMOUNT
+        );
+    })
+}
+
+#[test]
+fn report_slash_should_work() {
+    new_test_ext().execute_with(|| {
+        let derivative_index = 0u16;
+        let unlocking_amount = ksm(0.5f64);
+        let slashed_amount = ksm(1.5f64);
+        let mut staking_ledger = get_mock_staking_ledger(derivative_index);
+        staking_ledger.active += slashed_amount - unlocking_amount;
+        staking_ledger.total += slashed_amount;
+        staking_ledger.unlocking = vec![UnlockChunk {
+            value: unlocking_amount,
+            era: 4,
+        }];
+        StakingLedgers::<Test>::insert(derivative_index, staking_ledger.clone());
+        // enough issuance for the slashed ledger to back less than current rate
+        assert_ok!(<Test as Config>::Assets::mint_into(
+            SKSM,
+            &BOB,
+            ksm(1000f64)
+        ));
+        LiquidStaking::on_finalize(1);
+
+        assert_noop!(
+            LiquidStaking::report_slash(
+                RuntimeOrigin::signed(ALICE),
+                derivative_index,
+                staking_ledger.clone(),
+                get_mock_proof_bytes()
+            ),
+            Error::<Test>::InvalidSlashAmount
+        );
+        let mut wrong_ledger = get_mock_staking_ledger(derivative_index);
+        wrong_ledger.active -= ksm(1f64);
+        wrong_ledger.total -= ksm(1f64);
+        assert_noop!(
+            LiquidStaking::report_slash(
+                RuntimeOrigin::signed(ALICE),
+                derivative_index,
+                wrong_ledger,
+                get_mock_proof_bytes()
+            ),
+            Error::<Test>::ProofValueMismatch
+        );
+
+        assert_ok!(LiquidStaking::report_slash(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            get_mock_staking_ledger(derivative_index),
+            get_mock_proof_bytes()
+        ));
+        assert_eq!(
+            LiquidStaking::staking_ledger(derivative_index),
+            Some(get_mock_staking_ledger(derivative_index))
+        );
+        let exchange_rate = Rate::checked_from_rational(
+            MOCK_LEDGER_AMOUNT,
+            <Test as Config>::Assets::total_issuance(SKSM),
+        )
+        .unwrap();
+        assert!(exchange_rate < Rate::one());
+        assert_eq!(LiquidStaking::exchange_rate(), exchange_rate);
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::SlashReported(derivative_index, slashed_amount, exchange_rate),
+        ));
+    })
+}
+
+#[test]
+fn report_slash_should_ignore_withdrawn_chunks() {
+    new_test_ext().execute_with(|| {
+        let derivative_index = 0u16;
+        let withdrawn_amount = ksm(2f64);
+        let mut staking_ledger = get_mock_staking_ledger(derivative_index);
+        staking_ledger.total += withdrawn_amount;
+        staking_ledger.unlocking = vec![UnlockChunk {
+            value: withdrawn_amount,
+            era: 0,
+        }];
+        StakingLedgers::<Test>::insert(derivative_index, staking_ledger.clone());
+        LiquidStaking::on_finalize(1);
+
+        // the matured chunk was withdrawn, nothing was slashed
+        assert_noop!(
+            LiquidStaking::report_slash(
+                RuntimeOrigin::signed(ALICE),
+                derivative_index,
+                get_mock_staking_ledger(derivative_index),
+                get_mock_proof_bytes()
+            ),
+            Error::<Test>::InvalidSlashAmount
+        );
+
+        let slashed_amount = ksm(1f64);
+        staking_ledger.active += slashed_amount;
+        staking_ledger.total += slashed_amount;
+        StakingLedgers::<Test>::insert(derivative_index, staking_ledger);
+        assert_ok!(LiquidStaking::report_slash(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            get_mock_staking_ledger(derivative_index),
+            get_mock_proof_bytes()
+        ));
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::SlashReported(
+                derivative_index,
+                slashed_amount,
+                LiquidStaking::exchange_rate(),
+            ),
+        ));
+    })
+}
+
+#[test]
+fn restrict_proof_submitters_should_work() {
+    new_test_ext().execute_with(|| {
+        let derivative_index = 0u16;
+        let staking_ledger = <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+            LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            100,
+        );
+        RestrictProofSubmitters::set(true);
+        assert_noop!(
+            LiquidStaking::set_staking_ledger(
+                RuntimeOrigin::signed(ALICE),
+                derivative_index,
+                staking_ledger.clone(),
+                get_mock_proof_bytes()
+            ),
+            BadOrigin
+        );
+        assert_noop!(
+            LiquidStaking::set_current_era(RuntimeOrigin::signed(ALICE), 1, get_mock_proof_bytes()),
+            BadOrigin
+        );
+        assert_noop!(
+            LiquidStaking::set_staking_ledger(
+                RuntimeOrigin::signed(BOB),
+                derivative_index,
+                staking_ledger.clone(),
+                get_mock_proof_bytes()
+            ),
+            Error::<Test>::NotBonded
+        );
+
+        RestrictProofSubmitters::set(false);
+        assert_noop!(
+            LiquidStaking::set_staking_ledger(
+                RuntimeOrigin::signed(ALICE),
+                derivative_index,
+                staking_ledger,
+                get_mock_proof_bytes()
+            ),
+            Error::<Test>::NotBonded
+        );
+    })
+}
+
+#[test]
+fn incentive_should_respect_budget_per_era() {
+    new_test_ext().execute_with(|| {
+        let derivative_index = 0u16;
+        let incentive = 600_000_000_000u128;
+        StakingLedgers::<Test>::insert(
+            derivative_index,
+            <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+                LiquidStaking::derivative_sovereign_account_id(derivative_index),
+                100,
+            ),
+        );
+        assert_ok!(<Test as Config>::Assets::mint_into(
+            HKO,
+            &LiquidStaking::account_id(),
+            10 * incentive
+        ));
+        assert_ok!(LiquidStaking::update_incentive(
+            RuntimeOrigin::root(),
+            incentive
+        ));
+        let balance = <Test as Config>::Assets::balance(HKO, &ALICE);
+
+        LiquidStaking::on_finalize(1);
+        assert_ok!(LiquidStaking::set_staking_ledger(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            get_mock_staking_ledger(derivative_index),
+            get_mock_proof_bytes()
+        ));
+        assert_eq!(
+            <Test as Config>::Assets::balance(HKO, &ALICE),
+            balance + incentive
+        );
+        assert_eq!(LiquidStaking::incentive_spent(), incentive);
+
+        // Budget of 1 HKO can't cover another 0.6 HKO in this era
+        LiquidStaking::on_finalize(1);
+        assert_ok!(LiquidStaking::set_staking_ledger(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            get_mock_staking_ledger(derivative_index),
+            get_mock_proof_bytes()
+        ));
+        assert_eq!(
+            <Test as Config>::Assets::balance(HKO, &ALICE),
+            balance + incentive
+        );
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::IncentiveBudgetExhausted(0),
+        ));
+
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                assert_ok!(LiquidStaking::do_advance_era(1));
+                TransactionOutcome::Commit(Ok(()))
+            }
+        ));
+        assert_eq!(LiquidStaking::incentive_spent(), 0);
+    })
+}
+
+#[test]
+fn test_force_set_era_start_block_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(EraStartBlock::<Test>::get(), 0);
+        assert_ok!(LiquidStaking::force_set_era_start_block(
+            RuntimeOrigin::root(),
+            11
+        ));
+        assert_eq!(EraStartBlock::<Test>::get(), 11);
+    })
+}
+
+#[test]
+fn test_force_set_current_era_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(CurrentEra::<Test>::get(), 0);
+        assert_ok!(LiquidStaking::force_set_current_era(
+            RuntimeOrigin::root(),
+            12
+        ));
+        assert_eq!(CurrentEra::<Test>::get(), 12);
+    })
+}
+
+#[test]
+fn test_force_notification_received_work() {
+    new_test_ext().execute_with(|| {
+        let derivative_index = 0u16;
+        let bond_amount = ksm(10f64);
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(20f64),
+        ));
+
+        assert_ok!(LiquidStaking::bond(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            bond_amount,
+            RewardDestination::Staked
+        ));
+
+        let query_id = 0;
+        assert_eq!(
+            XcmRequests::<Test>::get(query_id),
+            Some(XcmRequest::Bond {
+                index: derivative_index,
+                amount: bond_amount,
+            })
+        );
+        assert_noop!(
+            LiquidStaking::notification_received(
+                RuntimeOrigin::signed(ALICE),
+                query_id,
+                Response::ExecutionResult(None),
+            ),
+            BadOrigin
+        );
+        assert_ok!(LiquidStaking::notification_received(
+            RuntimeOrigin::root(),
+            query_id,
+            Response::ExecutionResult(None),
+        ));
+        assert_eq!(XcmRequests::<Test>::get(query_id), None);
+    })
+}
+
+#[test]
+fn test_storage_proof_approach_should_work() {
+    let relay_root = sp_core::hash::H256::from_slice(&hex::decode(ROOT_HASH).unwrap());
+    let key = hex::decode(MOCK_KEY).unwrap();
+    let value = hex::decode(MOCK_DATA).unwrap();
+    let relay_proof = StorageProof::new(get_mock_proof_bytes());
+    let result = sp_state_machine::read_proof_check::<BlakeTwo256, _>(
+        relay_root,
+        relay_proof.clone(),
+        [key.clone()],
+    )
+    .unwrap();
+    assert_eq!(
+        result.into_iter().collect::<Vec<_>>(),
+        vec![(key, Some(value))],
+    );
+}
+
+#[test]
+fn test_verify_trie_proof_work() {
+    type LayoutV1 = sp_trie::LayoutV1<BlakeTwo256>;
+    let relay_root = sp_core::hash::H256::from_slice(&hex::decode(ROOT_HASH).unwrap());
+    let key = hex::decode(MOCK_KEY).unwrap();
+    let value = hex::decode(MOCK_DATA).unwrap();
+    let relay_proof = StorageProof::new(get_mock_proof_bytes());
+    let db = relay_proof.into_memory_db();
+    let result = sp_trie::read_trie_value::<LayoutV1, _>(&db, &relay_root, &key, None, None)
+        .unwrap()
+        .unwrap();
+    assert_eq!(result, value);
+}
+
+#[test]
+fn test_verify_merkle_proof_work() {
+    new_test_ext().execute_with(|| {
+        use codec::Encode;
+        let derivative_index = 0u16;
+        let staking_ledger = get_mock_staking_ledger(derivative_index);
+        let key = LiquidStaking::get_staking_ledger_key(derivative_index);
+        let value = staking_ledger.encode();
+        assert_eq!(hex::encode(&value), MOCK_DATA);
+        assert_eq!(
+            LiquidStaking::verify_merkle_proof(key.clone(), value.clone(), get_mock_proof_bytes()),
+            Err(ProofError::NoValidationData)
+        );
+        LiquidStaking::on_finalize(1);
+        assert_eq!(
+            LiquidStaking::verify_merkle_proof(key.clone(), value.clone(), vec![]),
+            Err(ProofError::MalformedProof)
+        );
+        assert_eq!(
+            LiquidStaking::verify_merkle_proof(key.clone(), vec![0u8], get_mock_proof_bytes()),
+            Err(ProofError::ValueMismatch)
+        );
+        assert_eq!(
+            LiquidStaking::verify_merkle_proof(key, value, get_mock_proof_bytes()),
+            Ok(())
+        );
+    })
+}
+
+#[test]
+fn reduce_reserves_works() {
+    new_test_ext().execute_with(|| {
+        // Stake 1000 KSM, 0.5% for reserves
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(100f64)
+        ));
+        assert_eq!(LiquidStaking::total_reserves(), ksm(0.5f64));
+        // Reduce 20 KSM reserves
+        assert_ok!(LiquidStaking::reduce_reserves(
+            RuntimeOrigin::root(),
+            Id(ALICE),
+            ksm(0.2f64)
+        ));
+        assert_eq!(LiquidStaking::total_reserves(), ksm(0.3f64));
+
+        // should failed if exceed the cap
+        assert_noop!(
+            LiquidStaking::reduce_reserves(RuntimeOrigin::root(), Id(ALICE), ksm(0.31f64)),
+            Underflow
+        );
+    })
+}
+
+#[test]
+fn add_reserves_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(100f64)
+        ));
+        assert_eq!(LiquidStaking::total_reserves(), ksm(0.5f64));
+        let exchange_rate = LiquidStaking::exchange_rate();
+        let pool_balance = <Test as Config>::Assets::balance(KSM, &LiquidStaking::account_id());
+
+        assert_noop!(
+            LiquidStaking::add_reserves(RuntimeOrigin::root(), ksm(1f64)),
+            BadOrigin
+        );
+        assert_ok!(LiquidStaking::add_reserves(
+            RuntimeOrigin::signed(BOB),
+            ksm(1f64)
+        ));
+        assert_eq!(LiquidStaking::total_reserves(), ksm(1.5f64));
+        assert_eq!(
+            <Test as Config>::Assets::balance(KSM, &LiquidStaking::account_id()),
+            pool_balance + ksm(1f64)
+        );
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::ReservesAdded(BOB, ksm(1f64)),
+        ));
+
+        // reserves aren't backing
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                assert_ok!(LiquidStaking::do_advance_era(1));
+                TransactionOutcome::Commit(Ok(()))
+            }
+        ));
+        assert_eq!(LiquidStaking::exchange_rate(), exchange_rate);
+    })
+}
+
+#[test]
+fn cancel_unstake_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(6f64),
+            UnstakeProvider::MatchingPool,
+            None,
+            None,
+            None
+        ));
+
+        assert_eq!(
+            LiquidStaking::fast_unstake_requests(&ALICE).amount,
+            ksm(6f64)
+        );
+
+        // Check storage is correct
+        assert_eq!(ExchangeRate::<Test>::get(), Rate::one());
+        assert_eq!(
+            MatchingPool::<Test>::get(),
+            MatchingLedger {
+                total_stake_amount: ReservableAmount {
+                    total: ksm(9.95f64),
+                    reserved: 0
+                },
+                total_unstake_amount: ReservableAmount {
+                    total: 0,
+                    reserved: 0
+                }
+            }
+        );
+
+        assert_ok!(LiquidStaking::cancel_unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(6f64)
+        ));
+        assert_eq!(
+            MatchingPool::<Test>::get(),
+            MatchingLedger {
+                total_stake_amount: ReservableAmount {
+                    total: ksm(9.95f64),
+                    reserved: 0
+                },
+                total_unstake_amount: ReservableAmount {
+                    total: 0,
+                    reserved: 0
+                }
+            }
+        );
+
+        assert_eq!(LiquidStaking::fast_unstake_requests(&ALICE).amount, 0);
+    })
+}
+
+#[test]
+fn unstake_should_fail_when_fee_too_high() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, ksm(100f64)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(BOB),
+            KSM,
+            true
+        ));
+        assert_noop!(
+            LiquidStaking::unstake(
+                RuntimeOrigin::signed(ALICE),
+                ksm(6f64),
+                UnstakeProvider::Loans,
+                None,
+                Some(Rate::saturating_from_rational(7u32, 1000u32)),
+                None
+            ),
+            Error::<Test>::FeeTooHigh
+        );
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(6f64),
+            UnstakeProvider::Loans,
+            None,
+            Some(Rate::saturating_from_rational(8u32, 1000u32)),
+            None
+        ));
+    })
+}
+
+#[test]
+fn fast_unstake_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, ksm(100f64)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(BOB),
+            KSM,
+            true
+        ));
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(6f64),
+            UnstakeProvider::Loans,
+            None,
+            None,
+            None
+        ));
+        assert_eq!(
+            Unlockings::<Test>::get(LiquidStaking::loans_account_id()).unwrap(),
+            vec![UnlockChunk {
+                value: ksm(6f64),
+                era: 4
+            },]
+        );
+        // 90 * 1e12 + (6 * (1 - 8/1000) * 1e12)
+        assert_eq!(
+            <Test as Config>::Assets::balance(KSM, &ALICE),
+            95952000000000u128
+        );
+        assert_eq!(LiquidStaking::loans_outstanding_borrow(), 5952000000000u128);
+
+        let derivative_index = 0u16;
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                assert_ok!(LiquidStaking::do_matching());
+                assert_ok!(LiquidStaking::do_advance_era(4));
+                TransactionOutcome::Commit(Ok(()))
+            }
+        ));
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            0,
+            Response::ExecutionResult(None),
+        ));
+        assert_ok!(LiquidStaking::withdraw_unbonded(
+            RuntimeOrigin::root(),
+            derivative_index,
+            0
+        ));
+        assert_ok!(LiquidStaking::notification_received(
+            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
+            1,
+            Response::ExecutionResult(None),
+        ));
+
+        assert_ok!(LiquidStaking::claim_for(
+            RuntimeOrigin::signed(BOB),
+            Id(LiquidStaking::loans_account_id())
+        ));
+        assert_eq!(
+            Unlockings::<Test>::get(LiquidStaking::loans_account_id()),
+            None
+        );
+        assert_eq!(LiquidStaking::loans_outstanding_borrow(), 0);
+    })
+}
+
+#[test]
+fn commission_rate_should_follow_reward_source() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(10f64)
+        ));
+        assert_ok!(LiquidStaking::update_commission_rate(
+            RuntimeOrigin::root(),
+            Rate::from_rational(1, 100)
+        ));
+        assert_ok!(LiquidStaking::update_source_commission_rate(
+            RuntimeOrigin::root(),
+            RewardSource::SlashRecovery,
+            Some(Rate::from_rational(10, 100))
+        ));
+        assert_noop!(
+            LiquidStaking::update_source_commission_rate(
+                RuntimeOrigin::root(),
+                RewardSource::SlashRecovery,
+                Some(Rate::one())
+            ),
+            Error::<Test>::InvalidCommissionRate
+        );
+
+        let rewards = ksm(1f64);
+        let issuance = <Test as Config>::Assets::total_issuance(SKSM);
+        let total_bonded = LiquidStaking::matching_pool().total_stake_amount.total;
+        let expected = |commission_rate: Rate| {
+            let commission_staking_amount = commission_rate.saturating_mul_int(rewards);
+            Rate::checked_from_rational(
+                commission_staking_amount,
+                total_bonded + rewards - commission_staking_amount,
+            )
+            .unwrap()
+            .saturating_mul_int(issuance)
+        };
+
+        assert_eq!(
+            LiquidStaking::get_inflate_liquid_amount(rewards, RewardSource::RelayStaking),
+            Ok(expected(Rate::from_rational(1, 100)))
+        );
+        assert_eq!(
+            LiquidStaking::get_inflate_liquid_amount(rewards, RewardSource::SlashRecovery),
+            Ok(expected(Rate::from_rational(10, 100)))
+        );
+
+        // Falls back to the single commission rate once cleared
+        assert_ok!(LiquidStaking::update_source_commission_rate(
+            RuntimeOrigin::root(),
+            RewardSource::SlashRecovery,
+            None
+        ));
+        assert_eq!(
+            LiquidStaking::get_inflate_liquid_amount(rewards, RewardSource::SlashRecovery),
+            Ok(expected(Rate::from_rational(1, 100)))
+        );
+    })
+}
+
+#[test]
+fn test_charge_commission_work() {
+    new_test_ext().execute_with(|| {
+        let derivative_index = 0u16;
+        let bond_amount = ksm(200f64);
+        let staking_ledger = <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+            LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            bond_amount,
+        );
+        StakingLedgers::<Test>::insert(derivative_index, staking_ledger.clone());
+        assert_ok!(LiquidStaking::update_commission_rate(
+            RuntimeOrigin::root(),
+            Rate::from_rational(1, 100)
+        ));
+        LiquidStaking::on_finalize(1);
+
+        // liquid_amount_to_fee=TotalLiquidCurrency * (commission_rate*total_rewards/(TotalStakeCurrency+(1-commission_rate)*total_rewards))
+        let commission_rate = CommissionRate::<Test>::get();
+        let total_rewards = MOCK_LEDGER_AMOUNT - bond_amount;
+        let commission_staking_amount = commission_rate.saturating_mul_int(total_rewards);
+        let issurance = <Test as Config>::Assets::total_issuance(SKSM);
+        let matching_ledger = LiquidStaking::matching_pool();
+        let total_active_bonded: u128 = StakingLedgers::<Test>::iter_values()
+            .fold(Zero::zero(), |acc, ledger| {
+                acc.saturating_add(ledger.active)
+            });
+        let total_bonded = total_active_bonded + matching_ledger.total_stake_amount.total
+            - matching_ledger.total_unstake_amount.total;
+        let inflate_rate = Rate::checked_from_rational(
+            commission_staking_amount,
+            total_bonded + total_rewards - commission_staking_amount,
+        )
+        .unwrap();
+
+        let inflate_liquid_amount = inflate_rate.saturating_mul_int(issurance);
+
+        assert_ok!(LiquidStaking::set_staking_ledger(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            get_mock_staking_ledger(derivative_index),
+            get_mock_proof_bytes()
+        ));
+
+        assert_eq!(
+            LiquidStaking::staking_ledger(derivative_index)
+                .unwrap()
+                .total,
+            MOCK_LEDGER_AMOUNT
+        );
+
+        assert_eq!(
+            <Test as Config>::Assets::balance(SKSM, &DefaultProtocolFeeReceiver::get()),
+            inflate_liquid_amount
+        );
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::CommissionMinted(derivative_index, total_rewards, inflate_liquid_amount),
+        ));
+    })
+}
+
+#[test]
+fn small_commissions_should_accumulate_until_minted() {
+    new_test_ext().execute_with(|| {
+        let derivative_index = 0u16;
+        let rewards = ksm(1f64);
+        let staking_ledger = <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+            LiquidStaking::derivative_sovereign_account_id(derivative_index),
+            MOCK_LEDGER_AMOUNT - rewards,
+        );
+        assert_ok!(LiquidStaking::update_commission_rate(
+            RuntimeOrigin::root(),
+            Rate::from_rational(1, 100)
+        ));
+
+        StakingLedgers::<Test>::insert(derivative_index, staking_ledger.clone());
+        let commission =
+            LiquidStaking::get_inflate_liquid_amount(rewards, RewardSource::RelayStaking).unwrap();
+        assert!(!commission.is_zero());
+        MinCommissionMint::set(commission + 1);
+
+        LiquidStaking::on_finalize(1);
+        assert_ok!(LiquidStaking::set_staking_ledger(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            get_mock_staking_ledger(derivative_index),
+            get_mock_proof_bytes()
+        ));
+        assert_eq!(LiquidStaking::pending_commission(), commission);
+        assert_eq!(
+            <Test as Config>::Assets::balance(SKSM, &DefaultProtocolFeeReceiver::get()),
+            0
+        );
+
+        // same rewards again, accumulated commission exceeds the threshold
+        StakingLedgers::<Test>::insert(derivative_index, staking_ledger);
+        let next_commission =
+            LiquidStaking::get_inflate_liquid_amount(rewards, RewardSource::RelayStaking).unwrap();
+        LiquidStaking::on_finalize(1);
+        assert_ok!(LiquidStaking::set_staking_ledger(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            get_mock_staking_ledger(derivative_index),
+            get_mock_proof_bytes()
+        ));
+        assert_eq!(LiquidStaking::pending_commission(), 0);
+        assert_eq!(
+            <Test as Config>::Assets::balance(SKSM, &DefaultProtocolFeeReceiver::get()),
+            commission + next_commission
+        );
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::CommissionMinted(derivative_index, rewards, commission + next_commission),
+        ));
+    })
+}
+
+#[test]
+fn exchange_rate_should_count_pending_commission() {
+    new_test_ext().execute_with(|| {
+        let derivative_index = 0u16;
+        let rewards = ksm(1f64);
+        assert_ok!(LiquidStaking::update_commission_rate(
+            RuntimeOrigin::root(),
+            Rate::from_rational(1, 100)
+        ));
+        StakingLedgers::<Test>::insert(
+            derivative_index,
+            <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+                LiquidStaking::derivative_sovereign_account_id(derivative_index),
+                MOCK_LEDGER_AMOUNT - rewards,
+            ),
+        );
+        let commission =
+            LiquidStaking::get_inflate_liquid_amount(rewards, RewardSource::RelayStaking).unwrap();
+        MinCommissionMint::set(commission + 1);
+
+        LiquidStaking::on_finalize(1);
+        assert_ok!(LiquidStaking::set_staking_ledger(
+            RuntimeOrigin::signed(ALICE),
+            derivative_index,
+            get_mock_staking_ledger(derivative_index),
+            get_mock_proof_bytes()
+        ));
+        assert_eq!(LiquidStaking::pending_commission(), commission);
+
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                LiquidStaking::do_advance_era(1).unwrap();
+                TransactionOutcome::Commit(Ok(()))
+            }
+        ));
+        // commission not minted yet is still owed to the protocol
+        let issuance = <Test as Config>::Assets::total_issuance(SKSM);
+        assert_eq!(
+            LiquidStaking::exchange_rate(),
+            Rate::saturating_from_rational(MOCK_LEDGER_AMOUNT, issuance + commission)
+        );
+        assert_eq!(
+            LiquidStaking::liquid_backing().unwrap().liquid_issuance,
+            issuance + commission
+        );
+    })
+}
+
+#[test]
+fn test_complete_fast_match_unstake_work() {
+    new_test_ext().execute_with(|| {
+        let reserve_factor = LiquidStaking::reserve_factor();
+        let xcm_fees = XcmFees::get();
+        let bond_amount = ksm(10f64);
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(BOB),
+            bond_amount
+        ));
+        let total_stake_amount = bond_amount - xcm_fees - reserve_factor.mul_floor(bond_amount);
+
+        let fast_unstake_amount = ksm(3f64);
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(BOB),
+            fast_unstake_amount,
+            UnstakeProvider::MatchingPool,
+            None,
+            None,
+            None
+        ));
+        assert_ok!(LiquidStaking::fast_match_unstake(
+            RuntimeOrigin::signed(BOB),
+            [BOB].to_vec(),
+        ));
+
+        assert_eq!(
+            <Test as Config>::Assets::balance(SKSM, &DefaultProtocolFeeReceiver::get()),
+            MatchingPoolFastUnstakeFee::get().saturating_mul_int(fast_unstake_amount)
+        );
+
+        assert_eq!(
+            <Test as Config>::Assets::balance(SKSM, &BOB),
+            total_stake_amount - fast_unstake_amount
+        );
+        let pool_stake_amount = total_stake_amount
+            - Rate::one()
+                .saturating_sub(MatchingPoolFastUnstakeFee::get())
+                .saturating_mul_int(fast_unstake_amount);
+        assert_eq!(
+            LiquidStaking::matching_pool(),
+            MatchingLedger {
+                total_stake_amount: ReservableAmount {
+                    total: pool_stake_amount,
+                    reserved: 0
+                },
+                total_unstake_amount: Default::default(),
+            }
+        );
+    })
+}
+
+#[test]
+fn set_protocol_fee_receiver_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            LiquidStaking::protocol_fee_receiver(),
+            DefaultProtocolFeeReceiver::get()
+        );
+        assert_noop!(
+            LiquidStaking::set_protocol_fee_receiver(RuntimeOrigin::signed(ALICE), ALICE),
+            BadOrigin
+        );
+        assert_ok!(LiquidStaking::set_protocol_fee_receiver(
+            RuntimeOrigin::signed(BOB),
+            ALICE
+        ));
+        assert_eq!(LiquidStaking::protocol_fee_receiver(), ALICE);
+
+        assert_ok!(LiquidStaking::stake(RuntimeOrigin::signed(BOB), ksm(10f64)));
+        let alice_balance = <Test as Config>::Assets::balance(SKSM, &ALICE);
+        let fast_unstake_amount = ksm(3f64);
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(BOB),
+            fast_unstake_amount,
+            UnstakeProvider::MatchingPool,
+            None,
+            None,
+            None
+        ));
+        assert_ok!(LiquidStaking::fast_match_unstake(
+            RuntimeOrigin::signed(BOB),
+            [BOB].to_vec(),
         ));
-        // print_events::<Test>("ParaA");
-    });
-    Relay::execute_with(|| {
-        // print_events::<kusama_runtime::Runtime>("Relay");
-        let ledger = RelayStaking::ledger(LiquidStaking::derivative_sovereign_account_id(
-            derivative_index,
-        ))
-        .unwrap();
-        assert_eq!(ledger.total, xcm_transfer_amount);
+
         assert_eq!(
-            RelayBalances::free_balance(LiquidStaking::derivative_sovereign_account_id(
-                derivative_index
-            )),
-            xcm_transfer_amount
+            <Test as Config>::Assets::balance(SKSM, &ALICE) - alice_balance,
+            MatchingPoolFastUnstakeFee::get().saturating_mul_int(fast_unstake_amount)
         );
         assert_eq!(
-            RelayBalances::usable_balance(LiquidStaking::derivative_sovereign_account_id(
-                derivative_index
-            )),
+            <Test as Config>::Assets::balance(SKSM, &DefaultProtocolFeeReceiver::get()),
             0
         );
-    });
+    })
 }
 
 #[test]
-fn update_staking_ledger_cap_should_not_work_if_with_invalid_param() {
+fn fast_unstake_capacity_works() {
     new_test_ext().execute_with(|| {
-        assert_noop!(
-            LiquidStaking::update_staking_ledger_cap(RuntimeOrigin::root(), Zero::zero()),
-            Error::<Test>::InvalidCap
-        );
+        assert_eq!(LiquidStaking::fast_unstake_capacity(), 0);
+        assert_ok!(LiquidStaking::stake(RuntimeOrigin::signed(BOB), ksm(10f64)));
+        assert_eq!(LiquidStaking::fast_unstake_capacity(), ksm(9.95f64));
+
+        ExchangeRate::<Test>::put(Rate::saturating_from_rational(2, 1));
+        assert_eq!(LiquidStaking::fast_unstake_capacity(), ksm(9.95f64) / 2);
     })
 }
 
 #[test]
-fn update_reserve_factor_should_not_work_if_with_invalid_param() {
+fn fast_match_unstake_should_limit_unstakers() {
     new_test_ext().execute_with(|| {
+        MaxUnstakersPerCall::set(1);
         assert_noop!(
-            LiquidStaking::update_reserve_factor(RuntimeOrigin::root(), Ratio::zero()),
-            Error::<Test>::InvalidFactor
-        );
-        assert_noop!(
-            LiquidStaking::update_reserve_factor(RuntimeOrigin::root(), Ratio::one()),
-            Error::<Test>::InvalidFactor
+            LiquidStaking::fast_match_unstake(RuntimeOrigin::signed(BOB), [ALICE, BOB].to_vec()),
+            Error::<Test>::TooManyUnstakers
         );
+        assert_ok!(LiquidStaking::fast_match_unstake(
+            RuntimeOrigin::signed(BOB),
+            [BOB].to_vec(),
+        ));
     })
 }
 
 #[test]
-fn claim_for_should_work() {
+fn fast_match_unstake_should_respect_min_rate() {
     new_test_ext().execute_with(|| {
-        assert_ok!(LiquidStaking::stake(
-            RuntimeOrigin::signed(ALICE),
-            ksm(10f64)
+        assert_ok!(LiquidStaking::stake(RuntimeOrigin::signed(BOB), ksm(10f64)));
+        let min_rate = Rate::saturating_from_rational(11, 10);
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(BOB),
+            ksm(3f64),
+            UnstakeProvider::MatchingPool,
+            Some(min_rate),
+            None,
+            None
         ));
-        assert_eq!(<Test as Config>::Assets::balance(KSM, &ALICE), ksm(90f64));
 
-        assert_ok!(LiquidStaking::unstake(
-            RuntimeOrigin::signed(ALICE),
-            ksm(1f64),
-            Default::default()
+        // Exchange rate is still 1, request stays pending
+        assert_ok!(LiquidStaking::fast_match_unstake(
+            RuntimeOrigin::signed(BOB),
+            [BOB].to_vec(),
+        ));
+        assert_eq!(
+            LiquidStaking::fast_unstake_requests(&BOB),
+            FastUnstakeRequest {
+                amount: ksm(3f64),
+                min_rate: Some(min_rate),
+                auto_match: true,
+                expiry_era: None,
+            }
+        );
+
+        ExchangeRate::<Test>::put(min_rate);
+        assert_ok!(LiquidStaking::fast_match_unstake(
+            RuntimeOrigin::signed(BOB),
+            [BOB].to_vec(),
         ));
+        assert_eq!(LiquidStaking::fast_unstake_requests(&BOB).amount, 0);
+    })
+}
+
+#[test]
+fn fast_match_unstake_should_remove_expired_request() {
+    new_test_ext().execute_with(|| {
+        FastUnstakeRequestLifetime::set(2);
+        assert_ok!(LiquidStaking::stake(RuntimeOrigin::signed(BOB), ksm(10f64)));
         assert_ok!(LiquidStaking::unstake(
             RuntimeOrigin::signed(ALICE),
-            ksm(3.95f64),
-            Default::default()
+            ksm(3f64),
+            UnstakeProvider::MatchingPool,
+            None,
+            None,
+            None
         ));
         assert_eq!(
-            Unlockings::<Test>::get(ALICE).unwrap(),
-            vec![UnlockChunk {
-                value: ksm(4.95f64),
-                era: 4
-            },]
+            LiquidStaking::fast_unstake_requests(&ALICE).expiry_era,
+            Some(LiquidStaking::current_era() + 2)
         );
 
-        assert_noop!(
-            LiquidStaking::claim_for(RuntimeOrigin::signed(BOB), Id(ALICE)),
-            Error::<Test>::NothingToClaim
+        CurrentEra::<Test>::put(LiquidStaking::current_era() + 2);
+        let liquid_balance = <Test as Config>::Assets::balance(SKSM, &ALICE);
+        assert_ok!(LiquidStaking::fast_match_unstake(
+            RuntimeOrigin::signed(BOB),
+            [ALICE].to_vec(),
+        ));
+        assert!(!FastUnstakeRequests::<Test>::contains_key(ALICE));
+        assert_eq!(
+            <Test as Config>::Assets::balance(SKSM, &ALICE),
+            liquid_balance
         );
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::FastUnstakeExpired(ALICE, ksm(3f64)),
+        ));
+    })
+}
 
-        let derivative_index = 0u16;
-        assert_ok!(with_transaction(
-            || -> TransactionOutcome<DispatchResult> {
-                assert_ok!(LiquidStaking::do_advance_era(4));
-                assert_ok!(LiquidStaking::do_matching());
-                TransactionOutcome::Commit(Ok(()))
-            }
+#[test]
+fn fast_match_unstake_should_skip_when_auto_match_disabled() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LiquidStaking::stake(RuntimeOrigin::signed(BOB), ksm(10f64)));
+        assert_noop!(
+            LiquidStaking::set_auto_match(RuntimeOrigin::signed(ALICE), false),
+            Error::<Test>::NoFastUnstakeRequest
+        );
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(3f64),
+            UnstakeProvider::MatchingPool,
+            None,
+            None,
+            None
         ));
-        assert_ok!(LiquidStaking::notification_received(
-            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
-            0,
-            Response::ExecutionResult(None),
+        assert_ok!(LiquidStaking::set_auto_match(
+            RuntimeOrigin::signed(ALICE),
+            false
         ));
-        assert_ok!(LiquidStaking::withdraw_unbonded(
-            RuntimeOrigin::root(),
-            derivative_index,
-            0
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::AutoMatchUpdated(ALICE, false),
         ));
-        assert_ok!(LiquidStaking::notification_received(
-            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
-            1,
-            Response::ExecutionResult(None),
+
+        assert_ok!(LiquidStaking::fast_match_unstake(
+            RuntimeOrigin::signed(BOB),
+            [ALICE].to_vec(),
         ));
+        assert_eq!(
+            LiquidStaking::fast_unstake_requests(&ALICE).amount,
+            ksm(3f64)
+        );
 
-        assert_ok!(LiquidStaking::claim_for(
+        assert_ok!(LiquidStaking::match_unstake(RuntimeOrigin::signed(ALICE)));
+        assert_eq!(LiquidStaking::fast_unstake_requests(&ALICE).amount, 0);
+    })
+}
+
+#[test]
+fn fast_match_unstake_should_skip_dust_match() {
+    new_test_ext().execute_with(|| {
+        MinFastUnstakeMatch::set(ksm(2f64));
+        assert_ok!(LiquidStaking::stake(RuntimeOrigin::signed(BOB), ksm(1f64)));
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(3f64),
+            UnstakeProvider::MatchingPool,
+            None,
+            None,
+            None
+        ));
+
+        // only 0.995 KSM in matching pool, request stays pending untouched
+        assert_ok!(LiquidStaking::fast_match_unstake(
             RuntimeOrigin::signed(BOB),
-            Id(ALICE)
+            [ALICE].to_vec(),
         ));
         assert_eq!(
-            <Test as Config>::Assets::balance(KSM, &ALICE),
-            ksm(90f64) + ksm(4.95f64)
+            LiquidStaking::fast_unstake_requests(&ALICE).amount,
+            ksm(3f64)
+        );
+        assert_eq!(
+            LiquidStaking::matching_pool().total_stake_amount.total,
+            ksm(0.995f64)
         );
 
-        assert!(Unlockings::<Test>::get(ALICE).is_none());
+        assert_ok!(LiquidStaking::stake(RuntimeOrigin::signed(BOB), ksm(10f64)));
+        assert_ok!(LiquidStaking::fast_match_unstake(
+            RuntimeOrigin::signed(BOB),
+            [ALICE].to_vec(),
+        ));
+        assert_eq!(LiquidStaking::fast_unstake_requests(&ALICE).amount, 0);
     })
 }
 
 #[test]
-fn test_on_initialize_work() {
+fn test_partial_fast_match_unstake_work() {
     new_test_ext().execute_with(|| {
-        let derivative_index = 0u16;
-        let xcm_fees = XcmFees::get();
         let reserve_factor = LiquidStaking::reserve_factor();
-
-        // 1.1 stake
-        let bond_amount = ksm(10f64);
+        let xcm_fees = XcmFees::get();
+        let bond_amount = ksm(5f64);
         assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(ALICE),
             bond_amount
         ));
-        let total_stake_amount = bond_amount - xcm_fees - reserve_factor.mul_floor(bond_amount);
+        assert_ok!(LiquidStaking::stake(
+            RuntimeOrigin::signed(BOB),
+            bond_amount
+        ));
+
+        let alice_stake_amount = bond_amount - xcm_fees - reserve_factor.mul_floor(bond_amount);
+        let bob_stake_amount = alice_stake_amount;
+
+        // default exchange_rate is 1
+        let alice_fast_unstake_amount = ksm(10f64);
+        let bob_fast_unstake_amount = ksm(1f64);
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            alice_fast_unstake_amount,
+            UnstakeProvider::MatchingPool,
+            None,
+            None,
+            None
+        ));
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(BOB),
+            bob_fast_unstake_amount,
+            UnstakeProvider::MatchingPool,
+            None,
+            None,
+            None
+        ));
+        assert_ok!(LiquidStaking::fast_match_unstake(
+            RuntimeOrigin::signed(BOB),
+            [BOB, ALICE].to_vec(),
+        ));
+
+        assert_eq!(
+            <Test as Config>::Assets::balance(SKSM, &BOB),
+            bob_stake_amount - bob_fast_unstake_amount
+        );
+
+        let bob_matched_amount = Rate::one()
+            .saturating_sub(MatchingPoolFastUnstakeFee::get())
+            .saturating_mul_int(bob_fast_unstake_amount);
+
+        let available_amount = (alice_stake_amount + bob_stake_amount - bob_matched_amount)
+            .min(alice_fast_unstake_amount);
+        let alice_matched_amount = Rate::one()
+            .saturating_sub(MatchingPoolFastUnstakeFee::get())
+            .saturating_mul_int(available_amount);
+
+        // mint in mock
+        let alice_initial_amount = ksm(100f64);
+        assert_eq!(
+            <Test as Config>::Assets::balance(SKSM, &ALICE),
+            alice_initial_amount + alice_stake_amount - available_amount
+        );
 
-        // 1.2 on_initialize_bond
-        let total_era_blocknumbers = <Test as Config>::EraLength::get();
-        assert_eq!(total_era_blocknumbers, 10);
-        RelayChainValidationDataProvider::set(total_era_blocknumbers);
-        LiquidStaking::on_initialize(System::block_number());
-        assert_eq!(EraStartBlock::<Test>::get(), total_era_blocknumbers);
-        assert_eq!(CurrentEra::<Test>::get(), 1);
-        assert_eq!(LiquidStaking::staking_ledger(derivative_index), None);
         assert_eq!(
             LiquidStaking::matching_pool(),
             MatchingLedger {
                 total_stake_amount: ReservableAmount {
-                    total: total_stake_amount,
-                    reserved: total_stake_amount
+                    total: alice_stake_amount + bob_stake_amount
+                        - bob_matched_amount
+                        - alice_matched_amount,
+                    reserved: 0
                 },
                 total_unstake_amount: Default::default(),
             }
         );
-
-        // 1.3 notification_received bond
-        assert_ok!(LiquidStaking::notification_received(
-            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
-            0,
-            Response::ExecutionResult(None),
-        ));
-
-        let staking_ledger = <StakingLedger<AccountId, BalanceOf<Test>>>::new(
-            LiquidStaking::derivative_sovereign_account_id(derivative_index),
-            total_stake_amount,
-        );
         assert_eq!(
-            LiquidStaking::staking_ledger(derivative_index).unwrap(),
-            staking_ledger
+            LiquidStaking::fast_unstake_requests(&ALICE).amount,
+            alice_fast_unstake_amount - available_amount
         );
 
-        assert_eq!(LiquidStaking::matching_pool(), MatchingLedger::default());
-    })
-}
-
-#[test]
-fn test_set_staking_ledger_work() {
-    new_test_ext().execute_with(|| {
-        let derivative_index = 0u16;
-        let bond_amount = 100;
-        let bond_extra_amount = 50;
-        let mut staking_ledger = <StakingLedger<AccountId, BalanceOf<Test>>>::new(
-            LiquidStaking::derivative_sovereign_account_id(derivative_index),
-            bond_amount,
-        );
-        assert_noop!(
-            LiquidStaking::set_staking_ledger(
-                RuntimeOrigin::signed(ALICE),
-                derivative_index,
-                staking_ledger.clone(),
-                get_mock_proof_bytes()
-            ),
-            Error::<Test>::NotBonded
-        );
-        StakingLedgers::<Test>::insert(derivative_index, staking_ledger.clone());
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                assert_ok!(LiquidStaking::do_matching());
+                TransactionOutcome::Commit(Ok(()))
+            }
+        ));
+    })
+}
+
+#[test]
+fn check_invariants_should_work() {
+    new_test_ext().execute_with(|| {
+        // genesis liquid currency isn't backed by anything
         assert_eq!(
-            LiquidStaking::staking_ledger(derivative_index).unwrap(),
-            staking_ledger.clone()
+            LiquidStaking::check_invariants(),
+            Err(InvariantError::ExchangeRateDeviated)
         );
-        staking_ledger.bond_extra(bond_extra_amount);
-        assert_noop!(
-            LiquidStaking::set_staking_ledger(
-                RuntimeOrigin::signed(ALICE),
-                derivative_index,
-                staking_ledger.clone(),
-                get_mock_proof_bytes()
+
+        StakingLedgers::<Test>::insert(
+            0,
+            <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+                LiquidStaking::derivative_sovereign_account_id(0),
+                ksm(100f64),
             ),
-            Error::<Test>::InvalidProof
         );
-        LiquidStaking::on_finalize(1);
-        assert_ok!(LiquidStaking::set_staking_ledger(
-            RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            get_mock_staking_ledger(derivative_index),
-            get_mock_proof_bytes()
-        ));
+        assert_ok!(LiquidStaking::check_invariants());
 
-        assert_noop!(
-            LiquidStaking::set_staking_ledger(
-                RuntimeOrigin::signed(ALICE),
-                derivative_index,
-                staking_ledger.clone(),
-                get_mock_proof_bytes()
+        // ledger of an index which isn't in `DerivativeIndexList`
+        StakingLedgers::<Test>::insert(
+            1,
+            <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+                LiquidStaking::derivative_sovereign_account_id(1),
+                ksm(1f64),
             ),
-            Error::<Test>::StakingLedgerLocked
         );
+        assert_eq!(
+            LiquidStaking::check_invariants(),
+            Err(InvariantError::ActiveBondedMismatch)
+        );
+        StakingLedgers::<Test>::remove(1);
 
-        LiquidStaking::on_finalize(1);
+        // bond request without its matching pool lock
+        XcmRequests::<Test>::insert(
+            0,
+            XcmRequest::BondExtra {
+                index: 0,
+                amount: ksm(1f64),
+            },
+        );
         assert_eq!(
-            LiquidStaking::staking_ledger(derivative_index)
-                .unwrap()
-                .total,
-            MOCK_LEDGER_AMOUNT
+            LiquidStaking::check_invariants(),
+            Err(InvariantError::XcmRequestLocksExceeded)
         );
     })
 }
 
 #[test]
-fn test_force_set_era_start_block_work() {
+fn on_initialize_should_clamp_era_advance() {
     new_test_ext().execute_with(|| {
-        assert_eq!(EraStartBlock::<Test>::get(), 0);
-        assert_ok!(LiquidStaking::force_set_era_start_block(
-            RuntimeOrigin::root(),
-            11
-        ));
-        assert_eq!(EraStartBlock::<Test>::get(), 11);
-    })
-}
+        let era_length = <Test as Config>::EraLength::get();
+        RelayChainValidationDataProvider::set(5 * era_length);
 
-#[test]
-fn test_force_set_current_era_work() {
-    new_test_ext().execute_with(|| {
-        assert_eq!(CurrentEra::<Test>::get(), 0);
-        assert_ok!(LiquidStaking::force_set_current_era(
-            RuntimeOrigin::root(),
-            12
+        LiquidStaking::on_initialize(System::block_number());
+        assert_eq!(CurrentEra::<Test>::get(), 3);
+        assert_eq!(EraStartBlock::<Test>::get(), 3 * era_length);
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::EraAdvanceClamped(5, 3),
         ));
-        assert_eq!(CurrentEra::<Test>::get(), 12);
+
+        // remaining eras are advanced in the next block
+        LiquidStaking::on_initialize(System::block_number() + 1);
+        assert_eq!(CurrentEra::<Test>::get(), 5);
+        assert_eq!(EraStartBlock::<Test>::get(), 5 * era_length);
     })
 }
 
 #[test]
-fn test_force_notification_received_work() {
+fn era_should_be_advanced_once_per_block() {
     new_test_ext().execute_with(|| {
-        let derivative_index = 0u16;
-        let bond_amount = ksm(10f64);
-        assert_ok!(LiquidStaking::stake(
-            RuntimeOrigin::signed(ALICE),
-            ksm(20f64),
-        ));
+        let block_number = System::block_number();
+        assert_ok!(LiquidStaking::force_advance_era(RuntimeOrigin::root(), 1));
+        assert_eq!(LiquidStaking::current_era(), 1);
+        assert_eq!(LiquidStaking::last_era_advanced_block(), Some(block_number));
+        assert_noop!(
+            LiquidStaking::force_advance_era(RuntimeOrigin::root(), 1),
+            Error::<Test>::EraAlreadyAdvanced
+        );
 
-        assert_ok!(LiquidStaking::bond(
-            RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            bond_amount,
-            RewardDestination::Staked
+        // organic advance in the same block is rejected as well
+        RelayChainValidationDataProvider::set(<Test as Config>::EraLength::get());
+        LiquidStaking::on_initialize(block_number);
+        assert_eq!(LiquidStaking::current_era(), 1);
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::OnInitializeFailed(
+                OnInitializeStep::AdvanceEra,
+                Error::<Test>::EraAlreadyAdvanced.into(),
+            ),
         ));
 
-        let query_id = 0;
-        assert_eq!(
-            XcmRequests::<Test>::get(query_id),
-            Some(XcmRequest::Bond {
-                index: derivative_index,
-                amount: bond_amount,
-            })
-        );
+        System::set_block_number(block_number + 1);
+        LiquidStaking::on_initialize(block_number + 1);
+        assert_eq!(LiquidStaking::current_era(), 2);
         assert_noop!(
-            LiquidStaking::notification_received(
-                RuntimeOrigin::signed(ALICE),
-                query_id,
-                Response::ExecutionResult(None),
-            ),
-            BadOrigin
+            LiquidStaking::force_advance_era(RuntimeOrigin::root(), 1),
+            Error::<Test>::EraAlreadyAdvanced
         );
-        assert_ok!(LiquidStaking::notification_received(
-            RuntimeOrigin::root(),
-            query_id,
-            Response::ExecutionResult(None),
-        ));
-        assert_eq!(XcmRequests::<Test>::get(query_id), None);
     })
 }
 
 #[test]
-fn test_storage_proof_approach_should_work() {
-    let relay_root = sp_core::hash::H256::from_slice(&hex::decode(ROOT_HASH).unwrap());
-    let key = hex::decode(MOCK_KEY).unwrap();
-    let value = hex::decode(MOCK_DATA).unwrap();
-    let relay_proof = StorageProof::new(get_mock_proof_bytes());
-    let result = sp_state_machine::read_proof_check::<BlakeTwo256, _>(
-        relay_root,
-        relay_proof.clone(),
-        [key.clone()],
-    )
-    .unwrap();
-    assert_eq!(
-        result.into_iter().collect::<Vec<_>>(),
-        vec![(key, Some(value))],
-    );
-}
-
-#[test]
-fn test_verify_trie_proof_work() {
-    type LayoutV1 = sp_trie::LayoutV1<BlakeTwo256>;
-    let relay_root = sp_core::hash::H256::from_slice(&hex::decode(ROOT_HASH).unwrap());
-    let key = hex::decode(MOCK_KEY).unwrap();
-    let value = hex::decode(MOCK_DATA).unwrap();
-    let relay_proof = StorageProof::new(get_mock_proof_bytes());
-    let db = relay_proof.into_memory_db();
-    let result = sp_trie::read_trie_value::<LayoutV1, _>(&db, &relay_root, &key, None, None)
-        .unwrap()
-        .unwrap();
-    assert_eq!(result, value);
-}
-
-#[test]
-fn test_verify_merkle_proof_work() {
+fn set_current_era_should_note_era_advance() {
     new_test_ext().execute_with(|| {
-        use codec::Encode;
-        let derivative_index = 0u16;
-        let staking_ledger = get_mock_staking_ledger(derivative_index);
-        let key = LiquidStaking::get_staking_ledger_key(derivative_index);
-        let value = staking_ledger.encode();
-        assert_eq!(hex::encode(&value), MOCK_DATA);
+        let block_number = System::block_number();
         LiquidStaking::on_finalize(1);
-        assert!(LiquidStaking::verify_merkle_proof(
-            key,
-            value,
+        // Staking::CurrentEra in the mock proof
+        let era = 3492;
+        assert_ok!(LiquidStaking::set_current_era(
+            RuntimeOrigin::signed(ALICE),
+            era,
+            get_mock_proof_bytes()
+        ));
+        assert_eq!(LiquidStaking::current_era(), era);
+        assert_eq!(LiquidStaking::last_era_advanced_block(), Some(block_number));
+        assert_noop!(
+            LiquidStaking::force_advance_era(RuntimeOrigin::root(), 1),
+            Error::<Test>::EraAlreadyAdvanced
+        );
+
+        // resubmitting the same era doesn't advance it again
+        assert_ok!(LiquidStaking::set_current_era(
+            RuntimeOrigin::signed(ALICE),
+            era,
             get_mock_proof_bytes()
         ));
+        assert_eq!(LiquidStaking::current_era(), era);
     })
 }
 
 #[test]
-fn reduce_reserves_works() {
+fn unbonding_schedule_should_work() {
     new_test_ext().execute_with(|| {
-        // Stake 1000 KSM, 0.5% for reserves
-        assert_ok!(LiquidStaking::stake(
-            RuntimeOrigin::signed(ALICE),
-            ksm(100f64)
-        ));
-        assert_eq!(LiquidStaking::total_reserves(), ksm(0.5f64));
-        // Reduce 20 KSM reserves
-        assert_ok!(LiquidStaking::reduce_reserves(
-            RuntimeOrigin::root(),
-            Id(ALICE),
-            ksm(0.2f64)
-        ));
-        assert_eq!(LiquidStaking::total_reserves(), ksm(0.3f64));
+        assert_eq!(LiquidStaking::unbonding_schedule(0), vec![]);
 
-        // should failed if exceed the cap
-        assert_noop!(
-            LiquidStaking::reduce_reserves(RuntimeOrigin::root(), Id(ALICE), ksm(0.31f64)),
-            Underflow
+        for (index, unlocking) in [
+            (0u16, vec![(3, ksm(1f64)), (5, ksm(2f64))]),
+            (1u16, vec![(4, ksm(3f64)), (5, ksm(4f64))]),
+        ] {
+            StakingLedgers::<Test>::insert(
+                index,
+                StakingLedger {
+                    stash: LiquidStaking::derivative_sovereign_account_id(index),
+                    total: ksm(10f64),
+                    active: ksm(10f64) - unlocking.iter().map(|(_, value)| value).sum::<u128>(),
+                    unlocking: unlocking
+                        .into_iter()
+                        .map(|(era, value)| UnlockChunk { value, era })
+                        .collect(),
+                    claimed_rewards: vec![],
+                },
+            );
+        }
+
+        assert_eq!(
+            LiquidStaking::unbonding_schedule(0),
+            vec![(3, ksm(1f64)), (5, ksm(2f64))]
+        );
+        assert_eq!(
+            LiquidStaking::total_unbonding_schedule(),
+            vec![(3, ksm(1f64)), (4, ksm(3f64)), (5, ksm(6f64))]
         );
     })
 }
 
 #[test]
-fn cancel_unstake_works() {
+fn era_net_flow_should_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(ALICE),
@@ -1038,302 +4032,436 @@ fn cancel_unstake_works() {
         ));
         assert_ok!(LiquidStaking::unstake(
             RuntimeOrigin::signed(ALICE),
-            ksm(6f64),
-            UnstakeProvider::MatchingPool
+            ksm(1f64),
+            UnstakeProvider::RelayChain,
+            None,
+            None,
+            None
         ));
-
-        assert_eq!(LiquidStaking::fast_unstake_requests(&ALICE), ksm(6f64));
-
-        // Check storage is correct
-        assert_eq!(ExchangeRate::<Test>::get(), Rate::one());
-        assert_eq!(
-            MatchingPool::<Test>::get(),
-            MatchingLedger {
-                total_stake_amount: ReservableAmount {
-                    total: ksm(9.95f64),
-                    reserved: 0
-                },
-                total_unstake_amount: ReservableAmount {
-                    total: 0,
-                    reserved: 0
-                }
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                assert_ok!(LiquidStaking::do_advance_era(1));
+                assert_ok!(LiquidStaking::do_matching());
+                TransactionOutcome::Commit(Ok(()))
             }
+        ));
+        assert_eq!(LiquidStaking::era_net_flow(1), (ksm(9.95f64), ksm(1f64)));
+        assert_eq!(
+            LiquidStaking::era_net_flows(),
+            vec![(1, ksm(9.95f64), ksm(1f64))]
         );
 
-        assert_ok!(LiquidStaking::cancel_unstake(
-            RuntimeOrigin::signed(ALICE),
-            ksm(6f64)
+        // pruned once older than the retention window
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                assert_ok!(LiquidStaking::do_advance_era(HistoryDepth::get() + 1));
+                assert_ok!(LiquidStaking::do_matching());
+                TransactionOutcome::Commit(Ok(()))
+            }
         ));
-        assert_eq!(
-            MatchingPool::<Test>::get(),
-            MatchingLedger {
-                total_stake_amount: ReservableAmount {
-                    total: ksm(9.95f64),
-                    reserved: 0
+        assert!(!EraNetFlow::<Test>::contains_key(1));
+    })
+}
+
+#[test]
+fn all_staking_ledgers_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(LiquidStaking::all_staking_ledgers(), vec![]);
+
+        for index in [1u16, 0u16] {
+            StakingLedgers::<Test>::insert(
+                index,
+                StakingLedger {
+                    stash: LiquidStaking::derivative_sovereign_account_id(index),
+                    total: ksm(10f64),
+                    active: ksm(9f64),
+                    unlocking: vec![UnlockChunk {
+                        value: ksm(1f64),
+                        era: 3,
+                    }],
+                    claimed_rewards: vec![],
                 },
-                total_unstake_amount: ReservableAmount {
-                    total: 0,
-                    reserved: 0
-                }
-            }
-        );
+            );
+        }
+        IsUpdated::<Test>::insert(1, true);
 
-        assert_eq!(LiquidStaking::fast_unstake_requests(&ALICE), 0);
+        let ledger_info = |index: u16, is_updated: bool| StakingLedgerInfo {
+            stash: LiquidStaking::derivative_sovereign_account_id(index),
+            total: ksm(10f64),
+            active: ksm(9f64),
+            unlocking: vec![(3, ksm(1f64))],
+            claimed_rewards: vec![],
+            is_updated,
+        };
+        assert_eq!(
+            LiquidStaking::all_staking_ledgers(),
+            vec![(0, ledger_info(0, false)), (1, ledger_info(1, true))]
+        );
     })
 }
 
 #[test]
-fn fast_unstake_works() {
+fn derivative_accounts_should_work() {
     new_test_ext().execute_with(|| {
-        assert_ok!(LiquidStaking::stake(
-            RuntimeOrigin::signed(ALICE),
-            ksm(10f64)
-        ));
-        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, ksm(100f64)));
-        assert_ok!(Loans::collateral_asset(
-            RuntimeOrigin::signed(BOB),
-            KSM,
-            true
-        ));
-        assert_ok!(LiquidStaking::unstake(
-            RuntimeOrigin::signed(ALICE),
-            ksm(6f64),
-            UnstakeProvider::Loans
-        ));
+        DerivativeIndexList::set(vec![0, 1]);
         assert_eq!(
-            Unlockings::<Test>::get(LiquidStaking::loans_account_id()).unwrap(),
-            vec![UnlockChunk {
-                value: ksm(6f64),
-                era: 4
-            },]
+            LiquidStaking::derivative_accounts(),
+            vec![
+                (0, LiquidStaking::derivative_sovereign_account_id(0)),
+                (1, LiquidStaking::derivative_sovereign_account_id(1)),
+            ]
         );
-        // 90 * 1e12 + (6 * (1 - 8/1000) * 1e12)
+        assert_ne!(
+            LiquidStaking::derivative_sovereign_account_id(0),
+            LiquidStaking::derivative_sovereign_account_id(1)
+        );
+    })
+}
+
+#[test]
+fn ledger_cap_headroom_should_work() {
+    new_test_ext().execute_with(|| {
+        DerivativeIndexList::set(vec![0, 1, 2]);
+        for (derivative_index, total) in [(0u16, ksm(10f64)), (1u16, ksm(10001f64))] {
+            StakingLedgers::<Test>::insert(
+                derivative_index,
+                <StakingLedger<AccountId, BalanceOf<Test>>>::new(
+                    LiquidStaking::derivative_sovereign_account_id(derivative_index),
+                    total,
+                ),
+            );
+        }
+
+        // floored at zero once the cap is exceeded
         assert_eq!(
-            <Test as Config>::Assets::balance(KSM, &ALICE),
-            95952000000000u128
+            LiquidStaking::ledger_cap_headroom(),
+            vec![(0, ksm(9990f64)), (1, 0), (2, ksm(10000f64))]
         );
+    })
+}
 
+#[test]
+fn force_consolidate_ledger_should_work() {
+    new_test_ext().execute_with(|| {
         let derivative_index = 0u16;
-        assert_ok!(with_transaction(
-            || -> TransactionOutcome<DispatchResult> {
-                assert_ok!(LiquidStaking::do_matching());
-                assert_ok!(LiquidStaking::do_advance_era(4));
-                TransactionOutcome::Commit(Ok(()))
-            }
-        ));
-        assert_ok!(LiquidStaking::notification_received(
-            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
-            0,
-            Response::ExecutionResult(None),
-        ));
-        assert_ok!(LiquidStaking::withdraw_unbonded(
-            RuntimeOrigin::root(),
+        assert_noop!(
+            LiquidStaking::force_consolidate_ledger(RuntimeOrigin::signed(BOB), derivative_index),
+            Error::<Test>::NotBonded
+        );
+
+        StakingLedgers::<Test>::insert(
             derivative_index,
-            0
-        ));
-        assert_ok!(LiquidStaking::notification_received(
-            pallet_xcm::Origin::Response(MultiLocation::parent()).into(),
-            1,
-            Response::ExecutionResult(None),
-        ));
+            StakingLedger {
+                stash: LiquidStaking::derivative_sovereign_account_id(derivative_index),
+                total: ksm(10f64),
+                active: ksm(7f64),
+                unlocking: vec![
+                    UnlockChunk {
+                        value: ksm(1f64),
+                        era: 3,
+                    },
+                    UnlockChunk {
+                        value: ksm(2f64),
+                        era: 5,
+                    },
+                ],
+                claimed_rewards: vec![],
+            },
+        );
+        CurrentEra::<Test>::put(3);
+        let pool_balance = <Test as Config>::Assets::balance(KSM, &LiquidStaking::account_id());
 
-        assert_ok!(LiquidStaking::claim_for(
+        assert_noop!(
+            LiquidStaking::force_consolidate_ledger(RuntimeOrigin::signed(ALICE), derivative_index),
+            BadOrigin
+        );
+        assert_ok!(LiquidStaking::force_consolidate_ledger(
             RuntimeOrigin::signed(BOB),
-            Id(LiquidStaking::loans_account_id())
+            derivative_index
         ));
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::LedgerConsolidated(derivative_index, ksm(1f64)),
+        ));
+
+        let ledger = LiquidStaking::staking_ledger(derivative_index).unwrap();
+        assert_eq!(ledger.total, ksm(9f64));
         assert_eq!(
-            Unlockings::<Test>::get(LiquidStaking::loans_account_id()),
-            None
+            ledger.unlocking,
+            vec![UnlockChunk {
+                value: ksm(2f64),
+                era: 5,
+            }]
+        );
+        assert_eq!(
+            <Test as Config>::Assets::balance(KSM, &LiquidStaking::account_id()),
+            pool_balance + ksm(1f64)
+        );
+
+        // matured chunks can't be credited twice
+        assert_noop!(
+            LiquidStaking::force_consolidate_ledger(RuntimeOrigin::signed(BOB), derivative_index),
+            Error::<Test>::NothingToConsolidate
         );
     })
 }
 
 #[test]
-fn test_charge_commission_work() {
+fn exchange_rate_initialized_should_be_emitted_at_block_one() {
     new_test_ext().execute_with(|| {
-        let derivative_index = 0u16;
-        let bond_amount = ksm(200f64);
-        let staking_ledger = <StakingLedger<AccountId, BalanceOf<Test>>>::new(
-            LiquidStaking::derivative_sovereign_account_id(derivative_index),
-            bond_amount,
-        );
-        StakingLedgers::<Test>::insert(derivative_index, staking_ledger.clone());
-        assert_ok!(LiquidStaking::update_commission_rate(
-            RuntimeOrigin::root(),
-            Rate::from_rational(1, 100)
+        LiquidStaking::on_initialize(1);
+        ParaSystem::assert_has_event(mock::RuntimeEvent::LiquidStaking(
+            crate::Event::ExchangeRateInitialized(Rate::one()),
         ));
-        LiquidStaking::on_finalize(1);
+    })
+}
 
-        // liquid_amount_to_fee=TotalLiquidCurrency * (commission_rate*total_rewards/(TotalStakeCurrency+(1-commission_rate)*total_rewards))
-        let commission_rate = CommissionRate::<Test>::get();
-        let total_rewards = MOCK_LEDGER_AMOUNT - bond_amount;
-        let commission_staking_amount = commission_rate.saturating_mul_int(total_rewards);
-        let issurance = <Test as Config>::Assets::total_issuance(SKSM);
-        let matching_ledger = LiquidStaking::matching_pool();
-        let total_active_bonded: u128 = StakingLedgers::<Test>::iter_values()
-            .fold(Zero::zero(), |acc, ledger| {
-                acc.saturating_add(ledger.active)
-            });
-        let total_bonded = total_active_bonded + matching_ledger.total_stake_amount.total
-            - matching_ledger.total_unstake_amount.total;
-        let inflate_rate = Rate::checked_from_rational(
-            commission_staking_amount,
-            total_bonded + total_rewards - commission_staking_amount,
-        )
+#[test]
+#[should_panic(expected = "genesis exchange_rate must be non-zero")]
+fn genesis_should_reject_zero_exchange_rate() {
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
         .unwrap();
+    GenesisBuild::<Test>::assimilate_storage(
+        &crate::GenesisConfig {
+            exchange_rate: Rate::zero(),
+            reserve_factor: RESERVE_FACTOR,
+        },
+        &mut t,
+    )
+    .unwrap();
+}
 
-        let inflate_liquid_amount = inflate_rate.saturating_mul_int(issurance);
+#[test]
+fn default_genesis_should_build() {
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    GenesisBuild::<Test>::assimilate_storage(&crate::GenesisConfig::default(), &mut t).unwrap();
+    sp_io::TestExternalities::new(t).execute_with(|| {
+        assert_eq!(LiquidStaking::exchange_rate(), Rate::one());
+    });
+}
 
-        assert_ok!(LiquidStaking::set_staking_ledger(
-            RuntimeOrigin::signed(ALICE),
-            derivative_index,
-            get_mock_staking_ledger(derivative_index),
-            get_mock_proof_bytes()
-        ));
+#[test]
+fn estimated_apy_should_work() {
+    new_test_ext().execute_with(|| {
+        let advance_era = || {
+            assert_ok!(with_transaction(
+                || -> TransactionOutcome<DispatchResult> {
+                    assert_ok!(LiquidStaking::do_advance_era(1));
+                    TransactionOutcome::Commit(Ok(()))
+                }
+            ));
+        };
+        advance_era();
+        assert_eq!(LiquidStaking::era_exchange_rate(1), Some(Rate::one()));
+        // era 0 wasn't recorded
+        assert_eq!(LiquidStaking::estimated_apy(1), None);
 
+        ExchangeRate::<Test>::put(Rate::saturating_from_rational(1001u32, 1000u32));
+        advance_era();
+        // 0.1% growth in one era, 525600 eras per year
         assert_eq!(
-            LiquidStaking::staking_ledger(derivative_index)
-                .unwrap()
-                .total,
-            MOCK_LEDGER_AMOUNT
+            LiquidStaking::estimated_apy(1),
+            Some(Rate::saturating_from_rational(5256u32, 10u32))
         );
+        assert_eq!(LiquidStaking::estimated_apy(0), None);
+        assert_eq!(LiquidStaking::estimated_apy(2), None);
 
-        assert_eq!(
-            <Test as Config>::Assets::balance(SKSM, &DefaultProtocolFeeReceiver::get()),
-            inflate_liquid_amount
-        )
+        // pruned once older than the history depth
+        assert_ok!(with_transaction(
+            || -> TransactionOutcome<DispatchResult> {
+                assert_ok!(LiquidStaking::do_advance_era(HistoryDepth::get()));
+                TransactionOutcome::Commit(Ok(()))
+            }
+        ));
+        assert_eq!(LiquidStaking::era_exchange_rate(1), None);
+        assert!(LiquidStaking::era_exchange_rate(2).is_some());
     })
 }
 
 #[test]
-fn test_complete_fast_match_unstake_work() {
+fn history_should_not_exceed_history_depth() {
     new_test_ext().execute_with(|| {
-        let reserve_factor = LiquidStaking::reserve_factor();
-        let xcm_fees = XcmFees::get();
-        let bond_amount = ksm(10f64);
-        assert_ok!(LiquidStaking::stake(
-            RuntimeOrigin::signed(BOB),
-            bond_amount
-        ));
-        let total_stake_amount = bond_amount - xcm_fees - reserve_factor.mul_floor(bond_amount);
-
-        let fast_unstake_amount = ksm(3f64);
-        assert_ok!(LiquidStaking::unstake(
-            RuntimeOrigin::signed(BOB),
-            fast_unstake_amount,
-            UnstakeProvider::MatchingPool
-        ));
-        assert_ok!(LiquidStaking::fast_match_unstake(
-            RuntimeOrigin::signed(BOB),
-            [BOB].to_vec(),
-        ));
-
+        let advance_era = |offset| {
+            assert_ok!(with_transaction(
+                || -> TransactionOutcome<DispatchResult> {
+                    assert_ok!(LiquidStaking::do_advance_era(offset));
+                    TransactionOutcome::Commit(Ok(()))
+                }
+            ));
+        };
+        for _ in 0..HistoryDepth::get() * 3 {
+            advance_era(1);
+            EraNetFlow::<Test>::insert(LiquidStaking::current_era(), (ksm(1f64), ksm(0f64)));
+            assert!(
+                EraExchangeRates::<Test>::iter_keys().count() <= HistoryDepth::get() as usize + 1
+            );
+            assert!(EraNetFlow::<Test>::iter_keys().count() <= HistoryDepth::get() as usize + 1);
+        }
+        let oldest_era = LiquidStaking::current_era() - HistoryDepth::get();
         assert_eq!(
-            <Test as Config>::Assets::balance(SKSM, &DefaultProtocolFeeReceiver::get()),
-            MatchingPoolFastUnstakeFee::get().saturating_mul_int(fast_unstake_amount)
+            EraExchangeRates::<Test>::iter_keys().min(),
+            Some(oldest_era)
         );
+        assert_eq!(EraNetFlow::<Test>::iter_keys().min(), Some(oldest_era));
 
+        // a large backlog is pruned over several era advances
+        HistoryPrunedEra::<Test>::put(0);
+        for era in 0..MAX_HISTORY_PRUNE_PER_ERA * 2 {
+            EraExchangeRates::<Test>::insert(era, Rate::one());
+        }
+        CurrentEra::<Test>::put(MAX_HISTORY_PRUNE_PER_ERA * 2 + HistoryDepth::get());
+        advance_era(1);
         assert_eq!(
-            <Test as Config>::Assets::balance(SKSM, &BOB),
-            total_stake_amount - fast_unstake_amount
+            EraExchangeRates::<Test>::iter_keys().min(),
+            Some(MAX_HISTORY_PRUNE_PER_ERA)
         );
-        let pool_stake_amount = total_stake_amount
-            - Rate::one()
-                .saturating_sub(MatchingPoolFastUnstakeFee::get())
-                .saturating_mul_int(fast_unstake_amount);
         assert_eq!(
-            LiquidStaking::matching_pool(),
-            MatchingLedger {
-                total_stake_amount: ReservableAmount {
-                    total: pool_stake_amount,
-                    reserved: 0
-                },
-                total_unstake_amount: Default::default(),
-            }
+            LiquidStaking::history_pruned_era(),
+            Some(MAX_HISTORY_PRUNE_PER_ERA)
+        );
+        advance_era(1);
+        assert_eq!(
+            EraExchangeRates::<Test>::iter_keys().min(),
+            Some(MAX_HISTORY_PRUNE_PER_ERA * 2)
         );
     })
 }
 
 #[test]
-fn test_partial_fast_match_unstake_work() {
+fn many_small_unstakes_should_not_leave_unbacked_liquid() {
     new_test_ext().execute_with(|| {
-        let reserve_factor = LiquidStaking::reserve_factor();
-        let xcm_fees = XcmFees::get();
-        let bond_amount = ksm(5f64);
-        assert_ok!(LiquidStaking::stake(
-            RuntimeOrigin::signed(ALICE),
-            bond_amount
-        ));
+        DustThreshold::set(ksm(0.01f64));
+        ExchangeRate::<Test>::put(Rate::saturating_from_rational(11u32, 10u32));
         assert_ok!(LiquidStaking::stake(
             RuntimeOrigin::signed(BOB),
-            bond_amount
+            ksm(100f64)
         ));
 
-        let alice_stake_amount = bond_amount - xcm_fees - reserve_factor.mul_floor(bond_amount);
-        let bob_stake_amount = alice_stake_amount;
+        let issuance = <Test as Config>::Assets::total_issuance(SKSM);
+        let mut total_burned = 0;
+        for i in 0..50u128 {
+            let liquid_balance = <Test as Config>::Assets::balance(SKSM, &BOB);
+            // odd amounts so that every conversion rounds
+            let liquid_amount = ksm(1f64) + i * 7 + 1;
+            assert_ok!(LiquidStaking::unstake(
+                RuntimeOrigin::signed(BOB),
+                liquid_amount,
+                Default::default(),
+                None,
+                None,
+                None
+            ));
+            assert_eq!(
+                liquid_balance - <Test as Config>::Assets::balance(SKSM, &BOB),
+                liquid_amount
+            );
+            total_burned += liquid_amount;
+        }
 
-        // default exchange_rate is 1
-        let alice_fast_unstake_amount = ksm(10f64);
-        let bob_fast_unstake_amount = ksm(1f64);
+        // leaves less than the dust threshold, which is unstaked as well
+        let liquid_balance = <Test as Config>::Assets::balance(SKSM, &BOB);
         assert_ok!(LiquidStaking::unstake(
-            RuntimeOrigin::signed(ALICE),
-            alice_fast_unstake_amount,
-            UnstakeProvider::MatchingPool
-        ));
-        assert_ok!(LiquidStaking::unstake(
-            RuntimeOrigin::signed(BOB),
-            bob_fast_unstake_amount,
-            UnstakeProvider::MatchingPool
-        ));
-        assert_ok!(LiquidStaking::fast_match_unstake(
             RuntimeOrigin::signed(BOB),
-            [BOB, ALICE].to_vec(),
+            liquid_balance - ksm(0.005f64),
+            Default::default(),
+            None,
+            None,
+            None
         ));
-
+        assert_eq!(<Test as Config>::Assets::balance(SKSM, &BOB), 0);
+        total_burned += liquid_balance;
         assert_eq!(
-            <Test as Config>::Assets::balance(SKSM, &BOB),
-            bob_stake_amount - bob_fast_unstake_amount
+            issuance - <Test as Config>::Assets::total_issuance(SKSM),
+            total_burned
         );
 
-        let bob_matched_amount = Rate::one()
-            .saturating_sub(MatchingPoolFastUnstakeFee::get())
-            .saturating_mul_int(bob_fast_unstake_amount);
-
-        let available_amount = (alice_stake_amount + bob_stake_amount - bob_matched_amount)
-            .min(alice_fast_unstake_amount);
-        let alice_matched_amount = Rate::one()
-            .saturating_sub(MatchingPoolFastUnstakeFee::get())
-            .saturating_mul_int(available_amount);
-
-        // mint in mock
-        let alice_initial_amount = ksm(100f64);
+        // rounding never credits more than the burned liquid is worth
+        let total_unstaked: u128 = LiquidStaking::unlockings(BOB)
+            .unwrap()
+            .iter()
+            .map(|chunk| chunk.value)
+            .sum();
+        assert!(total_unstaked <= LiquidStaking::exchange_rate().saturating_mul_int(total_burned));
         assert_eq!(
-            <Test as Config>::Assets::balance(SKSM, &ALICE),
-            alice_initial_amount + alice_stake_amount - available_amount
+            MatchingPool::<Test>::get().total_unstake_amount.total,
+            total_unstaked
         );
+    })
+}
 
+#[test]
+fn full_pending_should_work() {
+    new_test_ext().execute_with(|| {
         assert_eq!(
-            LiquidStaking::matching_pool(),
-            MatchingLedger {
-                total_stake_amount: ReservableAmount {
-                    total: alice_stake_amount + bob_stake_amount
-                        - bob_matched_amount
-                        - alice_matched_amount,
-                    reserved: 0
-                },
-                total_unstake_amount: Default::default(),
+            LiquidStaking::full_pending(&ALICE),
+            FullPending {
+                unlockings: vec![],
+                claimable: 0,
+                fast_unstake_amount: 0,
             }
         );
-        assert_eq!(
-            LiquidStaking::fast_unstake_requests(&ALICE),
-            alice_fast_unstake_amount - available_amount
+
+        let current_era = LiquidStaking::current_era();
+        Unlockings::<Test>::insert(
+            ALICE,
+            vec![
+                UnlockChunk {
+                    value: ksm(2f64),
+                    era: current_era,
+                },
+                UnlockChunk {
+                    value: ksm(3f64),
+                    era: current_era + 1,
+                },
+            ],
+        );
+        assert_ok!(<Test as Config>::Assets::mint_into(
+            KSM,
+            &LiquidStaking::account_id(),
+            ksm(5f64)
+        ));
+        assert_ok!(LiquidStaking::unstake(
+            RuntimeOrigin::signed(ALICE),
+            ksm(1f64),
+            UnstakeProvider::MatchingPool,
+            None,
+            None,
+            None
+        ));
+
+        let full_pending = LiquidStaking::full_pending(&ALICE);
+        assert_eq!(full_pending.claimable, ksm(2f64));
+        assert_eq!(full_pending.fast_unstake_amount, ksm(1f64));
+        assert_eq!(full_pending.unlockings[0], (current_era, ksm(2f64)));
+        assert_eq!(full_pending.unlockings[1], (current_era + 1, ksm(3f64)));
+    })
+}
+
+#[test]
+fn migrate_to_v4_should_wrap_fast_unstake_amounts() {
+    use frame_support::traits::OnRuntimeUpgrade;
+    use migrations::v4::MigrateToV4;
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::<Test>::put(Versions::V3);
+        frame_support::storage::unhashed::put(
+            &FastUnstakeRequests::<Test>::hashed_key_for(ALICE),
+            &ksm(10f64),
         );
 
-        assert_ok!(with_transaction(
-            || -> TransactionOutcome<DispatchResult> {
-                assert_ok!(LiquidStaking::do_matching());
-                TransactionOutcome::Commit(Ok(()))
+        MigrateToV4::<Test>::on_runtime_upgrade();
+
+        assert_eq!(
+            LiquidStaking::fast_unstake_requests(ALICE),
+            FastUnstakeRequest {
+                amount: ksm(10f64),
+                min_rate: None,
+                auto_match: true,
+                expiry_era: None,
             }
-        ));
+        );
+        assert_eq!(StorageVersion::<Test>::get(), Versions::V4);
     })
 }
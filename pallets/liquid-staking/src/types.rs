@@ -5,7 +5,8 @@ use frame_support::{
     dispatch::DispatchResult,
     traits::{tokens::Balance as BalanceT, DefensiveSaturating},
 };
-use primitives::{DerivativeIndex, EraIndex};
+use pallet_traits::ump::RewardDestination;
+use primitives::{DerivativeIndex, EraIndex, Rate, Ratio};
 use scale_info::TypeInfo;
 use sp_runtime::{traits::Zero, ArithmeticError, DispatchError, FixedPointOperand, RuntimeDebug};
 use sp_std::{cmp::Ordering, result::Result, vec, vec::Vec};
@@ -142,7 +143,7 @@ impl<Balance: BalanceT + FixedPointOperand> MatchingLedger<Balance> {
         Ok(())
     }
 
-    fn remove_stake_amount_lock(&mut self, amount: Balance) -> DispatchResult {
+    pub fn remove_stake_amount_lock(&mut self, amount: Balance) -> DispatchResult {
         self.total_stake_amount.reserved = self
             .total_stake_amount
             .reserved
@@ -164,7 +165,7 @@ impl<Balance: BalanceT + FixedPointOperand> MatchingLedger<Balance> {
         Ok(())
     }
 
-    fn remove_unstake_amount_lock(&mut self, amount: Balance) -> DispatchResult {
+    pub fn remove_unstake_amount_lock(&mut self, amount: Balance) -> DispatchResult {
         self.total_unstake_amount.reserved = self
             .total_unstake_amount
             .reserved
@@ -194,6 +195,55 @@ impl<Balance: BalanceT + FixedPointOperand> MatchingLedger<Balance> {
     }
 }
 
+/// Step of `on_initialize` that failed
+#[derive(Copy, Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum OnInitializeStep {
+    /// Sending bonds deferred from previous blocks
+    DeferredBonds,
+    /// Matching, once per era or within the era
+    Matching,
+    /// Advancing to the next era
+    AdvanceEra,
+}
+
+/// Reason a relaychain storage proof failed to verify
+#[derive(Copy, Clone, Eq, PartialEq, RuntimeDebug)]
+pub enum ProofError {
+    /// Relaychain validation data isn't available yet in this block
+    NoValidationData,
+    /// The key isn't present under the relaychain storage root
+    KeyNotFound,
+    /// The value stored under the key differs from the provided one
+    ValueMismatch,
+    /// The proof can't be decoded or is missing trie nodes
+    MalformedProof,
+}
+
+/// User's fast unstake request waiting to be matched
+#[derive(Copy, Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct FastUnstakeRequest<Balance> {
+    /// The liquid amount to be matched
+    pub amount: Balance,
+    /// Matching is skipped while the exchange rate is below this rate
+    pub min_rate: Option<Rate>,
+    /// Whether `fast_match_unstake` may match this request, otherwise
+    /// only the unstaker can match it through `match_unstake`
+    pub auto_match: bool,
+    /// The request is removed without matching from this era on
+    pub expiry_era: Option<EraIndex>,
+}
+
+impl<Balance: Default> Default for FastUnstakeRequest<Balance> {
+    fn default() -> Self {
+        Self {
+            amount: Default::default(),
+            min_rate: None,
+            auto_match: true,
+            expiry_era: None,
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[scale_info(skip_type_params(T))]
 pub enum XcmRequest<T: Config> {
@@ -221,6 +271,37 @@ pub enum XcmRequest<T: Config> {
         index: DerivativeIndex,
         targets: Vec<T::AccountId>,
     },
+    SetPayee {
+        index: DerivativeIndex,
+        payee: RewardDestination<T::AccountId>,
+    },
+}
+
+/// Relaychain staking operation executed by `execute_staking_ops`
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub enum StakingOp<T: Config> {
+    Bond {
+        index: DerivativeIndex,
+        amount: BalanceOf<T>,
+        payee: RewardDestination<T::AccountId>,
+    },
+    BondExtra {
+        index: DerivativeIndex,
+        amount: BalanceOf<T>,
+    },
+    Unbond {
+        index: DerivativeIndex,
+        amount: BalanceOf<T>,
+    },
+    Rebond {
+        index: DerivativeIndex,
+        amount: BalanceOf<T>,
+    },
+    Nominate {
+        index: DerivativeIndex,
+        targets: Vec<T::AccountId>,
+    },
 }
 
 /// Just a Balance/BlockNumber tuple to encode when a chunk of funds will be unlocked.
@@ -339,6 +420,15 @@ impl<AccountId, Balance: BalanceT + FixedPointOperand> StakingLedger<AccountId,
     }
 }
 
+/// Where the rewards charged by commission come from
+#[derive(Copy, Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum RewardSource {
+    /// Relaychain staking rewards reported by `set_staking_ledger`
+    RelayStaking,
+    /// Credits compensating a previous slash
+    SlashRecovery,
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum UnstakeProvider {
     RelayChain = 0,
@@ -365,3 +455,12 @@ impl UnstakeProvider {
         self == &UnstakeProvider::MatchingPool
     }
 }
+
+/// Economic parameters set at once by `update_parameters`, `None` keeps the current value
+#[derive(Clone, Eq, PartialEq, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct ParametersUpdate<Balance> {
+    pub reserve_factor: Option<Ratio>,
+    pub commission_rate: Option<Rate>,
+    pub staking_ledger_cap: Option<Balance>,
+    pub incentive: Option<Balance>,
+}
@@ -25,5 +25,9 @@ sp_api::decl_runtime_apis! {
         fn get_account_liquidity(account: AccountId) -> Result<(Liquidity, Shortfall, Liquidity, Shortfall), DispatchError>;
         fn get_market_status(asset_id: CurrencyId) -> Result<(Rate, Rate, Rate, Ratio, Balance, Balance, FixedU128), DispatchError>;
         fn get_liquidation_threshold_liquidity(account: AccountId) -> Result<(Liquidity, Shortfall, Liquidity, Shortfall), DispatchError>;
+        fn get_account_health(account: AccountId) -> Result<Option<FixedU128>, DispatchError>;
+        fn get_current_borrow_balance(account: AccountId, asset_id: CurrencyId) -> Result<Balance, DispatchError>;
+        /// Raw exchange rate scaled by 1e18
+        fn get_exchange_rate(asset_id: CurrencyId) -> Result<u128, DispatchError>;
     }
 }
@@ -51,6 +51,25 @@ where
         account: AccountId,
         at: Option<BlockHash>,
     ) -> RpcResult<(Liquidity, Shortfall, Liquidity, Shortfall)>;
+    #[method(name = "loans_getAccountHealth")]
+    fn get_account_health(
+        &self,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<FixedU128>>;
+    #[method(name = "loans_getCurrentBorrowBalance")]
+    fn get_current_borrow_balance(
+        &self,
+        account: AccountId,
+        asset_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<NumberOrHex>;
+    #[method(name = "loans_getExchangeRate")]
+    fn get_exchange_rate(
+        &self,
+        asset_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<NumberOrHex>;
 }
 
 /// A struct that implements the [`LoansApi`].
@@ -159,6 +178,56 @@ where
             .map_err(runtime_error_into_rpc_error)?
             .map_err(account_liquidity_error_into_rpc_error)
     }
+
+    fn get_account_health(
+        &self,
+        account: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<FixedU128>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        api.get_account_health(&at, account)
+            .map_err(runtime_error_into_rpc_error)?
+            .map_err(account_liquidity_error_into_rpc_error)
+    }
+
+    fn get_current_borrow_balance(
+        &self,
+        account: AccountId,
+        asset_id: CurrencyId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<NumberOrHex> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        let borrow_balance = api
+            .get_current_borrow_balance(&at, account, asset_id)
+            .map_err(runtime_error_into_rpc_error)?
+            .map_err(account_liquidity_error_into_rpc_error)?;
+        try_into_rpc_balance(borrow_balance)
+    }
+
+    fn get_exchange_rate(
+        &self,
+        asset_id: CurrencyId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<NumberOrHex> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        let exchange_rate = api
+            .get_exchange_rate(&at, asset_id)
+            .map_err(runtime_error_into_rpc_error)?
+            .map_err(account_liquidity_error_into_rpc_error)?;
+        try_into_rpc_balance(exchange_rate)
+    }
 }
 
 /// Converts a runtime trap into an RPC error.
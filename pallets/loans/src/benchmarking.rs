@@ -8,7 +8,7 @@ use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whiteli
 use frame_support::assert_ok;
 use frame_system::{self, RawOrigin as SystemOrigin};
 use primitives::{
-    tokens::{CDOT_6_13, DOT, KSM, PKSM, PSKSM, PUSDT, SKSM, USDT},
+    tokens::{CDOT_6_13, DOT, KSM, PCDOT_6_13, PDOT, PKSM, PSKSM, PUSDT, SKSM, USDT},
     Balance, CurrencyId,
 };
 use rate_model::{InterestRateModel, JumpModel};
@@ -308,7 +308,8 @@ benchmarks! {
         let amount: u32 = 100_000;
     }: _(SystemOrigin::Signed(caller.clone()), USDT, amount.into())
     verify {
-        assert_last_event::<T>(Event::<T>::Deposited(caller, USDT, amount.into()).into());
+        let voucher_amount = Loans::<T>::calc_collateral_amount(amount.into(), Loans::<T>::exchange_rate(USDT)).unwrap();
+        assert_last_event::<T>(Event::<T>::Deposited(caller, USDT, amount.into(), voucher_amount).into());
     }
 
     borrow {
@@ -335,7 +336,8 @@ benchmarks! {
         assert_ok!(Loans::<T>::mint(SystemOrigin::Signed(caller.clone()).into(), USDT, deposit_amount.into()));
     }: _(SystemOrigin::Signed(caller.clone()), USDT, redeem_amount.into())
     verify {
-        assert_last_event::<T>(Event::<T>::Redeemed(caller, USDT, redeem_amount.into()).into());
+        let voucher_amount = Loans::<T>::calc_collateral_amount(redeem_amount.into(), Loans::<T>::exchange_rate(USDT)).unwrap();
+        assert_last_event::<T>(Event::<T>::Redeemed(caller, USDT, redeem_amount.into(), voucher_amount).into());
     }
 
     redeem_all {
@@ -345,9 +347,10 @@ benchmarks! {
         assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
         assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), USDT));
         assert_ok!(Loans::<T>::mint(SystemOrigin::Signed(caller.clone()).into(), USDT, deposit_amount.into()));
+        let voucher_amount = Loans::<T>::account_deposits(USDT, &caller).voucher_balance;
     }: _(SystemOrigin::Signed(caller.clone()), USDT)
     verify {
-        assert_last_event::<T>(Event::<T>::Redeemed(caller, USDT, deposit_amount.into()).into());
+        assert_last_event::<T>(Event::<T>::Redeemed(caller, USDT, deposit_amount.into(), voucher_amount).into());
     }
 
     repay_borrow {
@@ -447,6 +450,25 @@ benchmarks! {
     verify {
         assert_last_event::<T>(Event::<T>::LiquidationFreeCollateralsUpdated(vec![CDOT_6_13]).into());
     }
+
+    schedule_collateral_factor {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), KSM, pending_market_mock::<T>(PKSM)));
+    }: _(SystemOrigin::Root, KSM, Ratio::from_percent(40))
+    verify {
+        let effective_block = frame_system::Pallet::<T>::block_number() + T::CollateralFactorDelay::get();
+        assert_last_event::<T>(Event::<T>::CollateralFactorScheduled(KSM, Ratio::from_percent(40), effective_block).into());
+    }
+
+    accrue_all_interest {
+        let m in 1 .. 5;
+        let caller: T::AccountId = whitelisted_caller();
+        transfer_initial_balance::<T>(caller.clone());
+        let markets = [(KSM, PKSM), (SKSM, PSKSM), (USDT, PUSDT), (DOT, PDOT), (CDOT_6_13, PCDOT_6_13)];
+        for (asset_id, ptoken_id) in markets.into_iter().take(m as usize) {
+            assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), asset_id, pending_market_mock::<T>(ptoken_id)));
+            assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), asset_id));
+        }
+    }: _(SystemOrigin::Signed(caller), m)
 }
 
 impl_benchmark_test_suite!(Loans, crate::mock::new_test_ext(), crate::mock::Test);
@@ -30,6 +30,7 @@ use frame_support::{
     log,
     pallet_prelude::*,
     require_transactional,
+    storage::{with_transaction, TransactionOutcome},
     traits::{
         tokens::fungibles::{Inspect, Mutate, Transfer},
         UnixTime,
@@ -138,6 +139,11 @@ pub mod pallet {
 
         #[pallet::constant]
         type LiquidationFreeAssetId: Get<AssetIdOf<Self>>;
+
+        /// Number of blocks a scheduled collateral factor change waits before
+        /// being applied.
+        #[pallet::constant]
+        type CollateralFactorDelay: Get<Self::BlockNumber>;
     }
 
     #[pallet::error]
@@ -200,6 +206,8 @@ pub mod pallet {
         CodecError,
         /// Collateral is reserved and cannot be liquidated
         CollateralReserved,
+        /// The number of active markets exceeds the given upper bound
+        TooManyMarkets,
     }
 
     #[pallet::event]
@@ -212,11 +220,11 @@ pub mod pallet {
         /// [sender, asset_id]
         CollateralAssetRemoved(T::AccountId, AssetIdOf<T>),
         /// Event emitted when assets are deposited
-        /// [sender, asset_id, amount]
-        Deposited(T::AccountId, AssetIdOf<T>, BalanceOf<T>),
+        /// [sender, asset_id, amount, voucher_amount]
+        Deposited(T::AccountId, AssetIdOf<T>, BalanceOf<T>, BalanceOf<T>),
         /// Event emitted when assets are redeemed
-        /// [sender, asset_id, amount]
-        Redeemed(T::AccountId, AssetIdOf<T>, BalanceOf<T>),
+        /// [sender, asset_id, amount, voucher_amount]
+        Redeemed(T::AccountId, AssetIdOf<T>, BalanceOf<T>, BalanceOf<T>),
         /// Event emitted when cash is borrowed
         /// [sender, asset_id, amount]
         Borrowed(T::AccountId, AssetIdOf<T>, BalanceOf<T>),
@@ -265,6 +273,60 @@ pub mod pallet {
         IncentiveReservesReduced(T::AccountId, AssetIdOf<T>, BalanceOf<T>),
         /// Liquidation free collaterals has been updated
         LiquidationFreeCollateralsUpdated(Vec<AssetIdOf<T>>),
+        /// Collateral factor change is scheduled
+        /// [asset_id, new_collateral_factor, effective_block]
+        CollateralFactorScheduled(AssetIdOf<T>, Ratio, T::BlockNumber),
+        /// Scheduled collateral factor change is applied
+        /// [asset_id, new_collateral_factor]
+        CollateralFactorApplied(AssetIdOf<T>, Ratio),
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_initialize(n: T::BlockNumber) -> Weight {
+            let due = CollateralFactorSchedule::<T>::take(n);
+            let mut reads: u64 = 1;
+            let mut writes: u64 = if due.is_empty() { 0 } else { 1 };
+
+            for asset_id in due {
+                reads += 1;
+                // a later schedule of the same market supersedes this one
+                let collateral_factor = match PendingCollateralFactor::<T>::get(asset_id) {
+                    Some((collateral_factor, effective_block)) if effective_block == n => {
+                        collateral_factor
+                    }
+                    _ => continue,
+                };
+                PendingCollateralFactor::<T>::remove(asset_id);
+                reads += 1;
+                writes += 1;
+                match Self::market(asset_id) {
+                    // The liquidation threshold may have been lowered since scheduling
+                    Ok(market) if collateral_factor <= market.liquidation_threshold => {
+                        Markets::<T>::insert(
+                            asset_id,
+                            Market {
+                                collateral_factor,
+                                ..market
+                            },
+                        );
+                        writes += 1;
+                        Self::deposit_event(Event::<T>::CollateralFactorApplied(
+                            asset_id,
+                            collateral_factor,
+                        ));
+                    }
+                    _ => log::error!(
+                        target: "loans::on_initialize",
+                        "discard collateral factor change, asset_id: {:?}, collateral_factor: {:?}",
+                        asset_id,
+                        collateral_factor,
+                    ),
+                }
+            }
+
+            T::DbWeight::get().reads_writes(reads, writes)
+        }
     }
 
     /// The timestamp of the last calculation of accrued interest
@@ -278,6 +340,20 @@ pub mod pallet {
     #[pallet::getter(fn liquidation_free_collaterals)]
     pub type LiquidationFreeCollaterals<T: Config> = StorageValue<_, Vec<AssetIdOf<T>>, ValueQuery>;
 
+    /// Collateral factor changes waiting for their effective block.
+    /// CurrencyId -> (new_collateral_factor, effective_block)
+    #[pallet::storage]
+    #[pallet::getter(fn pending_collateral_factor)]
+    pub type PendingCollateralFactor<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, (Ratio, T::BlockNumber), OptionQuery>;
+
+    /// Markets whose pending collateral factor becomes effective at a block, so that
+    /// `on_initialize` only visits the due ones.
+    /// BlockNumber -> Vec<CurrencyId>
+    #[pallet::storage]
+    pub type CollateralFactorSchedule<T: Config> =
+        StorageMap<_, Twox64Concat, T::BlockNumber, Vec<AssetIdOf<T>>, ValueQuery>;
+
     /// Total number of collateral tokens in circulation
     /// CollateralType -> Balance
     #[pallet::storage]
@@ -591,7 +667,8 @@ pub mod pallet {
         /// Updates a stored market. Returns `Err` if the market currency does not exist.
         ///
         /// - `asset_id`: market related currency
-        /// - `collateral_factor`: the collateral utilization ratio
+        /// - `collateral_factor`: the collateral utilization ratio, a change is scheduled
+        /// through `schedule_collateral_factor` rather than applied immediately
         /// - `reserve_factor`: fraction of interest currently set aside for reserves
         /// - `close_factor`: maximum liquidation ratio at one time
         /// - `liquidate_incentive`: liquidation incentive ratio
@@ -630,8 +707,10 @@ pub mod pallet {
                 collateral_factor >= Ratio::zero() && collateral_factor < Ratio::one(),
                 Error::<T>::InvalidFactor
             );
+            // the stored collateral factor stays effective until the scheduled one applies
             ensure!(
-                liquidation_threshold >= collateral_factor && liquidation_threshold < Ratio::one(),
+                liquidation_threshold >= collateral_factor.max(market.collateral_factor)
+                    && liquidation_threshold < Ratio::one(),
                 Error::<T>::InvalidFactor
             );
             ensure!(
@@ -639,13 +718,14 @@ pub mod pallet {
                 Error::<T>::InvalidFactor
             );
             ensure!(supply_cap > Zero::zero(), Error::<T>::InvalidSupplyCap);
+            let collateral_factor_changed = collateral_factor != market.collateral_factor;
 
             let market = Self::mutate_market(asset_id, |stored_market| {
                 *stored_market = Market {
                     state: stored_market.state,
                     ptoken_id: stored_market.ptoken_id,
                     rate_model: stored_market.rate_model,
-                    collateral_factor,
+                    collateral_factor: stored_market.collateral_factor,
                     liquidation_threshold,
                     reserve_factor,
                     close_factor,
@@ -657,6 +737,9 @@ pub mod pallet {
                 stored_market.clone()
             })?;
             Self::deposit_event(Event::<T>::UpdatedMarket(asset_id, market));
+            if collateral_factor_changed {
+                Self::do_schedule_collateral_factor(asset_id, collateral_factor);
+            }
 
             Ok(().into())
         }
@@ -876,13 +959,7 @@ pub mod pallet {
             asset_id: AssetIdOf<T>,
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
-            Self::ensure_active_market(asset_id)?;
-            Self::accrue_interest(asset_id)?;
-            let exchange_rate = Self::exchange_rate_stored(asset_id)?;
-            Self::update_earned_stored(&who, asset_id, exchange_rate)?;
-            let deposits = AccountDeposits::<T>::get(asset_id, &who);
-            let redeem_amount = Self::do_redeem_voucher(&who, asset_id, deposits.voucher_balance)?;
-            Self::deposit_event(Event::<T>::Redeemed(who, asset_id, redeem_amount));
+            Self::do_redeem_all(&who, asset_id)?;
 
             Ok(().into())
         }
@@ -1136,6 +1213,69 @@ pub mod pallet {
             Self::deposit_event(Event::<T>::LiquidationFreeCollateralsUpdated(collaterals));
             Ok(().into())
         }
+
+        /// Schedules a collateral factor change which is applied automatically
+        /// `CollateralFactorDelay` blocks later, giving borrowers time to top up
+        /// their collateral. A new schedule replaces the pending one.
+        ///
+        /// - `asset_id`: market related currency
+        /// - `collateral_factor`: the new collateral utilization ratio
+        #[pallet::call_index(22)]
+        #[pallet::weight(T::WeightInfo::schedule_collateral_factor())]
+        #[transactional]
+        pub fn schedule_collateral_factor(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            collateral_factor: Ratio,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+
+            let market = Self::market(asset_id)?;
+            ensure!(
+                collateral_factor < Ratio::one()
+                    && collateral_factor <= market.liquidation_threshold,
+                Error::<T>::InvalidFactor
+            );
+            Self::do_schedule_collateral_factor(asset_id, collateral_factor);
+
+            Ok(().into())
+        }
+
+        /// Accrues interest for all active markets. A market whose accrual fails is
+        /// rolled back and skipped without affecting the others.
+        ///
+        /// - `max_markets`: upper bound of the number of active markets, used to
+        /// weigh the call before dispatch
+        #[pallet::call_index(23)]
+        #[pallet::weight(T::WeightInfo::accrue_all_interest(*max_markets))]
+        #[transactional]
+        pub fn accrue_all_interest(
+            origin: OriginFor<T>,
+            #[pallet::compact] max_markets: u32,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+            let markets: Vec<AssetIdOf<T>> = Self::active_markets()
+                .map(|(asset_id, _)| asset_id)
+                .collect();
+            let market_count = markets.len() as u32;
+            ensure!(market_count <= max_markets, Error::<T>::TooManyMarkets);
+
+            for asset_id in markets {
+                if let Err(err) = with_transaction(|| match Self::accrue_interest(asset_id) {
+                    Ok(()) => TransactionOutcome::Commit(Ok(())),
+                    Err(err) => TransactionOutcome::Rollback(Err(err)),
+                }) {
+                    log::error!(
+                        target: "loans::accrue_all_interest",
+                        "accrue interest failed, asset_id: {:?}, err: {:?}",
+                        asset_id,
+                        err,
+                    );
+                }
+            }
+
+            Ok(Some(T::WeightInfo::accrue_all_interest(market_count)).into())
+        }
     }
 }
 
@@ -1274,6 +1414,32 @@ impl<T: Config> Pallet<T> {
         }
     }
 
+    /// Returns the account's health factor, the sum of its collateral value weighted
+    /// by the collateral factors divided by its total borrowed value. A value below
+    /// one means the account can be liquidated.
+    ///
+    /// Returns `None` if the account has no borrows.
+    pub fn get_account_health(account: &T::AccountId) -> Result<Option<FixedU128>, DispatchError> {
+        let total_borrow_value = Self::total_borrowed_value(account)?;
+        if total_borrow_value.is_zero() {
+            return Ok(None);
+        }
+        let total_collateral_value = Self::total_collateral_value(account)?;
+
+        log::trace!(
+            target: "loans::get_account_health",
+            "account: {:?}, total_borrow_value: {:?}, total_collateral_value: {:?}",
+            account,
+            total_borrow_value.into_inner(),
+            total_collateral_value.into_inner(),
+        );
+
+        total_collateral_value
+            .checked_div(&total_borrow_value)
+            .map(Some)
+            .ok_or_else(|| ArithmeticError::Overflow.into())
+    }
+
     fn total_borrowed_value(borrower: &T::AccountId) -> Result<FixedU128, DispatchError> {
         let mut total_borrow_value: FixedU128 = FixedU128::zero();
         for (asset_id, _) in Self::active_markets() {
@@ -1455,6 +1621,44 @@ impl<T: Config> Pallet<T> {
         Ok(redeem_amount)
     }
 
+    /// Redeems the whole voucher balance of `supplier` rather than an underlying
+    /// amount, so the deposit entry is cleared without leaving rounding dust.
+    /// Returns the underlying amount redeemed.
+    pub fn do_redeem_all(
+        supplier: &AccountIdOf<T>,
+        asset_id: AssetIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        Self::ensure_active_market(asset_id)?;
+        Self::accrue_interest(asset_id)?;
+        let exchange_rate = Self::exchange_rate_stored(asset_id)?;
+        Self::update_earned_stored(supplier, asset_id, exchange_rate)?;
+        let voucher_amount = AccountDeposits::<T>::get(asset_id, supplier).voucher_balance;
+        let redeem_amount = Self::do_redeem_voucher(supplier, asset_id, voucher_amount)?;
+        Self::deposit_event(Event::<T>::Redeemed(
+            supplier.clone(),
+            asset_id,
+            redeem_amount,
+            voucher_amount,
+        ));
+        Ok(redeem_amount)
+    }
+
+    /// Replaces the pending collateral factor change of `asset_id`, effective
+    /// `CollateralFactorDelay` blocks from now
+    fn do_schedule_collateral_factor(asset_id: AssetIdOf<T>, collateral_factor: Ratio) {
+        // the current block is already initialized, so the earliest is the next one
+        let effective_block = frame_system::Pallet::<T>::block_number()
+            .saturating_add(T::CollateralFactorDelay::get().max(One::one()));
+        PendingCollateralFactor::<T>::insert(asset_id, (collateral_factor, effective_block));
+        CollateralFactorSchedule::<T>::append(effective_block, asset_id);
+
+        Self::deposit_event(Event::<T>::CollateralFactorScheduled(
+            asset_id,
+            collateral_factor,
+            effective_block,
+        ));
+    }
+
     /// Borrower shouldn't borrow more than his total collateral value
     fn borrow_allowed(
         asset_id: AssetIdOf<T>,
@@ -1515,6 +1719,31 @@ impl<T: Config> Pallet<T> {
     pub fn current_borrow_balance(
         who: &T::AccountId,
         asset_id: AssetIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        Self::borrow_balance_at_index(who, asset_id, Self::borrow_index(asset_id))
+    }
+
+    /// Borrow balance of `who` including the interest accrued since the last
+    /// checkpoint, calculated without writing to storage.
+    pub fn borrow_balance_with_interest(
+        who: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let (_, _, _, _, _, _, borrow_index) = Self::get_market_status(asset_id)?;
+        Self::borrow_balance_at_index(who, asset_id, borrow_index)
+    }
+
+    /// Exchange rate of `asset_id` including the interest accrued since the
+    /// last checkpoint, calculated without writing to storage.
+    pub fn exchange_rate_with_interest(asset_id: AssetIdOf<T>) -> Result<Rate, DispatchError> {
+        let (_, _, exchange_rate, _, _, _, _) = Self::get_market_status(asset_id)?;
+        Ok(exchange_rate)
+    }
+
+    fn borrow_balance_at_index(
+        who: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+        borrow_index: Rate,
     ) -> Result<BalanceOf<T>, DispatchError> {
         let snapshot: BorrowSnapshot<BalanceOf<T>> = Self::account_borrows(asset_id, who);
         if snapshot.principal.is_zero() || snapshot.borrow_index.is_zero() {
@@ -1522,7 +1751,7 @@ impl<T: Config> Pallet<T> {
         }
         // Calculate new borrow balance using the interest index:
         // recent_borrow_balance = snapshot.principal * borrow_index / snapshot.borrow_index
-        let recent_borrow_balance = Self::borrow_index(asset_id)
+        let recent_borrow_balance = borrow_index
             .checked_div(&snapshot.borrow_index)
             .and_then(|r| r.checked_mul_int(snapshot.principal))
             .ok_or(ArithmeticError::Overflow)?;
@@ -2054,7 +2283,12 @@ impl<T: Config> LoansTrait<AssetIdOf<T>, AccountIdOf<T>, BalanceOf<T>> for Palle
             *total_balance = new_balance;
             Ok(())
         })?;
-        Self::deposit_event(Event::<T>::Deposited(supplier.clone(), asset_id, amount));
+        Self::deposit_event(Event::<T>::Deposited(
+            supplier.clone(),
+            asset_id,
+            amount,
+            voucher_amount,
+        ));
         Ok(())
     }
 
@@ -2169,6 +2403,7 @@ impl<T: Config> LoansTrait<AssetIdOf<T>, AccountIdOf<T>, BalanceOf<T>> for Palle
             supplier.clone(),
             asset_id,
             redeem_amount,
+            voucher_amount,
         ));
         Ok(())
     }
@@ -344,6 +344,7 @@ parameter_types! {
     pub const LoansPalletId: PalletId = PalletId(*b"par/loan");
     pub const RewardAssetId: CurrencyId = HKO;
     pub const LiquidationFreeAssetId: CurrencyId = DOT;
+    pub const CollateralFactorDelay: BlockNumber = 10;
 }
 
 impl Config for Test {
@@ -357,6 +358,7 @@ impl Config for Test {
     type Assets = CurrencyAdapter;
     type RewardAssetId = RewardAssetId;
     type LiquidationFreeAssetId = LiquidationFreeAssetId;
+    type CollateralFactorDelay = CollateralFactorDelay;
 }
 
 parameter_types! {
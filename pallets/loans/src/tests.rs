@@ -258,8 +258,22 @@ fn lf_redeem_allowed_works() {
 #[test]
 fn redeem_works() {
     new_test_ext().execute_with(|| {
+        System::set_block_number(1);
         assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(100)));
+        System::assert_last_event(RuntimeEvent::Loans(crate::Event::Deposited(
+            ALICE,
+            DOT,
+            unit(100),
+            unit(5000),
+        )));
         assert_ok!(Loans::redeem(RuntimeOrigin::signed(ALICE), DOT, unit(20)));
+        // underlying and voucher amounts at the 0.02 initial exchange rate
+        System::assert_last_event(RuntimeEvent::Loans(crate::Event::Redeemed(
+            ALICE,
+            DOT,
+            unit(20),
+            unit(1000),
+        )));
 
         // DOT collateral: deposit - redeem = 100 - 20 = 80
         // DOT: cash - deposit + redeem = 1000 - 100 + 20 = 920
@@ -272,6 +286,31 @@ fn redeem_works() {
     })
 }
 
+#[test]
+fn mint_and_redeem_six_decimal_asset_has_no_rounding_drift() {
+    new_test_ext().execute_with(|| {
+        // USDT has 6 decimals, the exchange rate only scales the voucher amount
+        // so no precision is lost regardless of the asset decimal.
+        let usdt_balance = <Test as Config>::Assets::balance(USDT, &ALICE);
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), USDT, 1_234_567));
+        assert_eq!(
+            Loans::account_deposits(USDT, ALICE).voucher_balance,
+            61_728_350
+        );
+        assert_eq!(
+            <Test as Config>::Assets::balance(USDT, &ALICE),
+            usdt_balance - 1_234_567
+        );
+
+        assert_ok!(Loans::redeem_all(RuntimeOrigin::signed(ALICE), USDT));
+        assert_eq!(Loans::account_deposits(USDT, ALICE).voucher_balance, 0);
+        assert_eq!(
+            <Test as Config>::Assets::balance(USDT, &ALICE),
+            usdt_balance
+        );
+    })
+}
+
 #[test]
 fn redeem_fails() {
     new_test_ext().execute_with(|| {
@@ -303,6 +342,36 @@ fn redeem_fails_when_insufficient_liquidity() {
             Loans::redeem(RuntimeOrigin::signed(BOB), DOT, 151),
             Error::<Test>::InsufficientCash
         );
+        // cash is checked before the deposit and total supply are updated
+        assert_noop!(
+            Loans::redeem_all(RuntimeOrigin::signed(BOB), DOT),
+            Error::<Test>::InsufficientCash
+        );
+    })
+}
+
+#[test]
+fn redeem_fails_when_collateral_backs_borrow() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), DOT, 200));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), KSM, 200));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            true
+        ));
+        // Borrow 50 DOT locks 100 KSM for collateral_factor is 50%
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, 50));
+
+        assert_noop!(
+            Loans::redeem(RuntimeOrigin::signed(ALICE), KSM, 101),
+            Error::<Test>::InsufficientLiquidity
+        );
+        assert_noop!(
+            Loans::redeem_all(RuntimeOrigin::signed(ALICE), KSM),
+            Error::<Test>::InsufficientLiquidity
+        );
+        assert_ok!(Loans::redeem(RuntimeOrigin::signed(ALICE), KSM, 100));
     })
 }
 
@@ -368,6 +437,34 @@ fn redeem_all_works() {
     })
 }
 
+#[test]
+fn redeem_all_leaves_no_dust() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), DOT, unit(20)));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(10)));
+        TimestampPallet::set_timestamp(12000);
+
+        // exchange rate is no longer a round number after interest accrues
+        let bob_vouchers = Loans::account_deposits(DOT, BOB).voucher_balance;
+        let total_supply = Loans::total_supply(DOT);
+        assert_ok!(Loans::redeem_all(RuntimeOrigin::signed(BOB), DOT));
+        assert_ne!(
+            Loans::exchange_rate(DOT),
+            Rate::saturating_from_rational(2, 100)
+        );
+
+        assert_eq!(Loans::account_deposits(DOT, BOB).voucher_balance, 0);
+        assert!(!AccountDeposits::<Test>::contains_key(DOT, &BOB));
+        assert_eq!(Loans::total_supply(DOT), total_supply - bob_vouchers);
+    })
+}
+
 #[test]
 fn borrow_allowed_works() {
     new_test_ext().execute_with(|| {
@@ -461,6 +558,30 @@ fn get_account_liquidation_threshold_liquidity_works() {
     })
 }
 
+#[test]
+fn get_account_health_works() {
+    new_test_ext().execute_with(|| {
+        Loans::mint(RuntimeOrigin::signed(BOB), DOT, unit(200)).unwrap();
+        assert_eq!(Loans::get_account_health(&ALICE), Ok(None));
+
+        Loans::mint(RuntimeOrigin::signed(ALICE), USDT, unit(200)).unwrap();
+        Loans::collateral_asset(RuntimeOrigin::signed(ALICE), USDT, true).unwrap();
+        assert_eq!(Loans::get_account_health(&ALICE), Ok(None));
+
+        Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(50)).unwrap();
+        assert_eq!(
+            Loans::get_account_health(&ALICE),
+            Ok(Some(FixedU128::saturating_from_integer(2)))
+        );
+
+        MockPriceFeeder::set_price(DOT, 4.into());
+        assert_eq!(
+            Loans::get_account_health(&ALICE),
+            Ok(Some(FixedU128::saturating_from_rational(1, 2)))
+        );
+    })
+}
+
 #[test]
 fn lf_borrow_allowed_works() {
     new_test_ext().execute_with(|| {
@@ -1,6 +1,6 @@
 use crate::tests::Loans;
-use crate::{mock::*, Markets};
-use frame_support::assert_ok;
+use crate::{mock::*, Error, Markets};
+use frame_support::{assert_noop, assert_ok};
 use primitives::{Rate, Ratio, SECONDS_PER_YEAR};
 use sp_runtime::{
     traits::{CheckedDiv, One, Saturating},
@@ -180,6 +180,130 @@ fn accrue_interest_works_after_mint() {
     })
 }
 
+#[test]
+fn borrow_balance_with_interest_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(100)));
+        TimestampPallet::set_timestamp(12000);
+
+        // pending interest is included but not checkpointed
+        let borrow_balance = Loans::borrow_balance_with_interest(&ALICE, DOT).unwrap();
+        assert!(borrow_balance > unit(100));
+        assert_eq!(Loans::borrow_index(DOT), Rate::one());
+        assert_eq!(Loans::current_borrow_balance(&ALICE, DOT), Ok(unit(100)));
+
+        assert_ok!(Loans::accrue_interest(DOT));
+        assert_eq!(
+            Loans::current_borrow_balance(&ALICE, DOT),
+            Ok(borrow_balance)
+        );
+    })
+}
+
+#[test]
+fn exchange_rate_with_interest_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(100)));
+        let exchange_rate = Loans::exchange_rate(DOT);
+        assert_eq!(Loans::exchange_rate_with_interest(DOT), Ok(exchange_rate));
+        TimestampPallet::set_timestamp(12000);
+
+        // pending interest is included but not checkpointed
+        let exchange_rate_with_interest = Loans::exchange_rate_with_interest(DOT).unwrap();
+        assert!(exchange_rate_with_interest > exchange_rate);
+        assert_eq!(Loans::exchange_rate(DOT), exchange_rate);
+
+        assert_ok!(Loans::accrue_interest(DOT));
+        assert_eq!(Loans::exchange_rate(DOT), exchange_rate_with_interest);
+    })
+}
+
+#[test]
+fn accrue_interest_should_happen_once_per_block() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(100)));
+        TimestampPallet::set_timestamp(12000);
+        let borrow_balance = Loans::borrow_balance_with_interest(&ALICE, DOT).unwrap();
+
+        // the repayment is applied to the interest-inclusive balance
+        assert_ok!(Loans::repay_borrow(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            unit(10)
+        ));
+        assert_eq!(Loans::last_accrued_interest_time(DOT), 12);
+        assert_eq!(
+            Loans::borrow_index(DOT),
+            Rate::from_inner(1000000013318112633),
+        );
+        assert_eq!(
+            Loans::current_borrow_balance(&ALICE, DOT),
+            Ok(borrow_balance - unit(10))
+        );
+
+        // a second action in the same block doesn't accrue again
+        assert_ok!(Loans::repay_borrow(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            unit(10)
+        ));
+        assert_eq!(
+            Loans::borrow_index(DOT),
+            Rate::from_inner(1000000013318112633),
+        );
+        assert_eq!(
+            Loans::current_borrow_balance(&ALICE, DOT),
+            Ok(borrow_balance - unit(20))
+        );
+    })
+}
+
+#[test]
+fn accrue_all_interest_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(100)));
+        assert_eq!(Loans::last_accrued_interest_time(KSM), 0);
+        TimestampPallet::set_timestamp(12000);
+
+        // 5 active markets in mock
+        assert_noop!(
+            Loans::accrue_all_interest(RuntimeOrigin::signed(BOB), 4),
+            Error::<Test>::TooManyMarkets
+        );
+        assert_ok!(Loans::accrue_all_interest(RuntimeOrigin::signed(BOB), 5));
+        assert_eq!(
+            Loans::borrow_index(DOT),
+            Rate::from_inner(1000000013318112633),
+        );
+        assert_eq!(Loans::last_accrued_interest_time(DOT), 12);
+        assert_eq!(Loans::last_accrued_interest_time(KSM), 12);
+    })
+}
+
 #[test]
 fn accrue_interest_works_after_borrow() {
     new_test_ext().execute_with(|| {
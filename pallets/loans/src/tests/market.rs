@@ -1,9 +1,9 @@
 use crate::{
     mock::{
-        market_mock, new_test_ext, Loans, RuntimeOrigin, Test, ACTIVE_MARKET_MOCK, ALICE, DOT,
-        MARKET_MOCK, PDOT, PUSDT, SDOT,
+        _run_to_block, market_mock, new_test_ext, Loans, RuntimeEvent, RuntimeOrigin, System, Test,
+        ACTIVE_MARKET_MOCK, ALICE, DOT, MARKET_MOCK, PDOT, PUSDT, SDOT,
     },
-    Error, InterestRateModel, MarketState,
+    Error, Event, InterestRateModel, MarketState,
 };
 use frame_support::{assert_noop, assert_ok, error::BadOrigin};
 use primitives::{Rate, Ratio};
@@ -332,6 +332,122 @@ fn update_market_should_not_work_if_with_invalid_params() {
     })
 }
 
+#[test]
+fn schedule_collateral_factor_applies_after_delay() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Loans::schedule_collateral_factor(
+            RuntimeOrigin::root(),
+            DOT,
+            Ratio::from_percent(30),
+        ));
+        assert_eq!(
+            Loans::pending_collateral_factor(DOT),
+            Some((Ratio::from_percent(30), 11))
+        );
+        System::assert_last_event(RuntimeEvent::Loans(Event::CollateralFactorScheduled(
+            DOT,
+            Ratio::from_percent(30),
+            11,
+        )));
+
+        _run_to_block(10);
+        assert_eq!(
+            Loans::market(DOT).unwrap().collateral_factor,
+            Ratio::from_percent(50)
+        );
+
+        _run_to_block(11);
+        assert_eq!(
+            Loans::market(DOT).unwrap().collateral_factor,
+            Ratio::from_percent(30)
+        );
+        assert_eq!(Loans::pending_collateral_factor(DOT), None);
+        System::assert_has_event(RuntimeEvent::Loans(Event::CollateralFactorApplied(
+            DOT,
+            Ratio::from_percent(30),
+        )));
+    })
+}
+
+#[test]
+fn update_market_should_schedule_collateral_factor() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Loans::update_market(
+            RuntimeOrigin::root(),
+            DOT,
+            Some(Ratio::from_percent(30)),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ));
+        assert_eq!(
+            Loans::market(DOT).unwrap().collateral_factor,
+            Ratio::from_percent(50)
+        );
+        assert_eq!(
+            Loans::pending_collateral_factor(DOT),
+            Some((Ratio::from_percent(30), 11))
+        );
+
+        // a later schedule supersedes the pending one
+        System::set_block_number(5);
+        assert_ok!(Loans::schedule_collateral_factor(
+            RuntimeOrigin::root(),
+            DOT,
+            Ratio::from_percent(40),
+        ));
+        _run_to_block(11);
+        assert_eq!(
+            Loans::market(DOT).unwrap().collateral_factor,
+            Ratio::from_percent(50)
+        );
+
+        _run_to_block(15);
+        assert_eq!(
+            Loans::market(DOT).unwrap().collateral_factor,
+            Ratio::from_percent(40)
+        );
+        assert_eq!(Loans::pending_collateral_factor(DOT), None);
+    })
+}
+
+#[test]
+fn schedule_collateral_factor_should_not_work_if_with_invalid_params() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Loans::schedule_collateral_factor(
+                RuntimeOrigin::signed(ALICE),
+                DOT,
+                Ratio::from_percent(30),
+            ),
+            BadOrigin
+        );
+        assert_noop!(
+            Loans::schedule_collateral_factor(
+                RuntimeOrigin::root(),
+                SDOT,
+                Ratio::from_percent(30),
+            ),
+            Error::<Test>::MarketDoesNotExist
+        );
+        // collateral_factor is greater than liquidation_threshold
+        assert_noop!(
+            Loans::schedule_collateral_factor(
+                RuntimeOrigin::root(),
+                DOT,
+                Ratio::from_percent(60),
+            ),
+            Error::<Test>::InvalidFactor
+        );
+    })
+}
+
 #[test]
 fn update_rate_model_works() {
     new_test_ext().execute_with(|| {
@@ -66,6 +66,8 @@ pub trait WeightInfo {
 	fn add_reserves() -> Weight;
 	fn reduce_reserves() -> Weight;
 	fn update_liquidation_free_collateral() -> Weight;
+	fn schedule_collateral_factor() -> Weight;
+	fn accrue_all_interest(m: u32, ) -> Weight;
 }
 
 /// Weights for pallet_loans using the Substrate node and recommended hardware.
@@ -359,6 +361,37 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(2 as u64))
 			.saturating_add(T::DbWeight::get().writes(2 as u64))
 	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans PendingCollateralFactor (r:0 w:1)
+	// Storage: Loans CollateralFactorSchedule (r:1 w:1)
+	fn schedule_collateral_factor() -> Weight {
+		Weight::from_ref_time(39_217_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:6 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:5 w:5)
+	// Storage: Loans TotalSupply (r:5 w:0)
+	// Storage: Assets Account (r:5 w:0)
+	// Storage: Loans TotalBorrows (r:5 w:5)
+	// Storage: Loans TotalReserves (r:5 w:5)
+	// Storage: Loans BorrowIndex (r:5 w:5)
+	// Storage: Loans UtilizationRatio (r:0 w:5)
+	// Storage: Loans BorrowRate (r:0 w:5)
+	// Storage: Loans SupplyRate (r:0 w:5)
+	// Storage: Loans ExchangeRate (r:0 w:5)
+	fn accrue_all_interest(m: u32, ) -> Weight {
+		Weight::from_ref_time(28_561_000 as u64)
+			// Standard Error: 41_000
+			.saturating_add(Weight::from_ref_time(52_932_000 as u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().reads((6 as u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().writes((8 as u64).saturating_mul(m as u64)))
+	}
 }
 
 // For backwards compatibility and tests
@@ -651,4 +684,35 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(2 as u64))
 			.saturating_add(RocksDbWeight::get().writes(2 as u64))
 	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans PendingCollateralFactor (r:0 w:1)
+	// Storage: Loans CollateralFactorSchedule (r:1 w:1)
+	fn schedule_collateral_factor() -> Weight {
+		Weight::from_ref_time(39_217_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:6 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:5 w:5)
+	// Storage: Loans TotalSupply (r:5 w:0)
+	// Storage: Assets Account (r:5 w:0)
+	// Storage: Loans TotalBorrows (r:5 w:5)
+	// Storage: Loans TotalReserves (r:5 w:5)
+	// Storage: Loans BorrowIndex (r:5 w:5)
+	// Storage: Loans UtilizationRatio (r:0 w:5)
+	// Storage: Loans BorrowRate (r:0 w:5)
+	// Storage: Loans SupplyRate (r:0 w:5)
+	// Storage: Loans ExchangeRate (r:0 w:5)
+	fn accrue_all_interest(m: u32, ) -> Weight {
+		Weight::from_ref_time(28_561_000 as u64)
+			// Standard Error: 41_000
+			.saturating_add(Weight::from_ref_time(52_932_000 as u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().reads((6 as u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes((8 as u64).saturating_mul(m as u64)))
+	}
 }
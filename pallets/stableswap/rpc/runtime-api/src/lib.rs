@@ -0,0 +1,45 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use primitives::{CurrencyId, PoolInfo};
+use sp_runtime::DispatchError;
+
+sp_api::decl_runtime_apis! {
+    pub trait StableSwapApi<Balance, BlockNumber> where
+        Balance: Codec,
+        BlockNumber: Codec, {
+        /// [price_0_cumulative, price_1_cumulative, block_timestamp]
+        fn get_price_cumulative(
+            asset_0: CurrencyId,
+            asset_1: CurrencyId,
+        ) -> Result<(Balance, Balance, BlockNumber), DispatchError>;
+
+        /// LP tokens minted for depositing `amounts`, or burned for withdrawing them
+        fn calc_token_amount(
+            asset_0: CurrencyId,
+            asset_1: CurrencyId,
+            amounts: (Balance, Balance),
+            is_deposit: bool,
+        ) -> Option<Balance>;
+
+        /// Reserves, LP supply, amplification and fees of the pool
+        fn pool_info(
+            asset_0: CurrencyId,
+            asset_1: CurrencyId,
+        ) -> Option<PoolInfo<CurrencyId, Balance>>;
+    }
+}
@@ -0,0 +1,106 @@
+//! Stableswap pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+use crate::Pallet as StableSwap;
+
+use frame_benchmarking::{
+    benchmarks_instance_pallet, impl_benchmark_test_suite, whitelisted_caller,
+};
+use frame_support::{assert_ok, traits::EnsureOrigin};
+use frame_system::{self, RawOrigin as SystemOrigin};
+use primitives::{
+    tokens::{self, *},
+    CurrencyId,
+};
+use sp_runtime::traits::StaticLookup;
+use sp_std::prelude::*;
+
+const BASE_ASSET: CurrencyId = SDOT;
+const QUOTE_ASSET: CurrencyId = DOT;
+const INITIAL_AMOUNT: u128 = 1_000_000_000_000_000;
+const LIQUIDITY_AMOUNT: u128 = 1_000_000_000_000;
+const SWAP_AMOUNT: u128 = 1_000_000_000;
+const ASSET_ID: u32 = 10;
+
+fn assert_last_event<T: Config<I>, I: 'static>(generic_event: <T as Config<I>>::RuntimeEvent) {
+    frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+fn initial_set_up<
+    T: Config<I> + pallet_assets::Config<AssetId = CurrencyId, Balance = Balance>,
+    I: 'static,
+>(
+    caller: T::AccountId,
+) {
+    let account_id = T::Lookup::unlookup(caller.clone());
+
+    pallet_assets::Pallet::<T>::force_create(
+        SystemOrigin::Root.into(),
+        tokens::SDOT.into(),
+        account_id.clone(),
+        true,
+        One::one(),
+    )
+    .ok();
+
+    pallet_assets::Pallet::<T>::force_create(
+        SystemOrigin::Root.into(),
+        tokens::DOT.into(),
+        account_id.clone(),
+        true,
+        One::one(),
+    )
+    .ok();
+
+    pallet_assets::Pallet::<T>::force_create(
+        SystemOrigin::Root.into(),
+        ASSET_ID.into(),
+        account_id,
+        true,
+        One::one(),
+    )
+    .ok();
+
+    T::Assets::mint_into(BASE_ASSET, &caller, INITIAL_AMOUNT).ok();
+    T::Assets::mint_into(QUOTE_ASSET, &caller, INITIAL_AMOUNT).ok();
+}
+
+benchmarks_instance_pallet! {
+    where_clause {
+        where T: pallet_assets::Config<AssetId = CurrencyId, Balance = Balance>
+    }
+
+    swap {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T, I>(caller.clone());
+        assert_ok!(StableSwap::<T, I>::create_pool(T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard"),
+            (BASE_ASSET, QUOTE_ASSET), (LIQUIDITY_AMOUNT, LIQUIDITY_AMOUNT),
+            caller.clone(), ASSET_ID));
+        let amount_out = StableSwap::<T, I>::get_amounts_out(SWAP_AMOUNT, vec![BASE_ASSET, QUOTE_ASSET]).unwrap()[1];
+        let fee = T::LpFee::get()
+            .saturating_add(T::ProtocolFee::get())
+            .mul_floor(SWAP_AMOUNT);
+    }: _(
+        SystemOrigin::Signed(caller.clone()),
+        (BASE_ASSET, QUOTE_ASSET),
+        0,
+        1,
+        SWAP_AMOUNT,
+        amount_out
+    )
+    verify {
+        assert_last_event::<T, I>(Event::<T, I>::TokenSwapped(
+            (BASE_ASSET, QUOTE_ASSET),
+            caller,
+            0,
+            1,
+            SWAP_AMOUNT,
+            amount_out,
+            fee,
+        ).into());
+    }
+}
+
+impl_benchmark_test_suite!(StableSwap, crate::mock::new_test_ext(), crate::mock::Test,);
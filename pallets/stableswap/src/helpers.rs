@@ -2,6 +2,13 @@ use num_traits::{CheckedDiv, ToPrimitive};
 use pallet_traits::ConvertToBigUint;
 use sp_runtime::{biguint::BigUint, helpers_128bit::to_big_uint, ArithmeticError, DispatchError};
 
+/// Maximum number of iterations the solvers run before giving up
+pub const MAX_ITERATIONS: u32 = 255;
+
+/// Bounds of the amplification coefficient pools can be created with
+pub const MIN_A: u8 = 1;
+pub const MAX_A: u8 = 200;
+
 fn div(a: &mut BigUint, b: &mut BigUint) -> Result<BigUint, DispatchError> {
     let _nu = u128::try_from(a.clone()).unwrap_or(0);
     let _de = u128::try_from(b.clone()).unwrap_or(0);
@@ -30,11 +37,13 @@ fn div(a: &mut BigUint, b: &mut BigUint) -> Result<BigUint, DispatchError> {
 /// D[j + 1] = (A * n^n * sum(x_i) - D[j]^(n+1) / (n^n * prod(x_i))) / (A * n^n - 1)
 /// ```
 /// For two assets, n = 2 used while computation
+///
+/// Returns `None` if `d` doesn't converge within [`MAX_ITERATIONS`]
 pub fn compute_d(
     base_asset_aum: u128,
     quote_asset_aum: u128,
     amp_coeff: u128,
-) -> Result<u128, DispatchError> {
+) -> Result<Option<u128>, DispatchError> {
     let base_asset_amount = to_big_uint(base_asset_aum);
     let quote_asset_amount = to_big_uint(quote_asset_aum);
     let amplification_coefficient = to_big_uint(amp_coeff);
@@ -45,14 +54,14 @@ pub fn compute_d(
 
     let sum = base_asset_amount.clone().add(&quote_asset_amount);
     if sum == zero {
-        return Ok(0_u128);
+        return Ok(Some(0_u128));
     }
     let ann = amplification_coefficient.mul(&n).mul(&n);
     let mut d = sum.clone();
 
     let mut base_n = base_asset_amount.mul(&n);
     let mut quote_n = quote_asset_amount.mul(&n);
-    for _ in 0..255 {
+    for _ in 0..MAX_ITERATIONS {
         let mut d_p = d.clone();
         let ann_d = ann.clone().mul(&d);
         // d_p = d_p * d / (x * n)
@@ -74,14 +83,14 @@ pub fn compute_d(
         if d.clone() > d_prev {
             if d.clone() - d_prev <= one {
                 d.lstrip();
-                return Ok(d.try_into().map_err(|_| ArithmeticError::Overflow)?);
+                return Ok(Some(d.try_into().map_err(|_| ArithmeticError::Overflow)?));
             }
         } else if d_prev - d.clone() <= one {
             d.lstrip();
-            return Ok(d.try_into().map_err(|_| ArithmeticError::Overflow)?);
+            return Ok(Some(d.try_into().map_err(|_| ArithmeticError::Overflow)?));
         }
     }
-    Err(DispatchError::Other("could not compute d"))
+    Ok(None)
 }
 
 /// # Notes
@@ -95,7 +104,13 @@ pub fn compute_d(
 /// x_1 = (x_1^2 + c) / (2 * x_1 + b)
 /// ```
 /// For two assets, n = 2 used while computation
-pub fn compute_base(new_quote: u128, amp_coeff: u128, d: u128) -> Result<u128, DispatchError> {
+///
+/// Returns `None` if `y` doesn't converge within [`MAX_ITERATIONS`]
+pub fn compute_base(
+    new_quote: u128,
+    amp_coeff: u128,
+    d: u128,
+) -> Result<Option<u128>, DispatchError> {
     let mut n = to_big_uint(2_u128);
     let two = to_big_uint(2_u128);
     let one = to_big_uint(1_u128);
@@ -117,7 +132,7 @@ pub fn compute_base(new_quote: u128, amp_coeff: u128, d: u128) -> Result<u128, D
     let mut y = d.clone();
 
     // y = (y^2 * ann + term1) / (ann * term2 + d)
-    for _ in 0..255 {
+    for _ in 0..MAX_ITERATIONS {
         let y_prev = y.clone();
         let term2 = two
             .clone()
@@ -132,12 +147,12 @@ pub fn compute_base(new_quote: u128, amp_coeff: u128, d: u128) -> Result<u128, D
         if y.clone() > y_prev {
             if y.clone() - y_prev <= one {
                 y.lstrip();
-                return Ok(y.try_into().map_err(|_| ArithmeticError::Overflow)?);
+                return Ok(Some(y.try_into().map_err(|_| ArithmeticError::Overflow)?));
             }
         } else if y_prev - y.clone() <= one {
             y.lstrip();
-            return Ok(y.try_into().map_err(|_| ArithmeticError::Overflow)?);
+            return Ok(Some(y.try_into().map_err(|_| ArithmeticError::Overflow)?));
         }
     }
-    Err(DispatchError::Other("Error computing d"))
+    Ok(None)
 }
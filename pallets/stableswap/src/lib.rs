@@ -21,7 +21,9 @@ pub use pallet::*;
 use types::Pool;
 extern crate alloc;
 
+mod benchmarking;
 mod helpers;
+pub mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -41,14 +43,14 @@ use frame_support::{
 };
 
 use pallet_traits::ConvertToBigUint;
-use primitives::{Balance, CurrencyId, Ratio};
+use primitives::{Balance, CurrencyId, PoolInfo, Ratio};
 use sp_runtime::{
     traits::{AccountIdConversion, CheckedAdd, CheckedSub, One, Saturating, Zero},
     ArithmeticError, DispatchError, FixedPointNumber, FixedU128, SaturatedConversion,
 };
 use sp_std::{cmp::min, ops::Div, result::Result, vec::Vec};
 
-use crate::helpers::{compute_base, compute_d};
+use crate::helpers::{compute_base, compute_d, MAX_A, MAX_ITERATIONS, MIN_A};
 use num_traits::{CheckedDiv, CheckedMul, ToPrimitive};
 
 pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
@@ -67,6 +69,18 @@ pub mod pallet {
 
     pub type Amounts<T, I> = sp_std::vec::Vec<BalanceOf<T, I>>;
 
+    pub type Route<T, I> = BoundedVec<
+        (
+            // Pool
+            (AssetIdOf<T, I>, AssetIdOf<T, I>),
+            // Index of the asset in within the pool pair
+            u8,
+            // Index of the asset out within the pool pair
+            u8,
+        ),
+        <T as Config<I>>::MaxLengthRoute,
+    >;
+
     #[pallet::config]
     pub trait Config<I: 'static = ()>: frame_system::Config {
         type RuntimeEvent: From<Event<Self, I>>
@@ -87,6 +101,7 @@ pub mod pallet {
         #[pallet::constant]
         type LpFee: Get<Ratio>;
 
+        /// Keyless account holding the `MinimumLiquidity` locked by each new pool
         #[pallet::constant]
         type LockAccountId: Get<Self::AccountId>;
 
@@ -94,9 +109,12 @@ pub mod pallet {
         #[pallet::constant]
         type ProtocolFee: Get<Ratio>;
 
+        /// LP tokens permanently locked on pool creation, so the LP supply
+        /// can never return to zero
         #[pallet::constant]
         type MinimumLiquidity: Get<BalanceOf<Self, I>>;
 
+        /// Number of assets in a pool, pools are pairs so it must be 2
         #[pallet::constant]
         type NumTokens: Get<u8>;
 
@@ -110,6 +128,10 @@ pub mod pallet {
 
         /// Specify which origin is allowed to create new pools.
         type CreatePoolOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// How many hops a multi-hop swap supports at most
+        #[pallet::constant]
+        type MaxLengthRoute: Get<u32>;
     }
 
     #[pallet::error]
@@ -136,6 +158,16 @@ pub mod pallet {
         IdenticalAssets,
         /// Not an ideal price ratio
         NotAnIdealPrice,
+        /// Invariant solver did not converge within the iteration cap
+        DidNotConverge,
+        /// Final output of a multi-hop swap is below the minimum amount out
+        InsufficientOutputAmount,
+        /// Route is empty or consecutive hops' assets don't match
+        InvalidRoute,
+        /// Pool is paused
+        PoolPaused,
+        /// Lp token is one of the pool assets
+        InvalidPoolParameters,
     }
 
     #[pallet::event]
@@ -185,9 +217,26 @@ pub mod pallet {
             BalanceOf<T, I>,
             BalanceOf<T, I>,
         ),
+        /// Pool was paused or unpaused
+        /// [base_currency_id, quote_currency_id, paused]
+        PoolPausedUpdated(AssetIdOf<T, I>, AssetIdOf<T, I>, bool),
+        /// Tokens were swapped within a single pool
+        /// [pool, trader, in_index, out_index, amount_in, amount_out, fee]
+        TokenSwapped(
+            (AssetIdOf<T, I>, AssetIdOf<T, I>),
+            T::AccountId,
+            u8,
+            u8,
+            BalanceOf<T, I>,
+            BalanceOf<T, I>,
+            BalanceOf<T, I>,
+        ),
     }
 
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T, I = ()>(_);
 
     /// A bag of liquidity composed by two different assets
@@ -203,6 +252,22 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    #[pallet::hooks]
+    impl<T: Config<I>, I: 'static> Hooks<T::BlockNumber> for Pallet<T, I> {
+        fn integrity_test() {
+            // the invariant is computed for `NumTokens` assets
+            assert_eq!(
+                T::NumTokens::get(),
+                2,
+                "pools are pairs, NumTokens must be 2"
+            );
+            assert!(
+                (MIN_A..=MAX_A).contains(&T::AmplificationCoefficient::get()),
+                "AmplificationCoefficient is out of bounds"
+            );
+        }
+    }
+
     // No Extrinsic Calls
     #[pallet::call]
     impl<T: Config<I>, I: 'static> Pallet<T, I> {
@@ -240,6 +305,9 @@ pub mod pallet {
                 quote_asset,
                 |pool| -> DispatchResultWithPostInfo {
                     let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+                    ensure!(!pool.paused, Error::<T, I>::PoolPaused);
+
+                    Self::do_update_oracle(pool)?;
 
                     let (ideal_base_amount, ideal_quote_amount) =
                         Self::get_ideal_amounts(pool, (base_amount, quote_amount))?;
@@ -311,6 +379,7 @@ pub mod pallet {
             Pools::<T, I>::try_mutate(base_asset, quote_asset, |pool| -> DispatchResult {
                 let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
 
+                Self::do_update_oracle(pool)?;
                 Self::do_mint_protocol_fee(pool)?;
 
                 let (base_amount_removed, quote_amount_removed) =
@@ -353,6 +422,7 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             T::CreatePoolOrigin::ensure_origin(origin)?;
 
+            Self::ensure_valid_pool_parameters(pair, lp_token_id)?;
             let (is_inverted, base_asset, quote_asset) = Self::sort_assets(pair)?;
             ensure!(
                 !Pools::<T, I>::contains_key(base_asset, quote_asset),
@@ -415,6 +485,181 @@ pub mod pallet {
 
             Ok(().into())
         }
+
+        /// Allow users to withdraw a strictly proportional share of a given pool.
+        ///
+        /// No invariant math is involved and no protocol fee is minted, so this
+        /// always works even if the solver can't converge for the pool reserves.
+        ///
+        /// - `pair`: Currency pool, in which liquidity will be removed
+        /// - `liquidity`: liquidity to be removed from user's liquidity
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::remove_liquidity_emergency())]
+        #[transactional]
+        pub fn remove_liquidity_emergency(
+            origin: OriginFor<T>,
+            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+            #[pallet::compact] liquidity: BalanceOf<T, I>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let (_, base_asset, quote_asset) = Self::sort_assets(pair)?;
+
+            Pools::<T, I>::try_mutate(base_asset, quote_asset, |pool| -> DispatchResult {
+                let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+
+                // oracle update is best-effort here, it must not block withdrawals
+                let mut updated_pool = *pool;
+                match Self::do_update_oracle(&mut updated_pool) {
+                    Ok(()) => *pool = updated_pool,
+                    Err(e) => log::error!(
+                        target: "stableswap::remove_liquidity_emergency",
+                        "oracle update error caught: {:?}",
+                        &e
+                    ),
+                }
+
+                let (base_amount_removed, quote_amount_removed) =
+                    Self::do_remove_liquidity(&who, pool, liquidity, (base_asset, quote_asset))?;
+
+                log::trace!(
+                    target: "stableswap::remove_liquidity_emergency",
+                    "who: {:?}, base_asset: {:?}, quote_asset: {:?}, liquidity: {:?}",
+                    &who,
+                    &base_asset,
+                    &quote_asset,
+                    &liquidity
+                );
+
+                Self::deposit_event(Event::<T, I>::LiquidityRemoved(
+                    who,
+                    base_asset,
+                    quote_asset,
+                    liquidity,
+                    base_amount_removed,
+                    quote_amount_removed,
+                    pool.lp_token_id,
+                    pool.base_amount,
+                    pool.quote_amount,
+                ));
+
+                Ok(())
+            })
+        }
+
+        /// Swap an exact amount in through a route of pools atomically
+        ///
+        /// - `route`: hops to trade through, each output feeds the next hop
+        /// - `amount_in`: amount of the first hop's asset in to be traded
+        /// - `min_amount_out`: minimum amount of the last hop's asset out to be received
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::swap().saturating_mul(route.len() as u64))]
+        #[transactional]
+        pub fn swap_exact_tokens_for_tokens(
+            origin: OriginFor<T>,
+            route: Route<T, I>,
+            #[pallet::compact] amount_in: BalanceOf<T, I>,
+            #[pallet::compact] min_amount_out: BalanceOf<T, I>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let hops = Self::route_checks(&route)?;
+
+            let mut amount_out = amount_in;
+            for pair in hops {
+                amount_out = Self::do_swap(&who, pair, amount_out)?.0;
+            }
+
+            ensure!(
+                amount_out >= min_amount_out,
+                Error::<T, I>::InsufficientOutputAmount
+            );
+
+            log::trace!(
+                target: "stableswap::swap_exact_tokens_for_tokens",
+                "who: {:?}, route: {:?}, amount_in: {:?}, amount_out: {:?}",
+                &who,
+                &route,
+                &amount_in,
+                &amount_out
+            );
+
+            Ok(())
+        }
+
+        /// Pause or unpause swaps and deposits of a given pool
+        ///
+        /// `remove_liquidity_emergency` stays available while the pool is paused.
+        ///
+        /// - `pair`: Currency pool to be paused or unpaused
+        /// - `paused`: whether the pool is paused
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::set_pool_paused())]
+        #[transactional]
+        pub fn set_pool_paused(
+            origin: OriginFor<T>,
+            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+            paused: bool,
+        ) -> DispatchResult {
+            T::CreatePoolOrigin::ensure_origin(origin)?;
+
+            let (_, base_asset, quote_asset) = Self::sort_assets(pair)?;
+
+            Pools::<T, I>::try_mutate(base_asset, quote_asset, |pool| -> DispatchResult {
+                let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+                pool.paused = paused;
+                Ok(())
+            })?;
+
+            log::trace!(
+                target: "stableswap::set_pool_paused",
+                "base_asset: {:?}, quote_asset: {:?}, paused: {:?}",
+                &base_asset,
+                &quote_asset,
+                &paused
+            );
+
+            Self::deposit_event(Event::<T, I>::PoolPausedUpdated(
+                base_asset,
+                quote_asset,
+                paused,
+            ));
+
+            Ok(())
+        }
+
+        /// Swap an exact amount in within a single pool
+        ///
+        /// - `pool`: Currency pool to trade through
+        /// - `in_index`: index of the asset in within the pool pair
+        /// - `out_index`: index of the asset out within the pool pair
+        /// - `amount_in`: amount of the asset in to be traded
+        /// - `min_amount_out`: minimum amount of the asset out to be received
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::swap())]
+        #[transactional]
+        pub fn swap(
+            origin: OriginFor<T>,
+            pool: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+            in_index: u8,
+            out_index: u8,
+            #[pallet::compact] amount_in: BalanceOf<T, I>,
+            #[pallet::compact] min_amount_out: BalanceOf<T, I>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let hops = Self::route_checks(&[(pool, in_index, out_index)])?;
+            let (amount_out, fee) = Self::do_swap(&who, hops[0], amount_in)?;
+            ensure!(
+                amount_out >= min_amount_out,
+                Error::<T, I>::InsufficientOutputAmount
+            );
+
+            Self::deposit_event(Event::<T, I>::TokenSwapped(
+                pool, who, in_index, out_index, amount_in, amount_out, fee,
+            ));
+            Ok(())
+        }
     }
 }
 
@@ -444,6 +689,17 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         pool_base_aum: BalanceOf<T, I>,
         pool_quote_aum: BalanceOf<T, I>,
     ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let (amount_out, _) =
+            Self::get_amount_out_and_fees(amount_in, pool_base_aum, pool_quote_aum)?;
+        Ok(amount_out)
+    }
+
+    // same as `get_amount_out`, also returns the fees taken from `amount_in`
+    fn get_amount_out_and_fees(
+        amount_in: BalanceOf<T, I>,
+        pool_base_aum: BalanceOf<T, I>,
+        pool_quote_aum: BalanceOf<T, I>,
+    ) -> Result<(BalanceOf<T, I>, BalanceOf<T, I>), DispatchError> {
         let fees = T::LpFee::get()
             .checked_add(&T::ProtocolFee::get())
             .map(|r| r.mul_floor(amount_in))
@@ -473,7 +729,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             .checked_sub(new_base_amount)
             .ok_or(ArithmeticError::Underflow)?;
 
-        Ok(amount_out)
+        Ok((amount_out, fees))
     }
 
     // given an input amount of an asset and pair reserves, returns the maximum output amount of the other asset
@@ -545,6 +801,45 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             .ok_or(ArithmeticError::Underflow)?)
     }
 
+    // Resolve a route into the (asset_in, asset_out) pair of every hop, making sure
+    // each hop starts with the asset the previous hop ended with
+    fn route_checks(
+        route: &[((AssetIdOf<T, I>, AssetIdOf<T, I>), u8, u8)],
+    ) -> Result<Vec<(AssetIdOf<T, I>, AssetIdOf<T, I>)>, DispatchError> {
+        ensure!(!route.is_empty(), Error::<T, I>::InvalidRoute);
+
+        let mut hops: Vec<(AssetIdOf<T, I>, AssetIdOf<T, I>)> = Vec::with_capacity(route.len());
+        for &(pair, in_index, out_index) in route {
+            let asset_of = |index: u8| match index {
+                0 => Ok(pair.0),
+                1 => Ok(pair.1),
+                _ => Err(Error::<T, I>::InvalidRoute),
+            };
+            let (asset_in, asset_out) = (asset_of(in_index)?, asset_of(out_index)?);
+            ensure!(asset_in != asset_out, Error::<T, I>::InvalidRoute);
+
+            if let Some(&(_, prev_asset_out)) = hops.last() {
+                ensure!(prev_asset_out == asset_in, Error::<T, I>::InvalidRoute);
+            }
+
+            hops.push((asset_in, asset_out));
+        }
+
+        Ok(hops)
+    }
+
+    fn ensure_valid_pool_parameters(
+        (asset_a, asset_b): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        lp_token_id: AssetIdOf<T, I>,
+    ) -> DispatchResult {
+        ensure!(asset_a != asset_b, Error::<T, I>::IdenticalAssets);
+        ensure!(
+            lp_token_id != asset_a && lp_token_id != asset_b,
+            Error::<T, I>::InvalidPoolParameters
+        );
+        Ok(())
+    }
+
     fn sort_assets(
         (curr_a, curr_b): (AssetIdOf<T, I>, AssetIdOf<T, I>),
     ) -> Result<(bool, AssetIdOf<T, I>, AssetIdOf<T, I>), DispatchError> {
@@ -572,21 +867,32 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         pool: &mut Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
         (ideal_base_amount, ideal_quote_amount): (BalanceOf<T, I>, BalanceOf<T, I>),
     ) -> Result<BalanceOf<T, I>, DispatchError> {
-        // Extract to different functionality
-        let liquidity = if total_supply.is_zero() {
+        if total_supply.is_zero() {
             T::Assets::mint_into(
                 pool.lp_token_id,
                 &Self::lock_account_id(),
                 T::MinimumLiquidity::get(),
             )?;
+        }
 
+        Self::calc_liquidity(total_supply, pool, (ideal_base_amount, ideal_quote_amount))
+    }
+
+    // Liquidity minted for the given ideal amounts, without touching storage
+    fn calc_liquidity(
+        total_supply: BalanceOf<T, I>,
+        pool: &Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
+        (ideal_base_amount, ideal_quote_amount): (BalanceOf<T, I>, BalanceOf<T, I>),
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let liquidity = if total_supply.is_zero() {
             ideal_base_amount
                 .get_big_uint()
                 .checked_mul(&ideal_quote_amount.get_big_uint())
                 // loss of precision due to truncated sqrt
                 .map(|r| r.sqrt())
-                .and_then(|r| r.checked_sub(&T::MinimumLiquidity::get().get_big_uint()))
                 .ok_or(Error::<T, I>::ConversionToU128Failed)?
+                .checked_sub(&T::MinimumLiquidity::get().get_big_uint())
+                .ok_or(Error::<T, I>::InsufficientLiquidity)?
                 .to_u128()
                 .ok_or(ArithmeticError::Underflow)?
         } else {
@@ -622,17 +928,15 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         let mut d1 = 0u128;
         if Pools::<T, I>::contains_key(base_asset, quote_asset) {
             // d0 = Self::do_get_delta((base_asset, quote_asset)).unwrap();
-            let (tot_base_amount, tot_quote_amount) =
-                Self::get_reserves(base_asset, quote_asset).unwrap();
-            d0 = Self::delta_util(tot_base_amount, tot_quote_amount).unwrap()
+            let (tot_base_amount, tot_quote_amount) = Self::get_reserves(base_asset, quote_asset)?;
+            d0 = Self::delta_util(tot_base_amount, tot_quote_amount)?;
         }
 
         let total_supply = T::Assets::total_issuance(pool.lp_token_id);
 
         // Extract to different functionality
         let mut liquidity =
-            Self::do_get_liquidity(total_supply, pool, (ideal_base_amount, ideal_quote_amount))
-                .unwrap();
+            Self::do_get_liquidity(total_supply, pool, (ideal_base_amount, ideal_quote_amount))?;
 
         // update reserves after liquidity calculation
         pool.base_amount = pool
@@ -648,7 +952,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         let new_quote_amount = pool.quote_amount;
 
         if Pools::<T, I>::contains_key(base_asset, quote_asset) {
-            d1 = Self::do_get_delta_on_the_fly((new_base_amount, new_quote_amount)).unwrap();
+            d1 = Self::do_get_delta_on_the_fly((new_base_amount, new_quote_amount))?;
 
             ensure!(d1 >= d0, Error::<T, I>::InvalidInvariant);
         }
@@ -804,16 +1108,13 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             return Ok(Zero::zero());
         }
 
-        let root_k_last = Self::delta_util(pool.base_amount_last, pool.quote_amount_last)
-            .unwrap()
-            .get_big_uint();
+        let root_k_last =
+            Self::delta_util(pool.base_amount_last, pool.quote_amount_last)?.get_big_uint();
 
         // if the early exits do not return we know that k_last is not zero
         // and that protocol fees are on
 
-        let root_k = Self::delta_util(pool.base_amount, pool.quote_amount)
-            .unwrap()
-            .get_big_uint();
+        let root_k = Self::delta_util(pool.base_amount, pool.quote_amount)?.get_big_uint();
 
         if root_k <= root_k_last {
             return Ok(Zero::zero());
@@ -865,14 +1166,15 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         who: &T::AccountId,
         (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
         amount_in: BalanceOf<T, I>,
-    ) -> Result<BalanceOf<T, I>, DispatchError> {
+    ) -> Result<(BalanceOf<T, I>, BalanceOf<T, I>), DispatchError> {
         let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
 
         Pools::<T, I>::try_mutate(
             base_asset,
             quote_asset,
-            |pool| -> Result<BalanceOf<T, I>, DispatchError> {
+            |pool| -> Result<(BalanceOf<T, I>, BalanceOf<T, I>), DispatchError> {
                 let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+                ensure!(!pool.paused, Error::<T, I>::PoolPaused);
 
                 let (supply_in, supply_out) = if is_inverted {
                     (pool.quote_amount, pool.base_amount)
@@ -886,8 +1188,8 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
                 );
                 ensure!(!supply_out.is_zero(), Error::<T, I>::InsufficientAmountOut);
 
-                //let amount_out = Self::get_amount_out(amount_in, supply_in, supply_out)?;
-                let amount_out = Self::get_amount_out(amount_in, supply_in, supply_out)?;
+                let (amount_out, fees) =
+                    Self::get_amount_out_and_fees(amount_in, supply_in, supply_out)?;
 
                 let (new_supply_in, new_supply_out) = (
                     supply_in
@@ -932,7 +1234,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
                     pool.base_amount,
                 ));
 
-                Ok(amount_out)
+                Ok((amount_out, fees))
             },
         )
     }
@@ -945,9 +1247,9 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
     pub fn do_get_delta(
         (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
     ) -> Result<Balance, DispatchError> {
-        let (tot_base_amount, tot_quote_amount) = Self::get_reserves(asset_in, asset_out).unwrap();
+        let (tot_base_amount, tot_quote_amount) = Self::get_reserves(asset_in, asset_out)?;
 
-        let d = Self::delta_util(tot_base_amount, tot_quote_amount).unwrap();
+        let d = Self::delta_util(tot_base_amount, tot_quote_amount)?;
 
         Self::deposit_event(Event::<T, I>::DeltaCalculated(asset_in, asset_out, d));
 
@@ -971,7 +1273,8 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             tot_base_amount,
             tot_quote_amount,
             T::AmplificationCoefficient::get() as u128,
-        )?;
+        )?
+        .ok_or(Error::<T, I>::DidNotConverge)?;
 
         Ok(d)
     }
@@ -981,7 +1284,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         amp_coeff: BalanceOf<T, I>,
         d: BalanceOf<T, I>,
     ) -> Result<Balance, DispatchError> {
-        let base = compute_base(new_quote, amp_coeff, d)?;
+        let base = compute_base(new_quote, amp_coeff, d)?.ok_or(Error::<T, I>::DidNotConverge)?;
         Ok(base)
     }
 
@@ -1014,7 +1317,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
     pub fn do_get_delta_on_the_fly(
         (tot_base_amount, tot_quote_amount): (Balance, Balance),
     ) -> Result<Balance, DispatchError> {
-        let d = Self::delta_util(tot_base_amount, tot_quote_amount).unwrap();
+        let d = Self::delta_util(tot_base_amount, tot_quote_amount)?;
 
         log::trace!(
             target: "stableSwap::do_get_delta_on_the_fly",
@@ -1032,7 +1335,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         mut autonomous_var: BalanceOf<T, I>,
         (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
     ) -> Result<Balance, DispatchError> {
-        let (resx, _) = Self::get_reserves(asset_in, asset_out).unwrap();
+        let (resx, _) = Self::get_reserves(asset_in, asset_out)?;
         autonomous_var = autonomous_var
             .get_big_uint()
             .checked_add(&resx.get_big_uint())
@@ -1041,8 +1344,8 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             .ok_or(ArithmeticError::Overflow)?;
 
         // passes asset in and asset out
-        let (tot_base_amount, tot_quote_amount) = Self::get_reserves(asset_in, asset_out).unwrap();
-        let d = Self::delta_util(tot_base_amount, tot_quote_amount).unwrap();
+        let (tot_base_amount, tot_quote_amount) = Self::get_reserves(asset_in, asset_out)?;
+        let d = Self::delta_util(tot_base_amount, tot_quote_amount)?;
 
         let mut c = d;
         let mut s = 0u128;
@@ -1118,9 +1421,8 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
         let mut y_prev;
         let mut y = d;
-        // 255 is a max number of loops
-        // should throw error if does not converge
-        for _ in 0..255 {
+        let mut converged = false;
+        for _ in 0..MAX_ITERATIONS {
             y_prev = y;
 
             y = (y
@@ -1144,12 +1446,14 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             .ok_or(ArithmeticError::Underflow)?;
 
             if y.eq(&y_prev) {
+                converged = true;
                 break;
             }
         }
 
+        ensure!(converged, Error::<T, I>::DidNotConverge);
+
         Ok(y)
-        // throw new Error('Approximation did not converge')
     }
     // extract the reserves from a pool after sorting assets
     fn get_reserves(
@@ -1224,6 +1528,12 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
     ) -> Result<(), DispatchError> {
         let block_timestamp = frame_system::Pallet::<T>::block_number();
 
+        // an emptied side has no price to accumulate, only move the timestamp
+        if pool.base_amount.is_zero() || pool.quote_amount.is_zero() {
+            pool.block_timestamp_last = block_timestamp;
+            return Ok(());
+        }
+
         if pool.block_timestamp_last != block_timestamp {
             let time_elapsed: BalanceOf<T, I> = block_timestamp
                 .saturating_sub(pool.block_timestamp_last)
@@ -1266,6 +1576,119 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
         Ok(())
     }
+
+    /// Cumulative prices of a pool as of the current block, along with the block
+    /// they were accumulated up to. Sampling this at two blocks gives the time
+    /// weighted average price over that window.
+    ///
+    /// `price_0` is the price of the base asset in the quote asset and
+    /// `price_1` the price of the quote asset in the base asset, both as
+    /// `FixedU128` inner values.
+    pub fn get_price_cumulative(
+        pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+    ) -> Result<(BalanceOf<T, I>, BalanceOf<T, I>, T::BlockNumber), DispatchError> {
+        let (_, base_asset, quote_asset) = Self::sort_assets(pair)?;
+        let mut pool =
+            Self::pools(base_asset, quote_asset).ok_or(Error::<T, I>::PoolDoesNotExist)?;
+
+        Self::do_update_oracle(&mut pool)?;
+
+        Ok((
+            pool.price_0_cumulative_last,
+            pool.price_1_cumulative_last,
+            pool.block_timestamp_last,
+        ))
+    }
+
+    /// Amount of LP tokens minted for depositing `amounts` into the pool of
+    /// `pair`, or burned for withdrawing them when `is_deposit` is false.
+    /// `amounts` follow the order of `pair`.
+    ///
+    /// Deposits are trimmed to the pool ratio the same way `add_liquidity`
+    /// does, and the protocol fee minted on the next liquidity change is not
+    /// accounted for. Returns `None` if the pool doesn't exist or the amounts
+    /// can't be served.
+    pub fn calc_token_amount(
+        pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        amounts: (BalanceOf<T, I>, BalanceOf<T, I>),
+        is_deposit: bool,
+    ) -> Option<BalanceOf<T, I>> {
+        let (is_inverted, base_asset, quote_asset) = Self::sort_assets(pair).ok()?;
+        let pool = Self::pools(base_asset, quote_asset)?;
+        let (base_amount, quote_amount) = if is_inverted {
+            (amounts.1, amounts.0)
+        } else {
+            amounts
+        };
+        let total_supply = T::Assets::total_issuance(pool.lp_token_id);
+
+        if is_deposit {
+            let (ideal_base_amount, ideal_quote_amount) =
+                Self::get_ideal_amounts(&pool, (base_amount, quote_amount)).ok()?;
+            let liquidity =
+                Self::calc_liquidity(total_supply, &pool, (ideal_base_amount, ideal_quote_amount))
+                    .ok()?;
+            if total_supply.is_zero() {
+                return Some(liquidity);
+            }
+            // same invariant growth bonus as `do_add_liquidity`
+            let d0 = Self::delta_util(pool.base_amount, pool.quote_amount).ok()?;
+            let d1 = Self::do_get_delta_on_the_fly((
+                pool.base_amount.checked_add(ideal_base_amount)?,
+                pool.quote_amount.checked_add(ideal_quote_amount)?,
+            ))
+            .ok()?;
+            return liquidity.checked_add(
+                liquidity
+                    .checked_mul(d1.checked_sub(d0)?)?
+                    .checked_div(d0)?,
+            );
+        }
+
+        if base_amount > pool.base_amount || quote_amount > pool.quote_amount {
+            return None;
+        }
+
+        // round up so that burning the returned liquidity yields at least `amounts`
+        let liquidity_for = |amount: BalanceOf<T, I>, reserve: BalanceOf<T, I>| {
+            if amount.is_zero() {
+                return Some(Zero::zero());
+            }
+            amount
+                .get_big_uint()
+                .checked_mul(&total_supply.get_big_uint())
+                .map(|r| r + reserve.get_big_uint() - 1u32)
+                .and_then(|r| r.checked_div(&reserve.get_big_uint()))
+                .and_then(|r| r.to_u128())
+        };
+
+        let liquidity = liquidity_for(base_amount, pool.base_amount)?
+            .max(liquidity_for(quote_amount, pool.quote_amount)?);
+        (liquidity <= total_supply).then_some(liquidity)
+    }
+
+    /// Current reserves, LP supply, amplification and fees of the pool of
+    /// `pair`, or `None` if the pool doesn't exist.
+    pub fn pool_info(
+        pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+    ) -> Option<PoolInfo<AssetIdOf<T, I>, BalanceOf<T, I>>> {
+        let (_, base_asset, quote_asset) = Self::sort_assets(pair).ok()?;
+        let pool = Self::pools(base_asset, quote_asset)?;
+
+        Some(PoolInfo {
+            base_asset,
+            quote_asset,
+            base_amount: pool.base_amount,
+            quote_amount: pool.quote_amount,
+            lp_token_id: pool.lp_token_id,
+            lp_total_supply: T::Assets::total_issuance(pool.lp_token_id),
+            amplification: T::AmplificationCoefficient::get() as u128,
+            lp_fee: T::LpFee::get(),
+            protocol_fee: T::ProtocolFee::get(),
+            paused: pool.paused,
+        })
+    }
+
     // given a pool, calculate the ideal liquidity amounts as a function of the current
     // pool reserves ratio
     fn get_ideal_amounts(
@@ -0,0 +1,101 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod v1 {
+    use crate::{types::Pool, AssetIdOf, BalanceOf, Config, Pallet, Pools};
+    use codec::{Decode, Encode};
+    use frame_support::{
+        log,
+        traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+        weights::Weight,
+    };
+    use sp_std::marker::PhantomData;
+    #[cfg(feature = "try-runtime")]
+    use sp_std::vec::Vec;
+
+    /// Pool layout before `paused` was added
+    #[derive(Encode, Decode)]
+    pub struct OldPool<CurrencyId, Balance, BlockNumber> {
+        pub base_amount: Balance,
+        pub quote_amount: Balance,
+        pub base_amount_last: Balance,
+        pub quote_amount_last: Balance,
+        pub lp_token_id: CurrencyId,
+        pub block_timestamp_last: BlockNumber,
+        pub price_0_cumulative_last: Balance,
+        pub price_1_cumulative_last: Balance,
+    }
+
+    /// Add `paused: false` to every existing pool
+    pub struct MigrateToV1<T, I = ()>(PhantomData<(T, I)>);
+    impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for MigrateToV1<T, I> {
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+            frame_support::ensure!(
+                Pallet::<T, I>::on_chain_storage_version() == 0,
+                "must upgrade linearly"
+            );
+            let count = Pools::<T, I>::iter_keys().count() as u64;
+            log::info!(target: "stableswap::migrations", "{:?} pools need to migrate", count);
+            Ok(count.encode())
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T, I>::on_chain_storage_version() != 0 {
+                log::warn!(target: "stableswap::migrations", "already migrated to v1");
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut count = 0u64;
+            Pools::<T, I>::translate::<OldPool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>, _>(
+                |_, _, pool| {
+                    count += 1;
+                    Some(Pool {
+                        base_amount: pool.base_amount,
+                        quote_amount: pool.quote_amount,
+                        base_amount_last: pool.base_amount_last,
+                        quote_amount_last: pool.quote_amount_last,
+                        lp_token_id: pool.lp_token_id,
+                        block_timestamp_last: pool.block_timestamp_last,
+                        price_0_cumulative_last: pool.price_0_cumulative_last,
+                        price_1_cumulative_last: pool.price_1_cumulative_last,
+                        paused: false,
+                    })
+                },
+            );
+
+            StorageVersion::new(1).put::<Pallet<T, I>>();
+            log::info!(target: "stableswap::migrations", "migrated {:?} pools to v1", count);
+
+            T::DbWeight::get().reads_writes(count + 1, count + 1)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+            frame_support::ensure!(
+                Pallet::<T, I>::on_chain_storage_version() == 1,
+                "must upgrade to v1"
+            );
+            let count: u64 = Decode::decode(&mut &state[..]).map_err(|_| "invalid state")?;
+            frame_support::ensure!(
+                Pools::<T, I>::iter_values()
+                    .filter(|pool| !pool.paused)
+                    .count() as u64
+                    == count,
+                "every pool must be migrated unpaused"
+            );
+            Ok(())
+        }
+    }
+}
@@ -44,6 +44,7 @@ pub const BOB: AccountId = AccountId(2);
 pub const CHARLIE: AccountId = AccountId(3);
 pub const EVE: AccountId = AccountId(4);
 pub const FRANK: AccountId = AccountId(5);
+pub const LOCK_ACCOUNT: AccountId = AccountId(98);
 pub const PROTOCOL_FEE_RECEIVER: AccountId = AccountId(99);
 
 pub const DOT: CurrencyId = tokens::DOT;
@@ -115,9 +116,9 @@ impl pallet_balances::Config for Test {
 
 parameter_types! {
     pub const StableSwapPalletId: PalletId = PalletId(*b"par/sswp");
-    pub const NumTokens: u8 = 2;
+    pub static NumTokens: u8 = 2;
     pub const Precision: u32 = 100;
-    pub const AmplificationCoefficient: u8 = 85;
+    pub static AmplificationCoefficient: u8 = 85;
     //
     // pub DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);   // 0.05%
     // pub const MinimumLiquidity: u128 = 1_000u128;
@@ -139,6 +140,7 @@ impl pallet_stableswap::Config for Test {
     type ProtocolFee = DefaultProtocolFee;
     type MinimumLiquidity = MinimumLiquidity;
     type CreatePoolOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type MaxLengthRoute = MaxLengthRoute;
 }
 
 parameter_types! {
@@ -179,7 +181,7 @@ parameter_types! {
     pub DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);   // 0.05%
     pub const DefaultProtocolFeeReceiver: AccountId = PROTOCOL_FEE_RECEIVER;
     pub const MinimumLiquidity: u128 = 1_000u128;
-    pub const LockAccountId: AccountId = AccountId(1_u64);
+    pub const LockAccountId: AccountId = LOCK_ACCOUNT;
     pub const MaxLengthRoute: u8 = 10;
 }
 
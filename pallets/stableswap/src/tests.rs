@@ -1,8 +1,8 @@
 use super::*;
 use crate::mock::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, error::BadOrigin};
 use frame_system::RawOrigin;
-use pallet_traits::StableSwap as _;
+use pallet_traits::StableSwap;
 use primitives::tokens;
 
 const MINIMUM_LIQUIDITY: u128 = 1_000;
@@ -28,6 +28,59 @@ fn create_pool_should_work() {
     })
 }
 
+#[test]
+fn create_pool_with_invalid_parameters_should_not_work() {
+    new_test_ext().execute_with(|| {
+        let create_pool = |pair, lp_token_id| {
+            DefaultStableSwap::create_pool(
+                RawOrigin::Signed(ALICE).into(),
+                pair,
+                (1_000, 2_000),
+                BOB,
+                lp_token_id,
+            )
+        };
+
+        // identical assets
+        assert_noop!(
+            create_pool((DOT, DOT), SAMPLE_LP_TOKEN),
+            Error::<Test>::IdenticalAssets
+        );
+        // lp token is one of the pool assets
+        assert_noop!(
+            create_pool((DOT, SDOT), SDOT),
+            Error::<Test>::InvalidPoolParameters
+        );
+
+        assert_ok!(create_pool((DOT, SDOT), SAMPLE_LP_TOKEN));
+    })
+}
+
+#[test]
+fn integrity_test_should_work() {
+    new_test_ext().execute_with(|| {
+        DefaultStableSwap::integrity_test();
+    })
+}
+
+#[test]
+#[should_panic(expected = "AmplificationCoefficient is out of bounds")]
+fn integrity_test_with_invalid_amplification_should_panic() {
+    new_test_ext().execute_with(|| {
+        AmplificationCoefficient::set(201);
+        DefaultStableSwap::integrity_test();
+    })
+}
+
+#[test]
+#[should_panic(expected = "pools are pairs, NumTokens must be 2")]
+fn integrity_test_with_invalid_num_tokens_should_panic() {
+    new_test_ext().execute_with(|| {
+        NumTokens::set(3);
+        DefaultStableSwap::integrity_test();
+    })
+}
+
 #[test]
 fn double_liquidity_correct_liq_ratio_should_work() {
     new_test_ext().execute_with(|| {
@@ -456,6 +509,86 @@ fn remove_liquidity_only_portion_should_work() {
     })
 }
 
+#[test]
+fn calc_token_amount_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            DefaultStableSwap::calc_token_amount((DOT, SDOT), (1_000, 9_000), true),
+            None
+        );
+
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(), // Origin
+            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
+            (1_000, 9_000),                  // Liquidity amounts to be added in pool
+            ALICE,                           // LPToken receiver
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+        ));
+
+        // amounts follow the order of the pair
+        let expected_liquidity =
+            DefaultStableSwap::calc_token_amount((DOT, SDOT), (1_000, 9_000), true).unwrap();
+        assert_eq!(
+            DefaultStableSwap::calc_token_amount((SDOT, DOT), (9_000, 1_000), true),
+            Some(expected_liquidity)
+        );
+        assert_eq!(
+            DefaultStableSwap::calc_token_amount((DOT, SDOT), (500, 4_500), false),
+            Some(1_500)
+        );
+        // can't withdraw more than the reserves
+        assert_eq!(
+            DefaultStableSwap::calc_token_amount((DOT, SDOT), (1_001, 0), false),
+            None
+        );
+
+        let lp_before = Assets::balance(SAMPLE_LP_TOKEN, ALICE);
+        assert_ok!(DefaultStableSwap::add_liquidity(
+            RawOrigin::Signed(ALICE).into(), // Origin
+            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
+            (1_000, 9_000),                  // Liquidity amounts to be added in pool
+            (5, 5),                          // specifying its worst case ratio when pool already
+        ));
+        assert_eq!(
+            Assets::balance(SAMPLE_LP_TOKEN, ALICE) - lp_before,
+            expected_liquidity
+        );
+    })
+}
+
+#[test]
+fn pool_info_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(DefaultStableSwap::pool_info((DOT, SDOT)), None);
+
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(), // Origin
+            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
+            (1_000, 9_000),                  // Liquidity amounts to be added in pool
+            ALICE,                           // LPToken receiver
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+        ));
+
+        let pool_info = PoolInfo {
+            base_asset: SDOT,
+            quote_asset: DOT,
+            base_amount: 9_000,
+            quote_amount: 1_000,
+            lp_token_id: SAMPLE_LP_TOKEN,
+            lp_total_supply: 3_000,
+            amplification: AmplificationCoefficient::get() as u128,
+            lp_fee: DefaultLpFee::get(),
+            protocol_fee: DefaultProtocolFee::get(),
+            paused: false,
+        };
+        assert_eq!(
+            DefaultStableSwap::pool_info((DOT, SDOT)),
+            Some(pool_info.clone())
+        );
+        assert_eq!(DefaultStableSwap::pool_info((SDOT, DOT)), Some(pool_info));
+    })
+}
+
 #[test]
 fn remove_liquidity_user_more_liquidity_should_work() {
     new_test_ext().execute_with(|| {
@@ -517,6 +650,359 @@ fn remove_liquidity_with_more_liquidity_should_not_work() {
     })
 }
 
+#[test]
+fn remove_liquidity_emergency_should_work_when_invariant_does_not_converge() {
+    new_test_ext().execute_with(|| {
+        // An extremely imbalanced pool whose invariant can't be solved
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(), // Origin
+            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
+            (10, 100_000_000),               // Liquidity amounts to be added in pool
+            ALICE,                           // LPToken receiver
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+        ));
+        assert!(DefaultStableSwap::delta_util(100_000_000, 10).is_err());
+
+        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 31_622);
+        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, ALICE), 30_622);
+
+        assert_ok!(DefaultStableSwap::remove_liquidity_emergency(
+            RawOrigin::Signed(ALICE).into(), // Origin
+            (DOT, SDOT),                     // Currency pool, in which liquidity will be removed
+            30_622                           // Liquidity to be removed from user's liquidity
+        ));
+
+        // strictly proportional share: liquidity * reserve / total_supply
+        assert_eq!(Assets::balance(SDOT, ALICE), 96_837_644);
+        assert_eq!(Assets::balance(DOT, ALICE), 100_000_000 - 10 + 9);
+        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, ALICE), 0);
+        assert_eq!(
+            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
+            100_000_000 - 96_837_644
+        );
+        assert_eq!(DefaultStableSwap::pools(SDOT, DOT).unwrap().quote_amount, 1);
+    })
+}
+
+#[test]
+fn remove_liquidity_emergency_when_pool_does_not_exist_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            DefaultStableSwap::remove_liquidity_emergency(
+                RawOrigin::Signed(ALICE).into(),
+                (DOT, SDOT),
+                15
+            ),
+            Error::<Test>::PoolDoesNotExist
+        );
+    })
+}
+
+#[test]
+fn paused_pool_should_only_allow_emergency_withdrawal() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(), // Origin
+            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
+            (100_000, 100_000),              // Liquidity amounts to be added in pool
+            ALICE,                           // LPToken receiver
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+        ));
+
+        assert_noop!(
+            DefaultStableSwap::set_pool_paused(RawOrigin::Signed(BOB).into(), (DOT, SDOT), true),
+            BadOrigin
+        );
+        assert_ok!(DefaultStableSwap::set_pool_paused(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            true
+        ));
+        assert!(DefaultStableSwap::pools(SDOT, DOT).unwrap().paused);
+
+        assert_noop!(
+            DefaultStableSwap::add_liquidity(
+                RawOrigin::Signed(BOB).into(),
+                (DOT, SDOT),
+                (10_000, 10_000),
+                (0, 0),
+            ),
+            Error::<Test>::PoolPaused
+        );
+        assert_noop!(
+            <DefaultStableSwap as StableSwap<_, _, _>>::swap(&BOB, (DOT, SDOT), 1_000),
+            Error::<Test>::PoolPaused
+        );
+
+        let liquidity = Assets::balance(SAMPLE_LP_TOKEN, ALICE);
+        assert_ok!(DefaultStableSwap::remove_liquidity_emergency(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            liquidity
+        ));
+        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, ALICE), 0);
+
+        assert_ok!(DefaultStableSwap::set_pool_paused(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            false
+        ));
+        assert_ok!(DefaultStableSwap::add_liquidity(
+            RawOrigin::Signed(BOB).into(),
+            (DOT, SDOT),
+            (10_000, 10_000),
+            (0, 0),
+        ));
+    })
+}
+
+#[test]
+fn minimum_liquidity_should_stay_locked_after_full_withdrawal() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            DefaultStableSwap::create_pool(
+                RawOrigin::Signed(ALICE).into(),
+                (DOT, SDOT),
+                (10, 10),
+                ALICE,
+                SAMPLE_LP_TOKEN,
+            ),
+            Error::<Test>::InsufficientLiquidity
+        );
+
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(), // Origin
+            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
+            (100_000, 100_000),              // Liquidity amounts to be added in pool
+            ALICE,                           // LPToken receiver
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+        ));
+        assert_eq!(
+            Assets::balance(SAMPLE_LP_TOKEN, LOCK_ACCOUNT),
+            MINIMUM_LIQUIDITY
+        );
+        assert_eq!(
+            Assets::balance(SAMPLE_LP_TOKEN, ALICE),
+            100_000 - MINIMUM_LIQUIDITY
+        );
+
+        // the only depositor leaves, the locked share keeps the pool priced
+        assert_ok!(DefaultStableSwap::remove_liquidity_emergency(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            100_000 - MINIMUM_LIQUIDITY
+        ));
+        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), MINIMUM_LIQUIDITY);
+        assert_eq!(
+            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
+            1_000
+        );
+
+        // the pool still takes deposits priced against the locked share
+        let d0 = DefaultStableSwap::delta_util(1_000, 1_000).unwrap();
+        let d1 = DefaultStableSwap::delta_util(11_000, 11_000).unwrap();
+        let liquidity = 10_000 * MINIMUM_LIQUIDITY / 1_000;
+        assert_ok!(DefaultStableSwap::add_liquidity(
+            RawOrigin::Signed(BOB).into(),
+            (DOT, SDOT),
+            (10_000, 10_000),
+            (0, 0),
+        ));
+        assert_eq!(
+            Assets::balance(SAMPLE_LP_TOKEN, BOB),
+            liquidity + liquidity * (d1 - d0) / d0
+        );
+        assert_eq!(
+            Assets::total_issuance(SAMPLE_LP_TOKEN),
+            Assets::balance(SAMPLE_LP_TOKEN, BOB) + MINIMUM_LIQUIDITY
+        );
+        assert_eq!(
+            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
+            11_000
+        );
+    })
+}
+
+#[test]
+fn pathological_balance_ratio_should_not_converge() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(), // Origin
+            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
+            (10, 100_000_000),               // Liquidity amounts to be added in pool
+            ALICE,                           // LPToken receiver
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+        ));
+
+        assert_eq!(
+            DefaultStableSwap::delta_util(100_000_000, 10),
+            Err(Error::<Test>::DidNotConverge.into())
+        );
+        assert_noop!(
+            <DefaultStableSwap as StableSwap<_, _, _>>::swap(&EVE, (DOT, SDOT), 1_000),
+            Error::<Test>::DidNotConverge
+        );
+        assert_noop!(
+            DefaultStableSwap::add_liquidity(
+                RawOrigin::Signed(BOB).into(),
+                (DOT, SDOT),
+                (1, 10_000_000),
+                (0, 0),
+            ),
+            Error::<Test>::DidNotConverge
+        );
+        assert_noop!(
+            DefaultStableSwap::remove_liquidity(
+                RawOrigin::Signed(ALICE).into(),
+                (DOT, SDOT),
+                1_000
+            ),
+            Error::<Test>::DidNotConverge
+        );
+    })
+}
+
+#[test]
+fn swap_exact_tokens_for_tokens_should_work() {
+    new_test_ext().execute_with(|| {
+        let trader = EVE;
+
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(), // Origin
+            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
+            (1_000_000, 1_000_000),          // Liquidity amounts to be added in pool
+            ALICE,                           // LPToken receiver
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+        ));
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(), // Origin
+            (SDOT, KSM),                     // Currency pool, in which liquidity will be added
+            (1_000_000, 1_000_000),          // Liquidity amounts to be added in pool
+            ALICE,                           // LPToken receiver
+            SAMPLE_LP_TOKEN_2,               // Liquidity pool share representative token
+        ));
+
+        // DOT -> SDOT -> KSM
+        let route: Route<Test, ()> = vec![((DOT, SDOT), 0, 1), ((SDOT, KSM), 0, 1)]
+            .try_into()
+            .unwrap();
+
+        assert_noop!(
+            DefaultStableSwap::swap_exact_tokens_for_tokens(
+                RawOrigin::Signed(trader).into(),
+                route.clone(),
+                1_000,
+                996
+            ),
+            Error::<Test>::InsufficientOutputAmount
+        );
+
+        assert_ok!(DefaultStableSwap::swap_exact_tokens_for_tokens(
+            RawOrigin::Signed(trader).into(),
+            route,
+            1_000,
+            995
+        ));
+
+        assert_eq!(Assets::balance(DOT, trader), 1_000_000_000 - 1_000);
+        assert_eq!(Assets::balance(SDOT, trader), 1_000_000_000);
+        assert_eq!(Assets::balance(KSM, trader), 995);
+    })
+}
+
+#[test]
+fn swap_exact_tokens_for_tokens_with_invalid_route_should_not_work() {
+    new_test_ext().execute_with(|| {
+        for route in [
+            vec![],
+            // consecutive hops' assets don't match
+            vec![((DOT, SDOT), 0, 1), ((KSM, DOT), 0, 1)],
+            // asset in and asset out are the same
+            vec![((DOT, SDOT), 1, 1)],
+            // index out of the pool pair
+            vec![((DOT, SDOT), 0, 2)],
+        ] {
+            let route: Route<Test, ()> = route.try_into().unwrap();
+            assert_noop!(
+                DefaultStableSwap::swap_exact_tokens_for_tokens(
+                    RawOrigin::Signed(EVE).into(),
+                    route,
+                    1_000,
+                    0
+                ),
+                Error::<Test>::InvalidRoute
+            );
+        }
+    })
+}
+
+#[test]
+fn swap_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let trader = EVE;
+
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(), // Origin
+            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
+            (100_000_000, 100_000_000),      // Liquidity amounts to be added in pool
+            ALICE,                           // LPToken receiver
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+        ));
+
+        let amount_in = 1_000;
+        let amounts_out = DefaultStableSwap::get_amounts_out(amount_in, vec![DOT, SDOT]).unwrap();
+
+        assert_noop!(
+            DefaultStableSwap::swap(
+                RawOrigin::Signed(trader).into(),
+                (DOT, SDOT),
+                0,
+                2,
+                amount_in,
+                0
+            ),
+            Error::<Test>::InvalidRoute
+        );
+        assert_noop!(
+            DefaultStableSwap::swap(
+                RawOrigin::Signed(trader).into(),
+                (DOT, SDOT),
+                0,
+                1,
+                amount_in,
+                amounts_out[1] + 1
+            ),
+            Error::<Test>::InsufficientOutputAmount
+        );
+
+        assert_ok!(DefaultStableSwap::swap(
+            RawOrigin::Signed(trader).into(),
+            (DOT, SDOT),
+            0,
+            1,
+            amount_in,
+            amounts_out[1]
+        ));
+        assert_eq!(Assets::balance(DOT, trader), 1_000_000_000 - amount_in);
+        assert_eq!(
+            Assets::balance(SDOT, trader),
+            1_000_000_000 + amounts_out[1]
+        );
+
+        // 0.25% lp fee and 0.05% protocol fee taken from amount_in
+        System::assert_last_event(RuntimeEvent::DefaultStableSwap(crate::Event::TokenSwapped(
+            (DOT, SDOT),
+            trader,
+            0,
+            1,
+            amount_in,
+            amounts_out[1],
+            3,
+        )));
+    })
+}
+
 #[test]
 fn swap_should_work_base_to_quote() {
     new_test_ext().execute_with(|| {
@@ -554,7 +1040,7 @@ fn swap_should_work_base_to_quote() {
         assert_eq!(Assets::balance(DOT, trader), 1_000_000_000);
         assert_eq!(Assets::balance(SDOT, trader), 1_000_000_000);
 
-        assert_ok!(DefaultStableSwap::swap(
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
             &trader,
             (DOT, SDOT),
             amounts_out[0]
@@ -609,7 +1095,7 @@ fn swap_should_work_different_ratio_base_to_quote() {
         assert_eq!(Assets::balance(DOT, trader), 1_000_000_000);
         assert_eq!(Assets::balance(SDOT, trader), 1_000_000_000);
 
-        assert_ok!(DefaultStableSwap::swap(
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
             &trader,
             (DOT, SDOT),
             amounts_out[0],
@@ -664,7 +1150,7 @@ fn swap_should_work_quote_to_base() {
         assert_eq!(Assets::balance(DOT, trader), 1_000_000_000);
         assert_eq!(Assets::balance(SDOT, trader), 1_000_000_000);
 
-        assert_ok!(DefaultStableSwap::swap(
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
             &trader,
             (DOT, SDOT),
             amounts_out[0],
@@ -710,7 +1196,11 @@ fn trade_should_work_base_to_quote_flipped_currencies_on_pool_creation() {
         ); // DOT
 
         // calculate amount out
-        assert_ok!(DefaultStableSwap::swap(&trader, (DOT, SDOT), 1_000));
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
+            &trader,
+            (DOT, SDOT),
+            1_000
+        ));
 
         // old
         // assert_eq!(
@@ -771,7 +1261,11 @@ fn trade_should_work_quote_to_base() {
 
         // calculate amount out
         // trade base for quote
-        assert_ok!(DefaultStableSwap::swap(&trader, (DOT, SDOT), 1_000));
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
+            &trader,
+            (DOT, SDOT),
+            1_000
+        ));
 
         // Old
         // assert_eq!(
@@ -836,7 +1330,7 @@ fn trade_should_not_work_if_insufficient_amount_in() {
 
         // amount out is less than minimum_amount_out
         assert_noop!(
-            DefaultStableSwap::swap(&trader, (DOT, SDOT), 332),
+            <DefaultStableSwap as StableSwap<_, _, _>>::swap(&trader, (DOT, SDOT), 332),
             Error::<Test>::InsufficientAmountIn
         );
     })
@@ -867,7 +1361,11 @@ fn trade_should_work_flipped_currencies() {
         ); // SDOT
 
         // calculate amount out
-        assert_ok!(DefaultStableSwap::swap(&trader, (DOT, SDOT), 500));
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
+            &trader,
+            (DOT, SDOT),
+            500
+        ));
         // Old
         // assert_eq!(
         //     Assets::balance(SDOT, trader),
@@ -913,7 +1411,7 @@ fn trade_should_not_work_if_amount_in_is_zero() {
 
         // fail if amount_in is zero
         assert_noop!(
-            DefaultStableSwap::swap(&trader, (DOT, SDOT), 0),
+            <DefaultStableSwap as StableSwap<_, _, _>>::swap(&trader, (DOT, SDOT), 0),
             Error::<Test>::InsufficientAmountIn
         );
     })
@@ -926,7 +1424,7 @@ fn trade_should_not_work_if_pool_does_not_exist() {
 
         // try to trade in pool with no liquidity
         assert_noop!(
-            DefaultStableSwap::swap(&trader, (DOT, SDOT), 10),
+            <DefaultStableSwap as StableSwap<_, _, _>>::swap(&trader, (DOT, SDOT), 10),
             Error::<Test>::PoolDoesNotExist
         );
     })
@@ -1135,7 +1633,11 @@ fn update_oracle_should_work() {
 
         run_to_block(2);
 
-        assert_ok!(DefaultStableSwap::swap(&trader, (DOT, SDOT), 1_000));
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
+            &trader,
+            (DOT, SDOT),
+            1_000
+        ));
 
         assert_eq!(
             DefaultStableSwap::pools(SDOT, DOT)
@@ -1167,7 +1669,11 @@ fn update_oracle_should_work() {
 
         run_to_block(4);
 
-        assert_ok!(DefaultStableSwap::swap(&trader, (DOT, SDOT), 1_000));
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
+            &trader,
+            (DOT, SDOT),
+            1_000
+        ));
 
         assert_eq!(
             DefaultStableSwap::pools(SDOT, DOT)
@@ -1202,6 +1708,61 @@ fn update_oracle_should_work() {
     })
 }
 
+#[test]
+fn oracle_should_update_on_liquidity_events() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(), // Origin
+            (SDOT, DOT),                     // Currency pool, in which liquidity will be added
+            (100_000, 100_000),              // Liquidity amounts to be added in pool
+            BOB,                             // LPToken receiver
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+        ));
+
+        run_to_block(3);
+
+        assert_ok!(DefaultStableSwap::add_liquidity(
+            RawOrigin::Signed(BOB).into(),
+            (SDOT, DOT),
+            (10_000, 10_000),
+            (0, 0),
+        ));
+
+        let pool = DefaultStableSwap::pools(SDOT, DOT).unwrap();
+        assert_eq!(pool.block_timestamp_last, 3);
+        assert_eq!(pool.price_0_cumulative_last, 3_000_000_000_000_000_000);
+        assert_eq!(pool.price_1_cumulative_last, 3_000_000_000_000_000_000);
+
+        run_to_block(5);
+
+        // reading the accumulator extrapolates to the current block without writing
+        assert_eq!(
+            DefaultStableSwap::get_price_cumulative((DOT, SDOT)),
+            Ok((5_000_000_000_000_000_000, 5_000_000_000_000_000_000, 5))
+        );
+        assert_eq!(
+            DefaultStableSwap::pools(SDOT, DOT)
+                .unwrap()
+                .block_timestamp_last,
+            3
+        );
+
+        assert_ok!(DefaultStableSwap::remove_liquidity(
+            RawOrigin::Signed(BOB).into(),
+            (SDOT, DOT),
+            1_000
+        ));
+        let pool = DefaultStableSwap::pools(SDOT, DOT).unwrap();
+        assert_eq!(pool.block_timestamp_last, 5);
+        assert_eq!(pool.price_0_cumulative_last, 5_000_000_000_000_000_000);
+
+        assert_noop!(
+            DefaultStableSwap::get_price_cumulative((DOT, KSM)),
+            Error::<Test>::PoolDoesNotExist
+        );
+    })
+}
+
 // TODO: Fix this scenario
 
 #[test]
@@ -1242,7 +1803,11 @@ fn oracle_big_block_no_overflow() {
         for _ in 0..5 {
             big_block += 1000;
             run_to_block(big_block);
-            assert_ok!(DefaultStableSwap::swap(&trader, (DOT, KSM), 1000));
+            assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
+                &trader,
+                (DOT, KSM),
+                1000
+            ));
         }
 
         assert_eq!(
@@ -1270,7 +1835,11 @@ fn oracle_big_block_no_overflow() {
         run_to_block(big_block);
 
         // this would swap used to overflow
-        assert_ok!(DefaultStableSwap::swap(&trader, (DOT, KSM), 10_000_000_000));
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
+            &trader,
+            (DOT, KSM),
+            10_000_000_000
+        ));
     })
 }
 
@@ -1315,7 +1884,7 @@ fn create_pool_large_amount_should_work() {
         // should be issuance minus the min liq locked
         assert_eq!(
             Assets::balance(SAMPLE_LP_TOKEN, ALICE),
-            447_213_595_499_957_939_28
+            447_213_595_499_957_939_28 - MINIMUM_LIQUIDITY
         );
     })
 }
@@ -1456,7 +2025,11 @@ fn handling_fees_should_work() {
         ));
 
         // Another user makes a swap that should generate fees for the LP provider and the protocol
-        assert_ok!(DefaultStableSwap::swap(&FRANK, (DOT, SDOT), 6_000_000));
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
+            &FRANK,
+            (DOT, SDOT),
+            6_000_000
+        ));
         assert_eq!(Assets::balance(SDOT, FRANK), 5_981_998); // 18_002
 
         // we can check the total balance
@@ -1559,7 +2132,11 @@ fn swap_stable_tokens() {
         // println!("SDOT Balance Before\t{:?}", bal_sdot_before);
 
         // Swapping 1000 DOTs to SDOTs
-        assert_ok!(DefaultStableSwap::swap(&trader, (DOT, SDOT), amount_in));
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
+            &trader,
+            (DOT, SDOT),
+            amount_in
+        ));
 
         let bal_dot_after = Assets::balance(DOT, trader);
         let bal_sdot_after = Assets::balance(SDOT, trader);
@@ -1574,3 +2151,32 @@ fn swap_stable_tokens() {
         // println!("SDOT Diff\t{:?}", bal_sdot_after - bal_sdot_before);
     })
 }
+
+#[test]
+fn migrate_to_v1_should_unpause_existing_pools() {
+    use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+    use migrations::v1::{MigrateToV1, OldPool};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<DefaultStableSwap>();
+        let old_pool = OldPool {
+            base_amount: 2_000u128,
+            quote_amount: 1_000u128,
+            base_amount_last: 0u128,
+            quote_amount_last: 0u128,
+            lp_token_id: SAMPLE_LP_TOKEN,
+            block_timestamp_last: 1u64,
+            price_0_cumulative_last: 0u128,
+            price_1_cumulative_last: 0u128,
+        };
+        frame_support::storage::unhashed::put(&Pools::<Test>::hashed_key_for(SDOT, DOT), &old_pool);
+
+        MigrateToV1::<Test>::on_runtime_upgrade();
+
+        let pool = DefaultStableSwap::pools(SDOT, DOT).unwrap();
+        assert!(!pool.paused);
+        assert_eq!(pool.base_amount, 2_000);
+        assert_eq!(pool.quote_amount, 1_000);
+        assert_eq!(DefaultStableSwap::on_chain_storage_version(), 1);
+    })
+}
@@ -26,6 +26,7 @@ pub struct Pool<CurrencyId, Balance, BlockNumber> {
     pub block_timestamp_last: BlockNumber,
     pub price_0_cumulative_last: Balance,
     pub price_1_cumulative_last: Balance,
+    pub paused: bool,
 }
 
 impl<CurrencyId, Balance: BalanceT, BlockNumber: BalanceT> Pool<CurrencyId, Balance, BlockNumber> {
@@ -39,6 +40,7 @@ impl<CurrencyId, Balance: BalanceT, BlockNumber: BalanceT> Pool<CurrencyId, Bala
             block_timestamp_last: Zero::zero(),
             price_0_cumulative_last: Zero::zero(),
             price_1_cumulative_last: Zero::zero(),
+            paused: false,
         }
     }
 
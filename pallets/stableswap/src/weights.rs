@@ -18,7 +18,10 @@
 #![allow(unused_imports)]
 #![allow(clippy::all)]
 
-use frame_support::weights::Weight;
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
 use sp_std::marker::PhantomData;
 
 /// Weight functions needed for pallet_stableswap
@@ -28,6 +31,9 @@ pub trait WeightInfo {
     fn add_liquidity() -> Weight;
     fn remove_liquidity() -> Weight;
     fn create_pool() -> Weight;
+    fn remove_liquidity_emergency() -> Weight;
+    fn set_pool_paused() -> Weight;
+    fn swap() -> Weight;
 }
 
 /// Weights for stableswap using the Substrate node and recommended hardware.
@@ -48,6 +54,21 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn create_pool() -> Weight {
         Weight::from_ref_time(10_000 as u64)
     }
+    fn remove_liquidity_emergency() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn set_pool_paused() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    // Storage: StableSwap Pools (r:1 w:1)
+    // Storage: Assets Asset (r:2 w:2)
+    // Storage: Assets Account (r:4 w:4)
+    // Storage: System Account (r:1 w:1)
+    fn swap() -> Weight {
+        Weight::from_ref_time(139_522_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(8 as u64))
+            .saturating_add(T::DbWeight::get().writes(8 as u64))
+    }
 }
 
 // For backwards compatibility and tests
@@ -67,4 +88,19 @@ impl WeightInfo for () {
     fn create_pool() -> Weight {
         Weight::from_ref_time(10_000 as u64)
     }
+    fn remove_liquidity_emergency() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    fn set_pool_paused() -> Weight {
+        Weight::from_ref_time(10_000 as u64)
+    }
+    // Storage: StableSwap Pools (r:1 w:1)
+    // Storage: Assets Asset (r:2 w:2)
+    // Storage: Assets Account (r:4 w:4)
+    // Storage: System Account (r:1 w:1)
+    fn swap() -> Weight {
+        Weight::from_ref_time(139_522_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(8 as u64))
+            .saturating_add(RocksDbWeight::get().writes(8 as u64))
+    }
 }
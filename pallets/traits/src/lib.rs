@@ -47,6 +47,15 @@ pub trait LiquidStakingCurrenciesProvider<CurrencyId> {
     fn get_liquid_currency() -> Option<CurrencyId>;
 }
 
+/// Notified after the liquid staking exchange rate has been updated
+pub trait ExchangeRateChangeHandler<CurrencyId> {
+    fn on_exchange_rate_changed(liquid_currency: &CurrencyId, new_exchange_rate: Rate);
+}
+
+impl<CurrencyId> ExchangeRateChangeHandler<CurrencyId> for () {
+    fn on_exchange_rate_changed(_liquid_currency: &CurrencyId, _new_exchange_rate: Rate) {}
+}
+
 pub trait VaultTokenExchangeRateProvider<CurrencyId> {
     fn get_exchange_rate(asset_id: &CurrencyId, init_rate: Rate) -> Option<Rate>;
 }
@@ -1,7 +1,7 @@
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::pallet_prelude::Weight;
 use frame_system::Config;
-use primitives::{AccountId, Balance, BlockNumber, ParaId};
+use primitives::{AccountId, Balance, BlockNumber, DerivativeIndex, EraIndex, ParaId, Rate};
 use scale_info::TypeInfo;
 use sp_runtime::{traits::StaticLookup, MultiSignature, RuntimeDebug};
 use sp_std::{boxed::Box, vec::Vec};
@@ -72,6 +72,13 @@ pub struct StakingNominateCall<T: Config> {
     pub targets: Vec<<T::Lookup as StaticLookup>::Source>,
 }
 
+/// Relaychain staking.set_payee call arguments
+#[derive(Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct StakingSetPayeeCall<T: Config> {
+    /// A destination account for payment.
+    pub payee: RewardDestination<T::AccountId>,
+}
+
 /// Relaychain staking.payout_stakers call arguments
 #[derive(Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct StakingPayoutStakersCall<T: Config> {
@@ -93,6 +100,8 @@ pub enum StakingCall<T: Config> {
     WithdrawUnbonded(StakingWithdrawUnbondedCall),
     #[codec(index = 5)]
     Nominate(StakingNominateCall<T>),
+    #[codec(index = 7)]
+    SetPayee(StakingSetPayeeCall<T>),
     #[codec(index = 18)]
     PayoutStakers(StakingPayoutStakersCall<T>),
     #[codec(index = 19)]
@@ -321,6 +330,110 @@ pub enum XcmCall {
     Proxy,
     AddProxy,
     RemoveProxy,
+    SetPayee,
+}
+
+/// Pending relaychain staking request, as exposed to off-chain workers
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct XcmRequestInfo<Balance> {
+    /// The relaychain call which was sent
+    pub kind: XcmCall,
+    /// Derivative index the call was sent for
+    pub index: DerivativeIndex,
+    /// Amount carried by the call, zero if it has none
+    pub amount: Balance,
+    /// Era in which the request was sent, `None` for requests sent before it was tracked
+    pub created_era: Option<EraIndex>,
+}
+
+/// Liquid currency issuance and the staking value backing it
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct LiquidBackingInfo<Balance> {
+    /// Total issuance of the liquid currency
+    pub liquid_issuance: Balance,
+    /// Active bonded of all derivative indexes on relaychain
+    pub total_active_bonded: Balance,
+    /// Active bonded held back by `SlashingSafetyBuffer`
+    pub slashing_discount: Balance,
+    /// Stake pending in the matching pool
+    pub matching_stake: Balance,
+    /// Unstake pending in the matching pool
+    pub matching_unstake: Balance,
+    /// Reserves staked by the protocol, which back no liquid currency
+    pub protocol_owned_stake: Balance,
+    /// `(total_active_bonded - slashing_discount + matching_stake - matching_unstake - protocol_owned_stake) / liquid_issuance`,
+    /// `None` if the liquid currency has no issuance
+    pub backing_ratio: Option<Rate>,
+}
+
+/// Matching pool amounts split into free and locked portions
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct MatchingPoolState<Balance> {
+    /// Stake pending bond in next matching
+    pub stake_free: Balance,
+    /// Stake locked by bond requests in flight
+    pub stake_locked: Balance,
+    /// Unstake pending unbond in next matching
+    pub unstake_free: Balance,
+    /// Unstake locked by unbond requests in flight
+    pub unstake_locked: Balance,
+}
+
+/// Everything an account is waiting on in liquid staking
+///
+/// Chunks of loans instant unstakes are keyed under the loans account and
+/// pooled across users, they only repay the staking currency borrowed when
+/// the unstakers were paid out upfront. So nothing of them is pending for
+/// the unstakers, only a query for the loans account itself returns them.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct FullPending<Balance> {
+    /// Unlocking chunks keyed under the account, as `(era, amount)`
+    pub unlockings: Vec<(EraIndex, Balance)>,
+    /// Part of `unlockings` which can be claimed right now
+    pub claimable: Balance,
+    /// Liquid amount waiting in the account's fast unstake request
+    pub fast_unstake_amount: Balance,
+}
+
+/// Staking ledger of a derivative index, as exposed to dashboards
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct StakingLedgerInfo<AccountId, Balance> {
+    /// The derivative sovereign account bonded on relaychain
+    pub stash: AccountId,
+    /// Active plus unlocking balance
+    pub total: Balance,
+    /// Balance at stake in the forthcoming eras
+    pub active: Balance,
+    /// Balance becoming withdrawable at each era
+    pub unlocking: Vec<(EraIndex, Balance)>,
+    /// Eras whose rewards were claimed
+    pub claimed_rewards: Vec<EraIndex>,
+    /// Whether the ledger was already updated in this block
+    pub is_updated: bool,
+}
+
+/// Accounting inconsistency found by the liquid staking invariant check
+#[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum InvariantError {
+    /// Liquid currency isn't registered
+    CurrencyNotRegistered,
+    /// Backing per liquid currency deviates too much from the exchange rate
+    ExchangeRateDeviated,
+    /// Active bonded of the derivative indexes doesn't add up to the total active bonded
+    ActiveBondedMismatch,
+    /// Amounts of pending xcm requests exceed what is locked in the matching pool
+    XcmRequestLocksExceeded,
+}
+
+/// Matching progress of the current era
+#[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum MatchingStatus {
+    /// Relaychain hasn't stored the election solution yet
+    NotDue,
+    /// Matching is due but hasn't run yet
+    Due,
+    /// Matching has already run in current era
+    Completed,
 }
 
 #[macro_export]
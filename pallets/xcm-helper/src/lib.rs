@@ -231,6 +231,12 @@ pub trait XcmHelper<T: pallet_xcm::Config, Balance, TAccountId> {
         notify: impl Into<<T as pallet_xcm::Config>::RuntimeCall>,
     ) -> Result<QueryId, DispatchError>;
 
+    fn do_set_payee(
+        payee: RewardDestination<TAccountId>,
+        index: u16,
+        notify: impl Into<<T as pallet_xcm::Config>::RuntimeCall>,
+    ) -> Result<QueryId, DispatchError>;
+
     fn do_add_proxy(
         delegate: AccountId,
         proxy_type: Option<ProxyType>,
@@ -499,6 +505,9 @@ impl<T: Config> XcmHelper<T, BalanceOf<T>, AccountIdOf<T>> for Pallet<T> {
         notify: impl Into<<T as pallet_xcm::Config>::RuntimeCall>,
     ) -> Result<QueryId, DispatchError> {
         let xcm_weight_fee_misc = Self::xcm_weight_fee(XcmCall::Contribute);
+        // the proxied contribute is a transact of its own, so it's paid
+        // with the fee configured for `Proxy` on top of `Contribute`
+        let proxy_weight_fee_misc = Self::xcm_weight_fee(XcmCall::Proxy);
         let real =
             AccountId::try_from(&who.encode()[..]).map_err(|_| Error::<T>::ConvertAccountError)?;
         Ok(switch_relay!({
@@ -513,7 +522,9 @@ impl<T: Config> XcmHelper<T, BalanceOf<T>, AccountIdOf<T>> for Pallet<T> {
                 call.encode().into(),
                 xcm_weight_fee_misc.weight,
                 Self::refund_location(),
-                xcm_weight_fee_misc.fee,
+                xcm_weight_fee_misc
+                    .fee
+                    .saturating_add(proxy_weight_fee_misc.fee),
             )?;
 
             let call = RelaychainCall::<T>::Proxy(Box::new(ProxyCall::Proxy(ProxyProxyCall {
@@ -527,7 +538,7 @@ impl<T: Config> XcmHelper<T, BalanceOf<T>, AccountIdOf<T>> for Pallet<T> {
                     },
                 )),
             })));
-            Self::append_transact(&mut msg, call.encode().into(), xcm_weight_fee_misc.weight);
+            Self::append_transact(&mut msg, call.encode().into(), proxy_weight_fee_misc.weight);
 
             let query_id = Self::report_outcome_notify(
                 &mut msg,
@@ -809,4 +820,42 @@ impl<T: Config> XcmHelper<T, BalanceOf<T>, AccountIdOf<T>> for Pallet<T> {
             query_id
         }))
     }
+
+    fn do_set_payee(
+        payee: RewardDestination<AccountIdOf<T>>,
+        index: u16,
+        notify: impl Into<<T as pallet_xcm::Config>::RuntimeCall>,
+    ) -> Result<QueryId, DispatchError> {
+        let xcm_weight_fee_misc = Self::xcm_weight_fee(XcmCall::SetPayee);
+        Ok(switch_relay!({
+            let call = RelaychainCall::Utility(Box::new(UtilityCall::AsDerivative(
+                UtilityAsDerivativeCall {
+                    index,
+                    call: RelaychainCall::Staking::<T>(StakingCall::SetPayee(
+                        StakingSetPayeeCall { payee },
+                    )),
+                },
+            )));
+
+            let mut msg = Self::do_ump_transact(
+                call.encode().into(),
+                xcm_weight_fee_misc.weight,
+                Self::refund_location(),
+                xcm_weight_fee_misc.fee,
+            )?;
+
+            let query_id = Self::report_outcome_notify(
+                &mut msg,
+                MultiLocation::parent(),
+                notify,
+                T::NotifyTimeout::get(),
+            )?;
+
+            if let Err(_err) = send_xcm::<T::XcmSender>(MultiLocation::parent(), msg) {
+                return Err(Error::<T>::SendFailure.into());
+            }
+
+            query_id
+        }))
+    }
 }
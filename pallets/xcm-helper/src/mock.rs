@@ -383,6 +383,31 @@ parameter_types! {
     pub SelfParaId: ParaId = para_a_id();
 }
 
+parameter_types! {
+    pub static SentXcm: Vec<(MultiLocation, Xcm<()>)> = vec![];
+}
+
+/// Records the messages sent by the pallet instead of routing them
+pub struct TestSendXcm;
+impl SendXcm for TestSendXcm {
+    type Ticket = (MultiLocation, Xcm<()>);
+
+    fn validate(
+        dest: &mut Option<MultiLocation>,
+        message: &mut Option<Xcm<()>>,
+    ) -> SendResult<Self::Ticket> {
+        let dest = dest.take().ok_or(SendError::MissingArgument)?;
+        let message = message.take().ok_or(SendError::MissingArgument)?;
+        Ok(((dest, message), MultiAssets::new()))
+    }
+
+    fn deliver((dest, message): Self::Ticket) -> Result<XcmHash, SendError> {
+        let hash = sp_io::hashing::blake2_256(&codec::Encode::encode(&message));
+        SentXcm::mutate(|sent| sent.push((dest, message)));
+        Ok(hash)
+    }
+}
+
 parameter_types! {
     pub const XcmHelperPalletId: PalletId = PalletId(*b"par/fees");
     pub const NotifyTimeout: BlockNumber = 100;
@@ -393,7 +418,7 @@ impl crate::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type UpdateOrigin = EnsureRoot<AccountId>;
     type Assets = Assets;
-    type XcmSender = XcmRouter;
+    type XcmSender = TestSendXcm;
     type PalletId = XcmHelperPalletId;
     type RelayNetwork = RelayNetwork;
     type NotifyTimeout = NotifyTimeout;
@@ -44,3 +44,64 @@ fn update_xcm_fees_should_work() {
         );
     });
 }
+
+#[test]
+fn proxy_contribute_should_pay_for_both_transacts() {
+    new_test_ext().execute_with(|| {
+        let contribute_weight = Weight::from_ref_time(3_000_000_000);
+        let proxy_weight = Weight::from_ref_time(1_000_000_000);
+        assert_ok!(XcmHelpers::update_xcm_weight_fee(
+            frame_system::RawOrigin::Root.into(),
+            XcmCall::Contribute,
+            XcmWeightFeeMisc {
+                weight: contribute_weight,
+                fee: dot(10f64),
+            }
+        ));
+        assert_ok!(XcmHelpers::update_xcm_weight_fee(
+            frame_system::RawOrigin::Root.into(),
+            XcmCall::Proxy,
+            XcmWeightFeeMisc {
+                weight: proxy_weight,
+                fee: dot(5f64),
+            }
+        ));
+
+        assert_ok!(XcmHelpers::do_proxy_contribute(
+            ParaId::from(2000),
+            dot(1f64),
+            &ALICE,
+            RuntimeCall::System(frame_system::Call::remark { remark: vec![] }),
+        ));
+
+        // fees of `Contribute` and `Proxy` are both burned and bought as execution
+        assert_eq!(
+            Assets::balance(DOT, XcmHelpers::account_id()),
+            dot(30f64) - dot(15f64)
+        );
+        let sent = SentXcm::get();
+        assert_eq!(sent.len(), 1);
+        let (dest, Xcm(instructions)) = &sent[0];
+        assert_eq!(dest, &MultiLocation::parent());
+        let fees: MultiAsset = (MultiLocation::here(), dot(15f64)).into();
+        assert_eq!(instructions[0], WithdrawAsset(fees.clone().into()));
+        assert_eq!(
+            instructions[1],
+            BuyExecution {
+                fees,
+                weight_limit: Unlimited,
+            }
+        );
+        let transact_weights: Vec<Weight> = instructions
+            .iter()
+            .filter_map(|instruction| match instruction {
+                Transact {
+                    require_weight_at_most,
+                    ..
+                } => Some(*require_weight_at_most),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(transact_weights, vec![contribute_weight, proxy_weight]);
+    });
+}
@@ -126,6 +126,22 @@ pub enum DataProviderId {
     Aggregated = 0isize,
 }
 
+/// Current state of a stableswap pool, assets are in the pool's sorted order
+#[derive(Encode, Decode, Eq, PartialEq, Clone, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct PoolInfo<CurrencyId, Balance> {
+    pub base_asset: CurrencyId,
+    pub quote_asset: CurrencyId,
+    pub base_amount: Balance,
+    pub quote_amount: Balance,
+    pub lp_token_id: CurrencyId,
+    pub lp_total_supply: Balance,
+    pub amplification: u128,
+    pub lp_fee: Ratio,
+    pub protocol_fee: Ratio,
+    pub paused: bool,
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 
 #[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, RuntimeDebug)]
@@ -94,9 +94,14 @@ pub use pallet_prices;
 pub use pallet_router;
 pub use pallet_streaming;
 
+use pallet_crowdloans::types::VaultSummary;
 use pallet_ethereum::PostLogContent;
 use pallet_evm::{FeeCalculator, Runner};
 use pallet_traits::{
+    ump::{
+        FullPending, InvariantError, LiquidBackingInfo, MatchingPoolState, MatchingStatus,
+        StakingLedgerInfo, XcmRequestInfo,
+    },
     xcm::{
         AccountIdToMultiLocation, AsAssetType, AssetType, CurrencyIdConvert, FirstAssetTrader,
         MultiCurrencyAdapter, XcmAssetRegistry,
@@ -107,9 +112,9 @@ use primitives::{
     network::HEIKO_PREFIX,
     paras,
     tokens::{EUSDC, EUSDT, HKO, KSM, SKSM},
-    AccountId, AuraId, Balance, BlockNumber, ChainId, CurrencyId, DataProviderId, EraIndex, Hash,
-    Index, Liquidity, Moment, PersistedValidationData, Price, Rate, Ratio, Shortfall, Signature,
-    KSM_U,
+    AccountId, AuraId, Balance, BlockNumber, ChainId, CurrencyId, DataProviderId, DerivativeIndex,
+    EraIndex, Hash, Index, Liquidity, Moment, ParaId, PersistedValidationData, Price, Rate, Ratio,
+    Shortfall, Signature, KSM_U,
 };
 
 use runtime_common::{
@@ -506,6 +511,7 @@ impl pallet_assets::Config for Runtime {
 parameter_types! {
     pub const RewardAssetId: CurrencyId = HKO;
     pub const LiquidationFreeAssetId: CurrencyId = KSM;
+    pub const CollateralFactorDelay: BlockNumber = 1 * DAYS;
 }
 
 impl pallet_loans::Config for Runtime {
@@ -519,6 +525,7 @@ impl pallet_loans::Config for Runtime {
     type Assets = CurrencyAdapter;
     type RewardAssetId = RewardAssetId;
     type LiquidationFreeAssetId = LiquidationFreeAssetId;
+    type CollateralFactorDelay = CollateralFactorDelay;
 }
 
 parameter_types! {
@@ -526,6 +533,10 @@ parameter_types! {
     pub const EraLength: BlockNumber = 6 * 1 * 3600 / 6; // 6HOURS
     pub const MinStake: Balance = 100_000_000_000; // 0.1KSM
     pub const MinUnstake: Balance = 50_000_000_000; // 0.05sKSM
+    pub const DustThreshold: Balance = 1_000_000_000; // 0.001sKSM
+    pub const MinCommissionMint: Balance = 1_000_000_000; // 0.001sKSM
+    pub ZeroIssuanceExchangeRate: Rate = Rate::saturating_from_rational(1u32, 1u32);
+    pub const SlashingSafetyBuffer: Ratio = Ratio::from_percent(0);
     pub const StakingCurrency: CurrencyId = KSM;
     pub const LiquidCurrency: CurrencyId = SKSM;
     pub const CollateralCurrency: CurrencyId = KSM_U;
@@ -533,11 +544,28 @@ parameter_types! {
     // delay 7 eras, we must be able to repay in less than 7 eras
     pub LoansInstantUnstakeFee: Rate = Rate::saturating_from_rational(1u32, 100u32); // (1.5 ** (3600 * 36 / 5256000) - 1) * 100% ~= 1.004%
     pub MatchingPoolFastUnstakeFee: Rate = Rate::saturating_from_rational(1u32, 100u32);
+    pub const MinFastUnstakeMatch: Balance = 50_000_000_000; // 0.05sKSM
+    pub const FastUnstakeRequestLifetime: EraIndex = 28; // 7Days
+    pub const RestrictProofSubmitters: bool = false;
     pub const BondingDuration: EraIndex = 28; // 7Days
+    pub const UnbondingEraBuffer: EraIndex = 1;
     pub const MinNominatorBond: Balance = 100_000_000_000; // 0.1KSM
+    pub const MinXcmBondAmount: Balance = 50_000_000_000; // 0.05KSM
+    pub const MaxExchangeRateDeviation: Ratio = Ratio::from_percent(10);
+    pub const MaxNominationChurnPerEra: Ratio = Ratio::from_percent(50);
+    pub const MaxEraAdvancePerBlock: EraIndex = 3;
+    pub const MaxXcmPerBlock: u32 = 20;
+    pub const MaxUnstakersPerCall: u32 = 50;
+    pub const MaxNominations: u32 = 24;
+    pub const IncentiveBudgetPerEra: Balance = 100 * DOLLARS; // 100HKO
     pub const NumSlashingSpans: u32 = 0;
     pub DerivativeIndexList: Vec<u16> = vec![0, 1, 2, 3, 4, 5, 6, 7, 8];
     pub const ElectionSolutionStoredOffset: BlockNumber = 3150;
+    pub const MatchingInterval: BlockNumber = 3600 / 6; // 1HOUR
+    pub const IntraEraMatchingThreshold: Balance = 1_000_000_000_000_000; // 1000KSM
+    pub const HistoryDepth: EraIndex = 120; // 30Days
+    pub const SplitLargeBonds: bool = false;
+    pub BondSplitThreshold: Rate = Rate::saturating_from_rational(1u32, 2u32);
 }
 
 impl pallet_liquid_staking::Config for Runtime {
@@ -559,20 +587,43 @@ impl pallet_liquid_staking::Config for Runtime {
     type XcmFees = XcmFees;
     type LoansInstantUnstakeFee = LoansInstantUnstakeFee;
     type MatchingPoolFastUnstakeFee = MatchingPoolFastUnstakeFee;
+    type MinFastUnstakeMatch = MinFastUnstakeMatch;
+    type FastUnstakeRequestLifetime = FastUnstakeRequestLifetime;
+    type RestrictProofSubmitters = RestrictProofSubmitters;
+    type RewardDestinationAllowlist = Nothing;
     type EraLength = EraLength;
     type MinStake = MinStake;
     type MinUnstake = MinUnstake;
+    type DustThreshold = DustThreshold;
+    type SlashingSafetyBuffer = SlashingSafetyBuffer;
+    type MinCommissionMint = MinCommissionMint;
+    type ZeroIssuanceExchangeRate = ZeroIssuanceExchangeRate;
     type XCM = XcmHelper;
     type BondingDuration = BondingDuration;
+    type UnbondingEraBuffer = UnbondingEraBuffer;
     type MinNominatorBond = MinNominatorBond;
+    type MinXcmBondAmount = MinXcmBondAmount;
+    type MaxExchangeRateDeviation = MaxExchangeRateDeviation;
+    type MaxNominationChurnPerEra = MaxNominationChurnPerEra;
+    type MaxEraAdvancePerBlock = MaxEraAdvancePerBlock;
+    type MaxXcmPerBlock = MaxXcmPerBlock;
+    type MaxUnstakersPerCall = MaxUnstakersPerCall;
+    type MaxNominations = MaxNominations;
+    type IncentiveBudgetPerEra = IncentiveBudgetPerEra;
     type RelayChainValidationDataProvider = RelayChainValidationDataProvider<Runtime>;
     type Loans = Loans;
     type Members = LiquidStakingAgentsMembership;
     type NumSlashingSpans = NumSlashingSpans;
     type ElectionSolutionStoredOffset = ElectionSolutionStoredOffset;
+    type MatchingInterval = MatchingInterval;
+    type IntraEraMatchingThreshold = IntraEraMatchingThreshold;
+    type HistoryDepth = HistoryDepth;
+    type SplitLargeBonds = SplitLargeBonds;
+    type BondSplitThreshold = BondSplitThreshold;
     type ProtocolFeeReceiver = DefaultProtocolFeeReceiver;
     type Decimal = Decimal;
     type NativeCurrency = NativeCurrencyId;
+    type ExchangeRateChangeHandler = ();
 }
 
 parameter_types! {
@@ -950,10 +1001,19 @@ impl InstanceFilter<RuntimeCall> for ProxyType {
                 matches!(
                     c,
                     RuntimeCall::LiquidStaking(pallet_liquid_staking::Call::stake { .. })
+                        | RuntimeCall::LiquidStaking(
+                            pallet_liquid_staking::Call::stake_up_to_cap { .. }
+                        )
                         | RuntimeCall::LiquidStaking(pallet_liquid_staking::Call::unstake { .. })
                         | RuntimeCall::LiquidStaking(
                             pallet_liquid_staking::Call::cancel_unstake { .. }
                         )
+                        | RuntimeCall::LiquidStaking(
+                            pallet_liquid_staking::Call::set_auto_match { .. }
+                        )
+                        | RuntimeCall::LiquidStaking(
+                            pallet_liquid_staking::Call::match_unstake { .. }
+                        )
                 )
             }
             ProxyType::Crowdloans => {
@@ -1851,6 +1911,7 @@ impl pallet_crowdloans::Config for Runtime {
     type WeightInfo = weights::pallet_crowdloans::WeightInfo<Runtime>;
     type XCM = XcmHelper;
     type RelayChainBlockNumberProvider = RelayChainValidationDataProvider<Runtime>;
+    type RelayChainValidationDataProvider = RelayChainValidationDataProvider<Runtime>;
     type Members = CrowdloansAutomatorsMembership;
     type LeasePeriod = LeasePeriod;
     type LeaseOffset = LeaseOffset;
@@ -2070,7 +2131,7 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllPalletsWithSystem,
-    (),
+    (pallet_liquid_staking::migrations::v4::MigrateToV4<Runtime>,),
 >;
 
 impl fp_self_contained::SelfContainedCall for RuntimeCall {
@@ -2278,6 +2339,102 @@ impl_runtime_apis! {
         fn get_liquidation_threshold_liquidity(account: AccountId) -> Result<(Liquidity, Shortfall, Liquidity, Shortfall), DispatchError> {
             Loans::get_account_liquidation_threshold_liquidity(&account)
         }
+
+        fn get_account_health(account: AccountId) -> Result<Option<sp_runtime::FixedU128>, DispatchError> {
+            Loans::get_account_health(&account)
+        }
+
+        fn get_current_borrow_balance(account: AccountId, asset_id: CurrencyId) -> Result<Balance, DispatchError> {
+            Loans::borrow_balance_with_interest(&account, asset_id)
+        }
+
+        fn get_exchange_rate(asset_id: CurrencyId) -> Result<u128, DispatchError> {
+            Loans::exchange_rate_with_interest(asset_id).map(|rate| rate.into_inner())
+        }
+    }
+
+    impl pallet_liquid_staking_rpc_runtime_api::LiquidStakingApi<Block, Balance, AccountId> for Runtime {
+        fn compute_exchange_rate(total_active_bonded: Balance, matching_stake: Balance, matching_unstake: Balance, issuance: Balance) -> Option<Rate> {
+            LiquidStaking::compute_exchange_rate(total_active_bonded, matching_stake, matching_unstake, issuance)
+        }
+
+        fn pending_xcm_requests() -> Vec<(QueryId, XcmRequestInfo<Balance>)> {
+            LiquidStaking::pending_xcm_requests()
+        }
+
+        fn fast_unstake_capacity() -> Balance {
+            LiquidStaking::fast_unstake_capacity()
+        }
+
+        fn reward_snapshots(account: AccountId) -> Vec<(EraIndex, Rate)> {
+            LiquidStaking::account_reward_snapshots(&account)
+        }
+
+        fn liquid_backing() -> Option<LiquidBackingInfo<Balance>> {
+            LiquidStaking::liquid_backing().ok()
+        }
+
+        fn check_invariants() -> Result<(), InvariantError> {
+            LiquidStaking::check_invariants()
+        }
+
+        fn unbonding_schedule(derivative_index: DerivativeIndex) -> Vec<(EraIndex, Balance)> {
+            LiquidStaking::unbonding_schedule(derivative_index)
+        }
+
+        fn total_unbonding_schedule() -> Vec<(EraIndex, Balance)> {
+            LiquidStaking::total_unbonding_schedule()
+        }
+
+        fn matching_status() -> MatchingStatus {
+            LiquidStaking::matching_status()
+        }
+
+        fn relay_storage_keys(derivative_index: DerivativeIndex) -> (Vec<u8>, Vec<u8>) {
+            LiquidStaking::relay_storage_keys(derivative_index)
+        }
+
+        fn claimable_now(account: AccountId) -> Balance {
+            LiquidStaking::claimable_now(&account)
+        }
+
+        fn preview_stake(amount: Balance) -> Result<(Balance, Balance, Balance), DispatchError> {
+            LiquidStaking::preview_stake(amount)
+        }
+
+        fn all_staking_ledgers() -> Vec<(DerivativeIndex, StakingLedgerInfo<AccountId, Balance>)> {
+            LiquidStaking::all_staking_ledgers()
+        }
+
+        fn era_net_flows() -> Vec<(EraIndex, Balance, Balance)> {
+            LiquidStaking::era_net_flows()
+        }
+
+        fn estimated_apy(lookback_eras: EraIndex) -> Option<Rate> {
+            LiquidStaking::estimated_apy(lookback_eras)
+        }
+
+        fn ledger_cap_headroom() -> Vec<(DerivativeIndex, Balance)> {
+            LiquidStaking::ledger_cap_headroom()
+        }
+
+        fn matching_pool_state() -> MatchingPoolState<Balance> {
+            LiquidStaking::matching_pool_state()
+        }
+
+        fn derivative_accounts() -> Vec<(DerivativeIndex, AccountId)> {
+            LiquidStaking::derivative_accounts()
+        }
+
+        fn full_pending(account: AccountId) -> FullPending<Balance> {
+            LiquidStaking::full_pending(&account)
+        }
+    }
+
+    impl pallet_crowdloans_rpc_runtime_api::CrowdloansApi<Block, VaultSummary<Balance, BlockNumber>> for Runtime {
+        fn vault_summary(crowdloan: ParaId) -> Option<VaultSummary<Balance, BlockNumber>> {
+            Crowdloans::vault_summary(crowdloan)
+        }
     }
 
     impl pallet_router_rpc_runtime_api::RouterApi<Block, Balance> for Runtime {
@@ -345,4 +345,150 @@ impl<T: frame_system::Config> pallet_liquid_staking::WeightInfo for WeightInfo<T
 			.saturating_add(T::DbWeight::get().reads(2 as u64))
 			.saturating_add(T::DbWeight::get().writes(2 as u64))
 	}
+	// Storage: LiquidStaking PayeeOverrides (r:0 w:1)
+	fn set_payee_override() -> Weight {
+		Weight::from_ref_time(21_934_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: LiquidStaking PerIndexMinBond (r:0 w:1)
+	fn set_per_index_min_bond() -> Weight {
+		Weight::from_ref_time(21_934_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: LiquidStaking ReserveFactor (r:1 w:0)
+	// Storage: Assets Metadata (r:2 w:0)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:6 w:6)
+	// Storage: System Account (r:2 w:2)
+	// Storage: LiquidStaking ExchangeRate (r:1 w:0)
+	// Storage: LiquidStaking StakingLedgers (r:1 w:0)
+	// Storage: LiquidStaking StakingLedgerCap (r:1 w:0)
+	// Storage: LiquidStaking MatchingPool (r:1 w:1)
+	// Storage: LiquidStaking TotalReserves (r:1 w:1)
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:1 w:1)
+	// Storage: Loans TotalSupply (r:1 w:1)
+	// Storage: Loans AccountDeposits (r:1 w:1)
+	// Storage: Loans ExchangeRate (r:1 w:0)
+	// Storage: Loans TotalBorrows (r:1 w:0)
+	// Storage: Loans TotalReserves (r:1 w:0)
+	fn stake_and_collateralize() -> Weight {
+		Weight::from_ref_time(286_904_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(25 as u64))
+			.saturating_add(T::DbWeight::get().writes(15 as u64))
+	}
+	// Storage: LiquidStaking SourceCommissionRates (r:0 w:1)
+	fn update_source_commission_rate() -> Weight {
+		Weight::from_ref_time(21_934_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: ParachainSystem ValidationData (r:1 w:0)
+	// Storage: LiquidStaking XcmRequests (r:1 w:1)
+	// Storage: LiquidStaking XcmRequestCreated (r:1 w:1)
+	// Storage: LiquidStaking MatchingPool (r:1 w:1)
+	fn prune_stale_xcm_requests(n: u32, ) -> Weight {
+		Weight::from_ref_time(20_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(34_218_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: LiquidStaking ExchangeRate (r:1 w:1)
+	fn force_set_exchange_rate() -> Weight {
+		Weight::from_ref_time(24_519_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: LiquidStaking RewardSnapshots (r:1 w:1)
+	// Storage: LiquidStaking CurrentEra (r:1 w:0)
+	// Storage: LiquidStaking ExchangeRate (r:1 w:0)
+	fn set_reward_snapshots() -> Weight {
+		Weight::from_ref_time(31_402_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: LiquidStaking DefaultNominationTargets (r:0 w:1)
+	fn set_default_targets(n: u32, ) -> Weight {
+		Weight::from_ref_time(17_120_000 as u64)
+			.saturating_add(Weight::from_ref_time(1_182_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: LiquidStaking FastUnstakeRequests (r:1 w:1)
+	fn set_auto_match() -> Weight {
+		Weight::from_ref_time(21_934_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: LiquidStaking SlashingSpans (r:0 w:1)
+	fn set_slashing_spans() -> Weight {
+		Weight::from_ref_time(21_934_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: LiquidStaking TotalReserves (r:1 w:1)
+	// Storage: LiquidStaking StakingLedgers (r:1 w:0)
+	// Storage: LiquidStaking StakingLedgerCap (r:1 w:0)
+	// Storage: LiquidStaking MatchingPool (r:1 w:1)
+	// Storage: LiquidStaking ProtocolOwnedStake (r:1 w:1)
+	fn stake_reserves() -> Weight {
+		Weight::from_ref_time(38_512_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: LiquidStaking ReserveFactor (r:0 w:1)
+	// Storage: LiquidStaking CommissionRate (r:0 w:1)
+	// Storage: LiquidStaking StakingLedgerCap (r:0 w:1)
+	// Storage: LiquidStaking Incentive (r:0 w:1)
+	fn update_parameters() -> Weight {
+		Weight::from_ref_time(21_934_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
+	// Storage: LiquidStaking XcmRequests (r:2 w:0)
+	// Storage: LiquidStaking MatchingPool (r:1 w:1)
+	fn recompute_matching_locks() -> Weight {
+		Weight::from_ref_time(24_517_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: LiquidStaking ProtocolFeeReceiver (r:0 w:1)
+	fn set_protocol_fee_receiver() -> Weight {
+		Weight::from_ref_time(21_934_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: LiquidStaking StakingLedgers (r:1 w:1)
+	// Storage: LiquidStaking CurrentEra (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: LiquidStaking IsUpdated (r:0 w:1)
+	fn force_consolidate_ledger() -> Weight {
+		Weight::from_ref_time(42_518_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: LiquidStaking TotalReserves (r:1 w:1)
+	// Storage: Assets Metadata (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	fn add_reserves() -> Weight {
+		Weight::from_ref_time(125_618_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(6 as u64))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: LiquidStaking StakingLedgers (r:2 w:1)
+	// Storage: LiquidStaking ValidationData (r:1 w:0)
+	// Storage: LiquidStaking MatchingPool (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: LiquidStaking PendingCommission (r:1 w:0)
+	// Storage: LiquidStaking ProtocolOwnedStake (r:1 w:0)
+	// Storage: LiquidStaking ExchangeRate (r:1 w:1)
+	// Storage: LiquidStaking IsUpdated (r:0 w:1)
+	fn report_slash() -> Weight {
+		Weight::from_ref_time(71_428_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(9 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
 }
@@ -317,4 +317,35 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans PendingCollateralFactor (r:0 w:1)
+	// Storage: Loans CollateralFactorSchedule (r:1 w:1)
+	fn schedule_collateral_factor() -> Weight {
+		// Minimum execution time: 31_402 nanoseconds.
+		Weight::from_ref_time(32_118_000)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: Loans Markets (r:6 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:5 w:5)
+	// Storage: Loans TotalSupply (r:5 w:0)
+	// Storage: Assets Account (r:5 w:0)
+	// Storage: Loans TotalBorrows (r:5 w:5)
+	// Storage: Loans TotalReserves (r:5 w:5)
+	// Storage: Loans BorrowIndex (r:5 w:5)
+	// Storage: Loans UtilizationRatio (r:0 w:5)
+	// Storage: Loans BorrowRate (r:0 w:5)
+	// Storage: Loans SupplyRate (r:0 w:5)
+	// Storage: Loans ExchangeRate (r:0 w:5)
+	/// The range of component `m` is `[1, 5]`.
+	fn accrue_all_interest(m: u32, ) -> Weight {
+		// Minimum execution time: 61_893 nanoseconds.
+		Weight::from_ref_time(22_406_117)
+			// Standard Error: 38_204
+			.saturating_add(Weight::from_ref_time(41_785_302).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(m.into())))
+			.saturating_add(T::DbWeight::get().writes((8_u64).saturating_mul(m.into())))
+	}
 }